@@ -0,0 +1,130 @@
+#[path = "../framework.rs"]
+mod framework;
+
+use framework::Example;
+use pi_wgpu::{util::DeviceExt, *};
+
+// 每个 元素 乘 2，再 拷到 MAP_READ 的 buffer 里 读回来 检查
+const COUNT: u32 = 256;
+
+const SHADER: &str = r#"
+#version 450
+
+layout(local_size_x = 64) in;
+
+layout(set = 0, binding = 0) buffer Data {
+    uint values[];
+};
+
+void main() {
+    uint i = gl_GlobalInvocationID.x;
+    values[i] = values[i] * 2u;
+}
+"#;
+
+fn main() {
+    framework::start::<ComputeExample>();
+}
+
+#[test]
+fn test() {
+    framework::start::<ComputeExample>();
+}
+
+pub struct ComputeExample;
+
+impl Example for ComputeExample {
+    fn init(device: &Device, queue: &Queue, _config: &SurfaceConfiguration) -> Self {
+        let input: Vec<u8> = (0..COUNT).flat_map(|v| v.to_ne_bytes()).collect();
+        let size = input.len() as BufferAddress;
+
+        let storage = device.create_buffer_init(&util::BufferInitDescriptor {
+            label: Some("compute storage"),
+            contents: &input,
+            usage: BufferUsages::STORAGE | BufferUsages::COPY_SRC,
+        });
+        let staging = device.create_buffer(&BufferDescriptor {
+            label: Some("compute staging"),
+            size,
+            usage: BufferUsages::MAP_READ | BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let bind_group_layout = device.create_bind_group_layout(&BindGroupLayoutDescriptor {
+            label: None,
+            entries: &[BindGroupLayoutEntry {
+                binding: 0,
+                visibility: ShaderStages::COMPUTE,
+                ty: BindingType::Buffer {
+                    ty: BufferBindingType::Storage { read_only: false },
+                    has_dynamic_offset: false,
+                    min_binding_size: None,
+                },
+                count: None,
+            }],
+        });
+        let bind_group = device.create_bind_group(&BindGroupDescriptor {
+            label: None,
+            layout: &bind_group_layout,
+            entries: &[BindGroupEntry {
+                binding: 0,
+                resource: storage.as_entire_binding(),
+            }],
+        });
+        let pipeline_layout = device.create_pipeline_layout(&PipelineLayoutDescriptor {
+            label: None,
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+
+        let module = device.create_shader_module(ShaderModuleDescriptor {
+            label: Some("compute"),
+            source: ShaderSource::Glsl {
+                shader: SHADER.into(),
+                stage: naga::ShaderStage::Compute,
+                defines: Default::default(),
+            },
+        });
+        let pipeline = device.create_compute_pipeline(&ComputePipelineDescriptor {
+            label: Some("compute"),
+            layout: Some(&pipeline_layout),
+            module: &module,
+            entry_point: "main",
+        });
+
+        let mut encoder = device.create_command_encoder(&CommandEncoderDescriptor { label: None });
+        {
+            let mut cpass = encoder.begin_compute_pass(&ComputePassDescriptor::default());
+            cpass.set_pipeline(&pipeline);
+            cpass.set_bind_group(0, &bind_group, &[]);
+            cpass.dispatch_workgroups(COUNT / 64, 1, 1);
+        }
+        encoder.copy_buffer_to_buffer(&storage, 0, &staging, 0, size);
+        queue.submit(Some(encoder.finish()));
+
+        let slice = staging.slice(..);
+        slice.map_async(MapMode::Read, |r| r.unwrap());
+        {
+            let data = slice.get_mapped_range();
+            for (i, c) in data.chunks_exact(4).enumerate() {
+                let v = u32::from_ne_bytes([c[0], c[1], c[2], c[3]]);
+                assert_eq!(v, i as u32 * 2, "value {} is wrong", i);
+            }
+        }
+        staging.unmap();
+
+        log::info!("compute dispatch and readback passed");
+
+        Self
+    }
+
+    fn render<'b, 'a: 'b>(&'a mut self, _device: &'a Device, _queue: &'a Queue, _rpass: &'b mut RenderPass<'a>) {}
+
+    fn required_downlevel_flags() -> DownlevelFlags {
+        DownlevelFlags::COMPUTE_SHADERS
+    }
+
+    fn is_finished(&self) -> bool {
+        true
+    }
+}
@@ -1,7 +1,7 @@
 use std::mem::transmute;
 
 use pi_wgpu::{
-    Adapter, Color, CommandEncoderDescriptor, Device, DeviceDescriptor, Extent3d, Features, Instance, Limits, LoadOp, Operations, PowerPreference, PresentMode, Queue, RenderPass, RenderPassColorAttachment, RenderPassDepthStencilAttachment, RenderPassDescriptor, RequestAdapterOptions, Surface, SurfaceConfiguration, SurfaceTexture, Texture, TextureDescriptor, TextureDimension, TextureFormat, TextureUsages, TextureView, TextureViewDescriptor
+    Adapter, Color, CommandEncoderDescriptor, Device, DeviceDescriptor, DownlevelFlags, Extent3d, Features, Instance, Limits, LoadOp, Operations, PowerPreference, PresentMode, Queue, RenderPass, RenderPassColorAttachment, RenderPassDepthStencilAttachment, RenderPassDescriptor, RequestAdapterOptions, Surface, SurfaceConfiguration, SurfaceTexture, Texture, TextureDescriptor, TextureDimension, TextureFormat, TextureUsages, TextureView, TextureViewDescriptor
 };
 #[cfg(target_arch = "wasm32")]
 use web_sys::Element;
@@ -48,6 +48,16 @@ pub trait Example: 'static + Sized {
         // None表示使用默认值
         true
     }
+
+    // 适配器 缺少 这些 能力 时，跳过 这个 例子
+    fn required_downlevel_flags() -> DownlevelFlags {
+        DownlevelFlags::empty()
+    }
+
+    // 返回 true 时 退出，测试 在 init 里 做完 检查 后 结束
+    fn is_finished(&self) -> bool {
+        false
+    }
 }
 
 pub fn start<T: Example + Sync + Send + 'static>() {
@@ -135,6 +145,17 @@ async fn run<T: Example + Sync + Send + 'static>(event_loop: EventLoop<()>, wind
 
                 if engine.is_none() {
                     let e = pollster::block_on(Engine::new(&window,));
+
+                    let flags = e.adapter.get_downlevel_capabilities().flags;
+                    if !flags.contains(T::required_downlevel_flags()) {
+                        log::warn!(
+                            "skipped, adapter is missing downlevel flags {:?}",
+                            T::required_downlevel_flags() - flags
+                        );
+                        *control_flow = ControlFlow::Exit;
+                        return;
+                    }
+
                     engine = Some(e);
                 } else {
                     engine.as_mut().unwrap().configure(&window);
@@ -152,6 +173,11 @@ async fn run<T: Example + Sync + Send + 'static>(event_loop: EventLoop<()>, wind
                 let depth_view = &e.depth_view;
                 let example = example.as_mut().unwrap();
 
+                if example.is_finished() {
+                    *control_flow = ControlFlow::Exit;
+                    return;
+                }
+
                 let texture_surface = surface.get_current_texture().unwrap();
                
 
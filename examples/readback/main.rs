@@ -0,0 +1,292 @@
+#[path = "../framework.rs"]
+mod framework;
+
+use framework::Example;
+use pi_wgpu::*;
+
+// 各种 回读：buffer 映射、MRT 清屏、带 填充 的 write_texture、WGSL 双 入口 的 module
+// 检查 全部 在 init 里 做完，然后 退出
+const SIZE: u32 = 4;
+
+// WGSL module 里 同时 有 顶点 和 片元 入口，全屏 三角形 输出 绿色
+#[cfg(feature = "wgsl")]
+const WGSL: &str = r#"
+@vertex
+fn vs_main(@builtin(vertex_index) i: u32) -> @builtin(position) vec4<f32> {
+    let x = f32(i32(i & 1u) * 4 - 1);
+    let y = f32(i32(i >> 1u) * 4 - 1);
+    return vec4<f32>(x, y, 0.0, 1.0);
+}
+
+@fragment
+fn fs_main() -> @location(0) vec4<f32> {
+    return vec4<f32>(0.0, 1.0, 0.0, 1.0);
+}
+"#;
+
+fn main() {
+    framework::start::<ReadbackExample>();
+}
+
+#[test]
+fn test() {
+    framework::start::<ReadbackExample>();
+}
+
+pub struct ReadbackExample;
+
+impl Example for ReadbackExample {
+    fn init(device: &Device, queue: &Queue, _config: &SurfaceConfiguration) -> Self {
+        check_buffer(device, queue);
+        check_padded_write_texture(device, queue);
+        check_mrt_clear(device, queue);
+        #[cfg(feature = "wgsl")]
+        check_wgsl_entry_points(device, queue);
+
+        log::info!("readback checks passed");
+
+        Self
+    }
+
+    fn render<'b, 'a: 'b>(&'a mut self, _device: &'a Device, _queue: &'a Queue, _rpass: &'b mut RenderPass<'a>) {}
+
+    fn is_finished(&self) -> bool {
+        true
+    }
+}
+
+// mapped_at_creation 写入，write_buffer 改 一段，再 拷到 MAP_READ 的 buffer 里 读回来
+fn check_buffer(device: &Device, queue: &Queue) {
+    let size = 64;
+
+    let src = device.create_buffer(&BufferDescriptor {
+        label: Some("readback src"),
+        size,
+        usage: BufferUsages::COPY_SRC | BufferUsages::COPY_DST,
+        mapped_at_creation: true,
+    });
+    {
+        let mut data = src.slice(..).get_mapped_range_mut();
+        for (i, b) in data.iter_mut().enumerate() {
+            *b = i as u8;
+        }
+    }
+    src.unmap();
+
+    queue.write_buffer(&src, 16, &[0xAB; 8]);
+
+    let mut encoder = device.create_command_encoder(&CommandEncoderDescriptor { label: None });
+    let dst = create_staging(device, size);
+    encoder.copy_buffer_to_buffer(&src, 0, &dst, 0, size);
+    queue.submit(Some(encoder.finish()));
+
+    let data = read_buffer(&dst);
+    for (i, &b) in data.iter().enumerate() {
+        let expected = if (16..24).contains(&i) { 0xAB } else { i as u8 };
+        assert_eq!(b, expected, "buffer byte {} is wrong", i);
+    }
+}
+
+// 每行 后面 有 填充 的 数据，只有 前 SIZE 个 像素 要 上传
+fn check_padded_write_texture(device: &Device, queue: &Queue) {
+    let texture = create_texture(device, TextureUsages::COPY_DST | TextureUsages::COPY_SRC);
+
+    let bytes_per_row = SIZE * 4 + 12;
+    let mut data = vec![0xEE_u8; (bytes_per_row * SIZE) as usize];
+    for y in 0..SIZE {
+        for x in 0..SIZE {
+            let i = (y * bytes_per_row + x * 4) as usize;
+            data[i..i + 4].copy_from_slice(&pixel(x, y));
+        }
+    }
+
+    queue.write_texture(
+        ImageCopyTexture {
+            texture: &texture,
+            mip_level: 0,
+            origin: Origin3d::ZERO,
+            aspect: TextureAspect::All,
+        },
+        &data,
+        ImageDataLayout {
+            offset: 0,
+            bytes_per_row: Some(bytes_per_row),
+            rows_per_image: Some(SIZE),
+        },
+        texture_size(),
+    );
+
+    let pixels = read_texture(device, queue, &texture);
+    for y in 0..SIZE {
+        for x in 0..SIZE {
+            assert_eq!(pixels[(y * SIZE + x) as usize], pixel(x, y), "texel ({}, {}) is wrong", x, y);
+        }
+    }
+}
+
+// 两个 颜色 附件 各自 清成 不同 的 颜色
+fn check_mrt_clear(device: &Device, queue: &Queue) {
+    let usage = TextureUsages::RENDER_ATTACHMENT | TextureUsages::COPY_SRC;
+    let targets = [create_texture(device, usage), create_texture(device, usage)];
+    let views: Vec<_> = targets
+        .iter()
+        .map(|t| t.create_view(&TextureViewDescriptor::default()))
+        .collect();
+
+    let mut encoder = device.create_command_encoder(&CommandEncoderDescriptor { label: None });
+    {
+        let _rpass = encoder.begin_render_pass(&RenderPassDescriptor {
+            label: Some("mrt clear"),
+            color_attachments: &[clear_attachment(&views[0], Color::RED), clear_attachment(&views[1], Color::BLUE)],
+            depth_stencil_attachment: None,
+            timestamp_writes: None,
+            occlusion_query_set: None,
+        });
+    }
+    queue.submit(Some(encoder.finish()));
+
+    for (texture, expected) in targets.iter().zip([[255, 0, 0, 255], [0, 0, 255, 255]]) {
+        for p in read_texture(device, queue, texture) {
+            assert_eq!(p, expected, "mrt clear color is wrong");
+        }
+    }
+}
+
+// 同一个 WGSL module 的 vs_main / fs_main 分别 作为 顶点 和 片元 入口
+#[cfg(feature = "wgsl")]
+fn check_wgsl_entry_points(device: &Device, queue: &Queue) {
+    let module = device.create_shader_module(ShaderModuleDescriptor {
+        label: Some("wgsl"),
+        source: ShaderSource::Wgsl(WGSL.into()),
+    });
+    let layout = device.create_pipeline_layout(&PipelineLayoutDescriptor {
+        label: None,
+        bind_group_layouts: &[],
+        push_constant_ranges: &[],
+    });
+    let pipeline = device.create_render_pipeline(&RenderPipelineDescriptor {
+        label: Some("wgsl"),
+        layout: Some(&layout),
+        vertex: VertexState {
+            module: &module,
+            entry_point: "vs_main",
+            buffers: &[],
+        },
+        primitive: PrimitiveState::default(),
+        depth_stencil: None,
+        multisample: MultisampleState::default(),
+        fragment: Some(FragmentState {
+            module: &module,
+            entry_point: "fs_main",
+            targets: &[Some(TextureFormat::Rgba8Unorm.into())],
+        }),
+        multiview: None,
+    });
+
+    let target = create_texture(device, TextureUsages::RENDER_ATTACHMENT | TextureUsages::COPY_SRC);
+    let view = target.create_view(&TextureViewDescriptor::default());
+
+    let mut encoder = device.create_command_encoder(&CommandEncoderDescriptor { label: None });
+    {
+        let mut rpass = encoder.begin_render_pass(&RenderPassDescriptor {
+            label: Some("wgsl"),
+            color_attachments: &[clear_attachment(&view, Color::BLACK)],
+            depth_stencil_attachment: None,
+            timestamp_writes: None,
+            occlusion_query_set: None,
+        });
+        rpass.set_pipeline(&pipeline);
+        rpass.draw(0..3, 0..1);
+    }
+    queue.submit(Some(encoder.finish()));
+
+    for p in read_texture(device, queue, &target) {
+        assert_eq!(p, [0, 255, 0, 255], "wgsl output color is wrong");
+    }
+}
+
+fn clear_attachment(view: &TextureView, color: Color) -> Option<RenderPassColorAttachment<'_>> {
+    Some(RenderPassColorAttachment {
+        view,
+        resolve_target: None,
+        ops: Operations {
+            load: LoadOp::Clear(color),
+            store: StoreOp::Store,
+        },
+    })
+}
+
+fn pixel(x: u32, y: u32) -> [u8; 4] {
+    [x as u8 * 60, y as u8 * 60, 200, 255]
+}
+
+fn texture_size() -> Extent3d {
+    Extent3d {
+        width: SIZE,
+        height: SIZE,
+        depth_or_array_layers: 1,
+    }
+}
+
+fn create_texture(device: &Device, usage: TextureUsages) -> Texture {
+    device.create_texture(&TextureDescriptor {
+        label: Some("readback texture"),
+        size: texture_size(),
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: TextureDimension::D2,
+        format: TextureFormat::Rgba8Unorm,
+        usage,
+        view_formats: &[],
+    })
+}
+
+fn create_staging(device: &Device, size: BufferAddress) -> Buffer {
+    device.create_buffer(&BufferDescriptor {
+        label: Some("readback staging"),
+        size,
+        usage: BufferUsages::MAP_READ | BufferUsages::COPY_DST,
+        mapped_at_creation: false,
+    })
+}
+
+// map_async 的 回调 在 返回 前 就 调用 了，可以 直接 get_mapped_range
+fn read_buffer(buffer: &Buffer) -> Vec<u8> {
+    let slice = buffer.slice(..);
+    slice.map_async(MapMode::Read, |r| r.unwrap());
+    let data = slice.get_mapped_range().to_vec();
+    buffer.unmap();
+
+    data
+}
+
+// 按 COPY_BYTES_PER_ROW_ALIGNMENT 对齐 的 行 拷出来，再 去掉 每行 的 填充
+fn read_texture(device: &Device, queue: &Queue, texture: &Texture) -> Vec<[u8; 4]> {
+    let bytes_per_row = COPY_BYTES_PER_ROW_ALIGNMENT;
+    let staging = create_staging(device, (bytes_per_row * SIZE) as BufferAddress);
+
+    let mut encoder = device.create_command_encoder(&CommandEncoderDescriptor { label: None });
+    encoder.copy_texture_to_buffer(
+        ImageCopyTexture {
+            texture,
+            mip_level: 0,
+            origin: Origin3d::ZERO,
+            aspect: TextureAspect::All,
+        },
+        ImageCopyBuffer {
+            buffer: &staging,
+            layout: ImageDataLayout {
+                offset: 0,
+                bytes_per_row: Some(bytes_per_row),
+                rows_per_image: Some(SIZE),
+            },
+        },
+        texture_size(),
+    );
+    queue.submit(Some(encoder.finish()));
+
+    let data = read_buffer(&staging);
+    data.chunks_exact(bytes_per_row as usize)
+        .flat_map(|row| row[..(SIZE * 4) as usize].chunks_exact(4).map(|p| [p[0], p[1], p[2], p[3]]))
+        .collect()
+}
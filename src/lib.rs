@@ -30,4 +30,15 @@ impl PiWgpuAdapter for Adapter {
     ) -> impl Future<Output = Result<(Device, Queue), RequestDeviceError>> + Send {
         self.request_device(desc, _trace_path)
     }
-}
\ No newline at end of file
+}
+
+pub trait PiWgpuSurface {
+    /// 直接 渲染到 默认帧缓冲，present 时 省掉 全屏 的 Flip-Y blit 和 离屏纹理；
+    /// 下一次 configure 时 生效
+    fn set_direct_present(&self, enable: bool);
+}
+
+#[cfg(feature = "use_wgpu")]
+impl PiWgpuSurface for Surface<'_> {
+    fn set_direct_present(&self, _enable: bool) {}
+}
\ No newline at end of file
@@ -53,7 +53,7 @@ impl Adapter {
         unsafe { gl.pixel_store_i32(glow::UNPACK_ALIGNMENT, 1) };
         unsafe { gl.pixel_store_i32(glow::PACK_ALIGNMENT, 1) };
 
-        let state = GLState::new(&gl);
+        let state = GLState::new(&gl, self.context.private_caps());
 
         Ok(super::OpenDevice {
             device: super::Device {
@@ -103,7 +103,16 @@ impl Adapter {
         let renderable =
             unfilterable | Tfc::COLOR_ATTACHMENT | sample_count | Tfc::MULTISAMPLE_RESOLVE;
         let filterable_renderable = filterable | renderable | Tfc::COLOR_ATTACHMENT_BLEND;
-        let storage = base | Tfc::STORAGE | Tfc::STORAGE_READ_WRITE;
+        // image load store：GLES 3.1 / GL 4.3 才有
+        let storage = if self
+            .context
+            .private_caps()
+            .contains(super::PrivateCapabilities::SHADER_STORAGE)
+        {
+            base | Tfc::STORAGE | Tfc::STORAGE_READ_WRITE
+        } else {
+            empty
+        };
 
         let feature_fn = |f, caps| {
             if self.context.features().contains(f) {
@@ -237,38 +246,8 @@ impl Adapter {
     /// `None` means presentation is not supported for it.
     pub(crate) fn surface_capabilities(
         &self,
-        _surface: &super::Surface,
+        surface: &super::Surface,
     ) -> Option<super::SurfaceCapabilities> {
-        let mut formats = vec![
-            wgt::TextureFormat::Rgba8Unorm,
-            #[cfg(not(target_arch = "wasm32"))]
-            wgt::TextureFormat::Bgra8Unorm,
-        ];
-
-        if self
-            .context
-            .private_caps()
-            .contains(super::PrivateCapabilities::COLOR_BUFFER_HALF_FLOAT)
-        {
-            formats.push(wgt::TextureFormat::Rgba16Float)
-        }
-
-        Some(super::SurfaceCapabilities {
-            formats,
-            present_modes: vec![wgt::PresentMode::Fifo],
-            composite_alpha_modes: vec![wgt::CompositeAlphaMode::Opaque],
-            swap_chain_sizes: 2..=2,
-            current_extent: None,
-            extents: wgt::Extent3d {
-                width: 4,
-                height: 4,
-                depth_or_array_layers: 1,
-            }..=wgt::Extent3d {
-                width: self.context.max_texture_size(),
-                height: self.context.max_texture_size(),
-                depth_or_array_layers: 1,
-            },
-            usage: super::TextureUses::COLOR_TARGET,
-        })
+        self.context.surface_capabilities(surface)
     }
 }
@@ -3,7 +3,7 @@ use std::sync::atomic::AtomicU32;
 use pi_share::Share;
 use derive_more::Debug;
 
-use super::super::wgt;
+use super::{super::wgt, gl_conv as conv};
 
 #[derive(Debug)]
 pub struct BindGroupLayout {
@@ -38,7 +38,27 @@ impl BindGroup {
     ) -> Result<Self, super::super::DeviceError> {
         profiling::scope!("hal::BindGroup::new");
 
-        let layout = desc.layout.inner.entries.as_ref();
+        // layout 和 资源 是否 有效，wgc 已经 检查过
+        let layout = desc.layout.inner.as_ref().unwrap().entries.as_ref();
+
+        // 只选 部分 层 的 D2Array 视图 采样 的 是 拷贝，glBindImageTexture 只能 绑定 一层 或 全部 层
+        for (v, layout) in desc.entries.iter().zip(layout.iter()) {
+            if let (
+                super::super::BindingResource::TextureView(view),
+                super::super::BindingType::StorageTexture { .. },
+            ) = (&v.resource, &layout.ty)
+            {
+                let view = view.inner.as_ref().unwrap();
+                if view.dimension == wgt::TextureViewDimension::D2Array && view.layer_copy.is_some() {
+                    log::error!(
+                        "storage texture binding {}: D2Array view of layers {:?} is not supported",
+                        v.binding,
+                        view.array_layers
+                    );
+                    return Err(super::super::DeviceError::Invalid);
+                }
+            }
+        }
 
         let mut next_dynamic_offset = -1;
 
@@ -73,7 +93,7 @@ impl BindGroup {
 
                         RawBinding::Buffer {
                             dynamic_offset,
-                            raw: b.buffer.inner.clone(),
+                            raw: b.buffer.inner.clone().unwrap(),
                             offset: b.offset as i32,
                             size,
                         }
@@ -84,16 +104,20 @@ impl BindGroup {
                             _ => panic!("mis match Sampler type"),
                         }
 
-                        RawBinding::Sampler(s.inner.clone())
+                        RawBinding::Sampler(s.inner.clone().unwrap())
                     }
-                    super::super::BindingResource::TextureView(view) => {
-                        match &layout.ty {
-                            super::super::BindingType::Texture { .. } => {}
-                            _ => panic!("mis match Texture type"),
+                    super::super::BindingResource::TextureView(view) => match &layout.ty {
+                        super::super::BindingType::Texture { .. } => {
+                            RawBinding::Texture(view.inner.clone().unwrap())
                         }
-
-                        RawBinding::Texture(view.inner.clone())
-                    }
+                        super::super::BindingType::StorageTexture { access, .. } => {
+                            RawBinding::StorageTexture {
+                                view: view.inner.clone().unwrap(),
+                                access: conv::map_storage_access(*access),
+                            }
+                        }
+                        _ => panic!("mis match Texture type"),
+                    },
                     super::super::BindingResource::BufferArray(_) => unimplemented!(),
                     super::super::BindingResource::SamplerArray(_) => unimplemented!(),
                     super::super::BindingResource::TextureViewArray(_) => unimplemented!(),
@@ -103,7 +127,7 @@ impl BindGroup {
             })
             .collect();
 
-        let layout = desc.layout.inner.entries.clone();
+        let layout = desc.layout.inner.as_ref().unwrap().entries.clone();
         Ok(Self { contents, layout, id: GROUP_AROM.fetch_add(1, std::sync::atomic::Ordering::Relaxed), })
     }
 }
@@ -123,4 +147,8 @@ pub(crate) enum RawBinding {
     },
     Texture(super::TextureView),
     Sampler(super::Sampler),
+    StorageTexture {
+        view: super::TextureView,
+        access: u32, // glow::READ_ONLY / WRITE_ONLY / READ_WRITE
+    },
 }
@@ -1,7 +1,11 @@
+use std::ptr::NonNull;
+
 use glow::HasContext;
-use pi_share::Share;
+use pi_share::{Share, ShareCell};
 
-use super::{super::BufferUsages, AdapterContext, BindTarget, GLState};
+use super::{
+    super::BufferUsages, AdapterContext, BindTarget, BufferMapping, GLState, PrivateCapabilities,
+};
 
 #[derive(Debug, Clone)]
 pub(crate) struct Buffer(pub(crate) Share<BufferImpl>);
@@ -20,12 +24,43 @@ impl Buffer {
             (glow::ELEMENT_ARRAY_BUFFER, glow::DYNAMIC_DRAW)
         } else if desc.usage.contains(BufferUsages::UNIFORM) {
             (glow::UNIFORM_BUFFER, glow::DYNAMIC_DRAW)
+        } else if desc.usage.contains(BufferUsages::INDIRECT) {
+            if adapter
+                .private_caps()
+                .contains(PrivateCapabilities::DRAW_INDIRECT)
+            {
+                (glow::DRAW_INDIRECT_BUFFER, glow::DYNAMIC_DRAW)
+            } else {
+                (glow::COPY_WRITE_BUFFER, glow::DYNAMIC_DRAW)
+            }
+        } else if desc.usage.contains(BufferUsages::STORAGE) {
+            (glow::SHADER_STORAGE_BUFFER, glow::DYNAMIC_DRAW)
+        } else if desc.usage.contains(BufferUsages::MAP_READ) {
+            // 回读 用的 staging buffer
+            (glow::COPY_READ_BUFFER, glow::STREAM_READ)
+        } else if desc
+            .usage
+            .intersects(BufferUsages::MAP_WRITE | BufferUsages::COPY_SRC | BufferUsages::COPY_DST)
+        {
+            (glow::COPY_WRITE_BUFFER, glow::DYNAMIC_DRAW)
         } else {
             unreachable!();
         };
 
         let size = desc.size as i32;
 
+        // MAP_WRITE 的 buffer 在 CPU 端 保留 一份 拷贝，映射 写 时 不用 从 GPU 回读
+        // 没有 glDraw*Indirect（GLES 3.0 / WebGL2）时，INDIRECT 的 buffer 也 保留，间接绘制 直接 从 CPU 端 取 参数
+        let is_indirect_emulated = desc.usage.contains(BufferUsages::INDIRECT)
+            && !adapter
+                .private_caps()
+                .contains(PrivateCapabilities::DRAW_INDIRECT);
+        let shadow = if desc.usage.contains(BufferUsages::MAP_WRITE) || is_indirect_emulated {
+            Some(vec![0; size as usize])
+        } else {
+            None
+        };
+
         let lock = adapter.lock(None);
         let gl = lock.get_glow();
 
@@ -38,6 +73,10 @@ impl Buffer {
             gl_target,
             gl_usage,
             size,
+            map_state: ShareCell::new(BufferMapState {
+                shadow,
+                mapped: None,
+            }),
         };
 
         imp.state.set_buffer_size(&gl, &imp, size);
@@ -52,6 +91,117 @@ impl Buffer {
         let imp = self.0.as_ref();
         imp.state.set_buffer_sub_data(gl, imp, offset, data);
     }
+
+    // 映射 [offset, offset + size)，把 buffer 的 当前内容 拷到 CPU 端
+    // 有 CPU 端拷贝 时 直接 取，否则 从 GPU 回读
+    // 返回 false 表示 已经 被映射，或者 回读 失败
+    pub fn map(&self, gl: &glow::Context, offset: i32, size: i32, is_write: bool) -> bool {
+        profiling::scope!("hal::Buffer::map");
+
+        let imp = self.0.as_ref();
+
+        if imp.map_state.borrow().mapped.is_some() {
+            return false;
+        }
+
+        let data = match imp.read_shadow(offset, size) {
+            Some(data) => data.into_boxed_slice(),
+            None => {
+                let mut data = vec![0; size as usize].into_boxed_slice();
+                if !imp.state.get_buffer_sub_data(gl, imp, offset, &mut data) {
+                    return false;
+                }
+                data
+            }
+        };
+
+        imp.map_state.borrow_mut().mapped = Some(MappedRange {
+            offset,
+            is_write,
+            data,
+            views: 0,
+        });
+
+        true
+    }
+
+    // mapped_at_creation: 内容 全是 0，不需要 回读
+    pub fn map_at_creation(&self) {
+        let imp = self.0.as_ref();
+
+        let mut map_state = imp.map_state.borrow_mut();
+        map_state.mapped = Some(MappedRange {
+            offset: 0,
+            is_write: true,
+            data: vec![0; imp.size as usize].into_boxed_slice(),
+            views: 0,
+        });
+    }
+
+    // 取 已映射 区域 中 [offset, offset + size) 的 指针，调用者 结束使用后 要 调 release_mapped_range
+    pub fn get_mapped_range(&self, offset: i32, size: i32, is_write: bool) -> BufferMapping {
+        let imp = self.0.as_ref();
+
+        let mut map_state = imp.map_state.borrow_mut();
+        let mapped = map_state
+            .mapped
+            .as_mut()
+            .expect("hal::Buffer::get_mapped_range: buffer is not mapped");
+
+        assert!(
+            !is_write || mapped.is_write,
+            "hal::Buffer::get_mapped_range: buffer is not mapped for writing"
+        );
+
+        let start = (offset - mapped.offset) as usize;
+        assert!(
+            offset >= mapped.offset && start + size as usize <= mapped.data.len(),
+            "hal::Buffer::get_mapped_range: range is out of the mapped range"
+        );
+
+        mapped.views += 1;
+
+        BufferMapping {
+            ptr: NonNull::new(mapped.data[start..].as_mut_ptr()).unwrap(),
+            is_coherent: false,
+        }
+    }
+
+    #[inline]
+    pub fn release_mapped_range(&self) {
+        let imp = self.0.as_ref();
+
+        let mut map_state = imp.map_state.borrow_mut();
+        if let Some(mapped) = map_state.mapped.as_mut() {
+            mapped.views -= 1;
+        }
+    }
+
+    // 写 映射 的 数据 回 GPU
+    pub fn unmap(&self, gl: &glow::Context) {
+        profiling::scope!("hal::Buffer::unmap");
+
+        let imp = self.0.as_ref();
+
+        let mapped = {
+            let mut map_state = imp.map_state.borrow_mut();
+
+            if let Some(mapped) = map_state.mapped.as_ref() {
+                assert!(
+                    mapped.views == 0,
+                    "You cannot unmap a buffer that still has accessible mapped views"
+                );
+            }
+
+            map_state.mapped.take()
+        };
+
+        if let Some(mapped) = mapped {
+            if mapped.is_write {
+                self.write_buffer(gl, mapped.offset, &mapped.data);
+            }
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -64,6 +214,53 @@ pub(crate) struct BufferImpl {
     pub(crate) gl_usage: u32,         // glow::STATIC_DRAW, glow::STREAM_DRAW
 
     pub(crate) size: i32,
+
+    pub(crate) map_state: ShareCell<BufferMapState>,
+}
+
+impl BufferImpl {
+    #[inline]
+    pub(crate) fn has_shadow(&self) -> bool {
+        self.map_state.borrow().shadow.is_some()
+    }
+
+    // 取 CPU 端拷贝 的 [offset, offset + size)；没有 拷贝 返回 None
+    pub(crate) fn read_shadow(&self, offset: i32, size: i32) -> Option<Vec<u8>> {
+        let map_state = self.map_state.borrow();
+
+        map_state.shadow.as_ref().map(|shadow| {
+            let offset = offset as usize;
+            shadow[offset..offset + size as usize].to_vec()
+        })
+    }
+
+    // GPU 端 内容 变化后，同步 CPU 端拷贝
+    pub(crate) fn write_shadow(&self, offset: i32, data: &[u8]) {
+        let mut map_state = self.map_state.borrow_mut();
+
+        if let Some(shadow) = map_state.shadow.as_mut() {
+            let offset = offset as usize;
+            shadow[offset..offset + data.len()].copy_from_slice(data);
+        }
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct BufferMapState {
+    // MAP_WRITE 的 buffer，以及 模拟 间接绘制 的 INDIRECT buffer 才有值
+    pub(crate) shadow: Option<Vec<u8>>,
+
+    pub(crate) mapped: Option<MappedRange>,
+}
+
+#[derive(Debug)]
+pub(crate) struct MappedRange {
+    pub(crate) offset: i32,
+    pub(crate) is_write: bool,
+    pub(crate) data: Box<[u8]>,
+
+    // 还活着的 BufferView 数量，为 0 才能 unmap
+    pub(crate) views: usize,
 }
 
 impl Drop for BufferImpl {
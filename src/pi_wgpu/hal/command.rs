@@ -1,10 +1,12 @@
 //!
 //! + 仅 单线程
-//! + 录制 即为 调用 GL 指令
+//! + 录制 只是 记录 指令，不 调用 GL，也 不持有 GL 锁
+//! + Queue::submit 时 加锁，按 录制顺序 回放 到 GLState
 //!
 //! `CommandEncoder` 目前 仅支持 如下接口：
 //!
-//! + begin_render_pass / end_render_pass
+//! + begin_render_pass / end_render_pass，end_render_pass 时 解析 多重采样 到 resolve_target
+//!     - StoreOp::Discard 的 附件，end_render_pass 时 glInvalidateFramebuffer
 //! + set_render_pipeline
 //! + set_bind_group
 //! + set_vertex_buffer
@@ -13,20 +15,255 @@
 //! + set_scissor_rect
 //! + set_stencil_reference
 //! + set_blend_constants
+//! + set_push_constants：模拟 成 普通 uniform，draw 时 glUniform* 上传
 //! + draw / draw_indexed
+//! + draw_indirect / draw_indexed_indirect：没有 glDraw*Indirect 时 回放 读 buffer 的 CPU 端拷贝
+//! + execute_bundles：回放 RenderBundle 录好的 指令
+//! + begin_compute_pass / end_compute_pass，GLES 3.1 / 桌面 GL 4.3 以上
+//!     - set_compute_pipeline
+//!     - dispatch / dispatch_indirect：每次 dispatch 之后 glMemoryBarrier
+//! + copy_buffer_to_buffer / copy_buffer_to_texture
+//! + copy_texture_to_buffer / copy_texture_to_texture
+//! + begin_occlusion_query / end_occlusion_query，渲染通道 的 timestamp_writes
+//! + resolve_query_set：回放时 读取 查询结果，会 等待 GPU
 //!
 
-use super::super::wgt;
-use super::{AdapterContext, AdapterContextLock, GLState, PrivateCapabilities};
+use std::ops::Range;
 
+use glow::HasContext;
+use pi_share::Share;
+
+use super::super::{wgt, BufferSize, Color, Operations, StoreOp};
+use super::{AdapterContext, GLState, PrivateCapabilities};
+
+/// 录制好的 指令，只有 提交 到 Queue 才会 执行
+/// 没有 提交 的 CommandBuffer 直接 丢弃 即可
 #[derive(Debug, Clone)]
-pub(crate) struct CommandBuffer;
+pub(crate) struct CommandBuffer {
+    state: GLState,
+    private_caps: PrivateCapabilities,
+    commands: Vec<Command>,
+}
+
+impl CommandBuffer {
+    // 回放 录制的指令，调用者 需要 持有 GL 锁
+    pub(crate) fn execute(&self, gl: &glow::Context) {
+        profiling::scope!("hal::CommandBuffer::execute");
+
+        for command in self.commands.iter() {
+            self.execute_command(gl, command);
+        }
+    }
+
+    fn execute_command(&self, gl: &glow::Context, command: &Command) {
+        match command {
+            Command::BeginRenderPass(desc) => {
+                desc.mark_written();
+                self.state.set_render_target(gl, desc);
+            }
+            Command::EndRenderPass => {}
+            Command::ResolveColor { index, target } => {
+                self.state.resolve_color(gl, *index, target);
+            }
+            Command::InvalidateAttachments(attachments) => unsafe {
+                // 当前 绑定的 FRAMEBUFFER 还是 本通道 的 渲染目标
+                gl.invalidate_framebuffer(glow::FRAMEBUFFER, attachments);
+            },
+            Command::SetBindGroup {
+                index,
+                contents,
+                dynamic_offsets,
+                ..
+            } => {
+                self.state.set_bind_group(*index, contents, dynamic_offsets);
+            }
+            Command::SetRenderPipeline(pipeline) => {
+                self.state.set_render_pipeline(gl, pipeline);
+            }
+            Command::SetVertexBuffer {
+                index,
+                buffer,
+                offset,
+                size,
+            } => {
+                self.state
+                    .set_vertex_buffer(*index as usize, buffer, *offset, *size);
+            }
+            Command::SetIndexBuffer {
+                buffer,
+                format,
+                offset,
+                size,
+            } => {
+                self.state
+                    .set_index_buffer(gl, buffer, *format, *offset, *size);
+            }
+            Command::SetViewport {
+                x,
+                y,
+                w,
+                h,
+                min_depth,
+                max_depth,
+            } => {
+                self.state.set_viewport(gl, *x, *y, *w, *h);
+
+                self.state.set_depth_range(gl, *min_depth, *max_depth);
+            }
+            Command::SetScissorRect { x, y, w, h } => {
+                self.state.set_scissor(gl, *x, *y, *w, *h);
+            }
+            Command::SetStencilReference(value) => {
+                self.state.set_stencil_reference(gl, *value as i32);
+            }
+            Command::SetBlendConstants(color) => {
+                self.state.set_blend_color(gl, color);
+            }
+            Command::SetPushConstants { offset, data } => {
+                self.state.set_push_constants(*offset, data);
+            }
+            Command::Draw {
+                start_vertex,
+                vertex_count,
+                first_instance,
+                instance_count,
+            } => {
+                self.state.draw(
+                    gl,
+                    self.private_caps,
+                    *start_vertex,
+                    *vertex_count,
+                    *first_instance,
+                    *instance_count,
+                );
+            }
+            Command::DrawIndexed {
+                start_index,
+                index_count,
+                base_vertex,
+                first_instance,
+                instance_count,
+            } => {
+                self.state.draw_indexed(
+                    gl,
+                    self.private_caps,
+                    *start_index as i32,
+                    *index_count as i32,
+                    *base_vertex,
+                    *first_instance,
+                    *instance_count as i32,
+                );
+            }
+            Command::DrawIndirect { buffer, offset } => {
+                self.state
+                    .draw_indirect(gl, self.private_caps, buffer, *offset);
+            }
+            Command::DrawIndexedIndirect { buffer, offset } => {
+                self.state
+                    .draw_indexed_indirect(gl, self.private_caps, buffer, *offset);
+            }
+            Command::SetComputePipeline(pipeline) => {
+                self.state.set_compute_pipeline(gl, pipeline);
+            }
+            Command::Dispatch(count) => {
+                self.state.dispatch(gl, self.private_caps, *count);
+            }
+            Command::DispatchIndirect { buffer, offset } => {
+                self.state
+                    .dispatch_indirect(gl, self.private_caps, buffer, *offset);
+            }
+            Command::Flush => unsafe {
+                gl.flush();
+            },
+            Command::ExecuteBundle(bundle) => {
+                self.state.reset_bindings();
+                for command in bundle.commands.iter() {
+                    self.execute_command(gl, command);
+                }
+                self.state.reset_bindings();
+            }
+            Command::CopyBufferToBuffer {
+                src,
+                src_offset,
+                dst,
+                dst_offset,
+                size,
+            } => {
+                self.state
+                    .copy_buffer_to_buffer(gl, src, *src_offset, dst, *dst_offset, *size);
+            }
+            Command::CopyBufferToTexture { src, dst, size } => {
+                dst.texture.0.mark_written();
+                self.state
+                    .copy_buffer_to_texture(gl, self.private_caps, src, dst, size);
+            }
+            Command::CopyTextureToBuffer { src, dst, size } => {
+                self.state
+                    .copy_texture_to_buffer(gl, self.private_caps, src, dst, size);
+            }
+            Command::CopyTextureToTexture { src, dst, size } => {
+                dst.texture.0.mark_written();
+                self.state.copy_texture_to_texture(gl, src, dst, size);
+            }
+            Command::BeginQuery(query_set, index) => unsafe {
+                let query_set = query_set.0.as_ref();
+                gl.begin_query(query_set.target, query_set.raw[*index as usize]);
+            },
+            Command::EndQuery(target) => unsafe {
+                gl.end_query(*target);
+            },
+            Command::TimestampQuery(query_set, index) => unsafe {
+                gl.query_counter(query_set.0.raw[*index as usize], glow::TIMESTAMP);
+            },
+            Command::ResolveQuerySet {
+                query_set,
+                range,
+                dst,
+                dst_offset,
+            } => {
+                self.state.resolve_query_set(
+                    gl,
+                    self.private_caps,
+                    query_set,
+                    range.clone(),
+                    dst,
+                    *dst_offset,
+                );
+            }
+        }
+    }
+}
 
 #[derive(Debug, Clone)]
 pub(crate) struct CommandEncoder {
     state: GLState,
     adapter: AdapterContext,
     private_caps: PrivateCapabilities,
+
+    commands: Vec<Command>,
+
+    // 当前 渲染通道 的 (颜色附件 序号, resolve_target)，end_render_pass 时 解析
+    resolve_targets: Vec<(u32, super::TextureView)>,
+    // 当前 渲染通道 中 StoreOp::Discard 的 附件，end_render_pass 时 丢弃
+    discard_attachments: Vec<u32>,
+
+    // 通道 中 还 没有 设置 管线，或者 设置了 无效 的 管线，之后的 绘制 / dispatch 不录制
+    is_pipeline_invalid: bool,
+    // 渲染通道 的 附件 有 无效 的 视图，整个 通道 不录制
+    is_pass_invalid: bool,
+    // 设置了 无效 资源 的 槽位（按位），重新 设置 有效 的 之前，绘制 / dispatch 不录制
+    invalid_bind_groups: u32,
+    invalid_vertex_buffers: u32,
+    is_index_buffer_invalid: bool,
+    // 当前 渲染管线 的 layout 声明的 push constant 范围
+    push_constant_ranges: Share<[wgt::PushConstantRange]>,
+
+    // 当前 渲染通道 的 遮挡查询集
+    occlusion_query_set: Option<super::QuerySet>,
+    // 遮挡查询集 是 创建失败 的，查询 不录制
+    is_query_set_invalid: bool,
+    // 当前 渲染通道 / compute 通道 结束时 要写的 时间戳
+    end_of_pass_timestamp: Option<(super::QuerySet, u32)>,
 }
 
 impl CommandEncoder {
@@ -34,83 +271,330 @@ impl CommandEncoder {
         state: GLState,
         adapter: &AdapterContext,
         _desc: &super::super::CommandEncoderDescriptor,
-    ) -> Result<Self, super::super::DeviceError> {
-        Ok(Self {
+    ) -> Self {
+        // 只是 录制 指令，不 创建 GL 资源，不会 失败
+        Self {
             state,
             adapter: adapter.clone(),
             private_caps: adapter.imp.borrow().as_ref().unwrap().private_caps.clone(),
-        })
+            commands: vec![],
+            resolve_targets: vec![],
+            discard_attachments: vec![],
+            is_pipeline_invalid: false,
+            is_pass_invalid: false,
+            invalid_bind_groups: 0,
+            invalid_vertex_buffers: 0,
+            is_index_buffer_invalid: false,
+            push_constant_ranges: Share::from(Vec::new()),
+            occlusion_query_set: None,
+            is_query_set_invalid: false,
+            end_of_pass_timestamp: None,
+        }
     }
 }
 
 impl CommandEncoder {
+    // 结束录制，取走 已录制的 指令；编码器 可以 继续 复用
+    #[inline]
+    pub(crate) fn finish(&mut self) -> CommandBuffer {
+        CommandBuffer {
+            state: self.state.clone(),
+            private_caps: self.private_caps,
+            commands: std::mem::take(&mut self.commands),
+        }
+    }
+
+    // 每个 通道 开始 时，之前 设置 的 无效 资源 不再 影响
+    #[inline]
+    fn reset_invalid_slots(&mut self) {
+        self.invalid_bind_groups = 0;
+        self.invalid_vertex_buffers = 0;
+        self.is_index_buffer_invalid = false;
+    }
+
+    #[inline]
+    fn is_draw_skipped(&self) -> bool {
+        self.is_pass_invalid
+            || self.is_pipeline_invalid
+            || self.invalid_bind_groups != 0
+            || self.invalid_vertex_buffers != 0
+    }
+
+    // 结束 RenderBundleEncoder 的 录制
     #[inline]
-    pub(crate) fn begin_render_pass<'a>(
-        &'a self,
-        desc: &super::super::RenderPassDescriptor,
-    ) -> AdapterContextLock<'a> {
+    pub(crate) fn finish_bundle(&mut self) -> RenderBundle {
+        RenderBundle::new(std::mem::take(&mut self.commands))
+    }
+
+    // 不经过 Queue，立即 回放 已录制的 指令
+    // 仅 给 内部流程 用，比如 SwapChain 的 Y 翻转
+    pub(crate) fn flush_commands(&mut self) {
+        let cb = self.finish();
+
         let lock = self.adapter.lock(None);
+        let gl = lock.get_glow();
 
+        cb.execute(&gl);
+    }
+
+    #[inline]
+    pub(crate) fn begin_render_pass(&mut self, desc: &super::super::RenderPassDescriptor) {
+        // 通道 不 继承 之前 的 管线，set_pipeline 之前 的 绘制 不录制
+        self.is_pipeline_invalid = true;
+        self.push_constant_ranges = Share::from(Vec::new());
+        self.reset_invalid_slots();
+
+        self.resolve_targets = desc
+            .color_attachments
+            .iter()
+            .enumerate()
+            .filter_map(|(i, c)| {
+                c.as_ref()
+                    .and_then(|c| c.resolve_target)
+                    .map(|r| (i as u32, r.inner.clone().unwrap()))
+            })
+            .collect();
+
+        if self
+            .private_caps
+            .contains(PrivateCapabilities::INVALIDATE_FRAMEBUFFER)
         {
-            let gl = lock.get_glow();
-            self.state.set_render_target(&gl, desc);
+            self.discard_attachments = discard_attachments(desc);
+        }
+
+        self.occlusion_query_set = desc.occlusion_query_set.and_then(|q| q.inner.clone());
+        self.is_query_set_invalid = desc
+            .occlusion_query_set
+            .map_or(false, |q| q.inner.is_none());
+
+        self.commands
+            .push(Command::BeginRenderPass(RenderPassDesc::from(desc)));
+
+        // 无效 的 查询集 不写 时间戳
+        if let Some(writes) = desc.timestamp_writes.as_ref() {
+            if let Some(query_set) = writes.query_set.inner.as_ref() {
+                if let Some(index) = writes.beginning_of_pass_write_index {
+                    self.commands
+                        .push(Command::TimestampQuery(query_set.clone(), index));
+                }
+
+                self.end_of_pass_timestamp = writes
+                    .end_of_pass_write_index
+                    .map(|index| (query_set.clone(), index));
+            }
+        }
+    }
+
+    // 附件 有 无效 的 视图，到 end_render_pass 为止 什么 都 不录制
+    #[inline]
+    pub(crate) fn begin_invalid_render_pass(&mut self) {
+        self.is_pass_invalid = true;
+    }
+
+    #[inline]
+    pub(crate) fn end_render_pass(&mut self) {
+        if std::mem::take(&mut self.is_pass_invalid) {
+            return;
+        }
+
+        for (index, target) in self.resolve_targets.drain(..) {
+            self.commands.push(Command::ResolveColor { index, target });
+        }
+
+        // 要在 解析 之后，否则 多重采样 的 内容 已经 丢了
+        if !self.discard_attachments.is_empty() {
+            let attachments = std::mem::take(&mut self.discard_attachments);
+            self.commands
+                .push(Command::InvalidateAttachments(attachments.into_boxed_slice()));
+        }
+
+        if let Some((query_set, index)) = self.end_of_pass_timestamp.take() {
+            self.commands.push(Command::TimestampQuery(query_set, index));
         }
+        self.occlusion_query_set = None;
+
+        self.commands.push(Command::EndRenderPass);
+    }
+
+    #[inline]
+    pub(crate) fn begin_compute_pass(&mut self, desc: &super::super::ComputePassDescriptor) {
+        // 通道 不 继承 之前 的 管线，set_pipeline 之前 的 dispatch 不录制
+        self.is_pipeline_invalid = true;
+        self.push_constant_ranges = Share::from(Vec::new());
+        self.reset_invalid_slots();
+
+        // 无效 的 查询集 不写 时间戳
+        if let Some(writes) = desc.timestamp_writes.as_ref() {
+            if let Some(query_set) = writes.query_set.inner.as_ref() {
+                if let Some(index) = writes.beginning_of_pass_write_index {
+                    self.commands
+                        .push(Command::TimestampQuery(query_set.clone(), index));
+                }
 
-        lock
+                self.end_of_pass_timestamp = writes
+                    .end_of_pass_write_index
+                    .map(|index| (query_set.clone(), index));
+            }
+        }
     }
 
     #[inline]
-    pub(crate) fn end_render_pass(&self) {}
+    pub(crate) fn end_compute_pass(&mut self) {
+        if let Some((query_set, index)) = self.end_of_pass_timestamp.take() {
+            self.commands.push(Command::TimestampQuery(query_set, index));
+        }
+    }
+
+    #[inline]
+    pub(crate) fn begin_occlusion_query(&mut self, query_index: u32) {
+        if self.is_query_set_invalid || self.is_pass_invalid {
+            return;
+        }
+
+        let Some(query_set) = self.occlusion_query_set.as_ref() else {
+            log::error!("begin_occlusion_query: RenderPassDescriptor::occlusion_query_set is None, skipped");
+            return;
+        };
+        assert!(
+            (query_index as usize) < query_set.0.raw.len(),
+            "begin_occlusion_query: query index {} is out of range",
+            query_index
+        );
+
+        self.commands
+            .push(Command::BeginQuery(query_set.clone(), query_index));
+    }
+
+    #[inline]
+    pub(crate) fn end_occlusion_query(&mut self) {
+        if self.is_query_set_invalid || self.is_pass_invalid {
+            return;
+        }
+
+        let Some(query_set) = self.occlusion_query_set.as_ref() else {
+            log::error!("end_occlusion_query: RenderPassDescriptor::occlusion_query_set is None, skipped");
+            return;
+        };
+
+        self.commands.push(Command::EndQuery(query_set.0.target));
+    }
+
+    #[inline]
+    pub(crate) fn resolve_query_set(
+        &mut self,
+        query_set: &super::QuerySet,
+        range: Range<u32>,
+        dst: &super::Buffer,
+        dst_offset: i32,
+    ) {
+        self.commands.push(Command::ResolveQuerySet {
+            query_set: query_set.clone(),
+            range,
+            dst: dst.clone(),
+            dst_offset,
+        });
+    }
+
+    #[inline]
+    pub(crate) fn flush(&mut self) {
+        self.commands.push(Command::Flush);
+    }
 
     #[inline]
     pub(crate) fn set_bind_group(
-        &self,
+        &mut self,
         index: u32,
         group: &super::BindGroup,
         dynamic_offsets: &[wgt::DynamicOffset],
     ) {
-        self.state.set_bind_group(index, group, dynamic_offsets);
+        self.invalid_bind_groups &= !(1 << index);
+
+        self.commands.push(Command::SetBindGroup {
+            index,
+            group_id: group.id,
+            contents: group.contents.clone(),
+            dynamic_offsets: dynamic_offsets.to_vec().into_boxed_slice(),
+        });
+    }
+
+    #[inline]
+    pub(crate) fn set_invalid_bind_group(&mut self, index: u32) {
+        self.invalid_bind_groups |= 1 << index;
+    }
+
+    #[inline]
+    pub(crate) fn set_render_pipeline(&mut self, pipeline: &super::RenderPipeline) {
+        self.is_pipeline_invalid = false;
+        self.push_constant_ranges = pipeline.0.layout.push_constant_ranges.clone();
+
+        self.commands
+            .push(Command::SetRenderPipeline(pipeline.clone()));
+    }
+
+    #[inline]
+    pub(crate) fn set_invalid_render_pipeline(&mut self) {
+        self.is_pipeline_invalid = true;
     }
 
     #[inline]
-    pub(crate) fn set_render_pipeline(&self, gl: &glow::Context, pipeline: &super::RenderPipeline) {
-        self.state.set_render_pipeline(gl, pipeline);
+    pub(crate) fn set_compute_pipeline(&mut self, pipeline: &super::ComputePipeline) {
+        self.is_pipeline_invalid = false;
+        self.push_constant_ranges = pipeline.0.layout.push_constant_ranges.clone();
+
+        self.commands
+            .push(Command::SetComputePipeline(pipeline.clone()));
+    }
+
+    #[inline]
+    pub(crate) fn set_invalid_compute_pipeline(&mut self) {
+        self.is_pipeline_invalid = true;
     }
 
     #[inline]
     pub(crate) fn set_vertex_buffer<'a>(
-        &self,
+        &mut self,
         index: u32,
         binding: super::super::BufferBinding<'a>,
     ) {
-        self.state.set_vertex_buffer(
-            index as usize,
-            &binding.buffer.inner,
-            binding.offset as i32,
-            binding.size,
-        );
+        let Some(buffer) = binding.buffer.inner.clone() else {
+            log::error!("set_vertex_buffer: buffer of slot {} is invalid, draws are skipped", index);
+            self.invalid_vertex_buffers |= 1 << index;
+            return;
+        };
+        self.invalid_vertex_buffers &= !(1 << index);
+
+        self.commands.push(Command::SetVertexBuffer {
+            index,
+            buffer,
+            offset: binding.offset as i32,
+            size: binding.size,
+        });
     }
 
     #[inline]
     pub(crate) fn set_index_buffer<'a>(
-        &self,
-        gl: &glow::Context,
+        &mut self,
         binding: super::super::BufferBinding<'a>,
         format: wgt::IndexFormat,
     ) {
-        self.state.set_index_buffer(
-            gl,
-            &binding.buffer.inner,
+        let Some(buffer) = binding.buffer.inner.clone() else {
+            log::error!("set_index_buffer: buffer is invalid, indexed draws are skipped");
+            self.is_index_buffer_invalid = true;
+            return;
+        };
+        self.is_index_buffer_invalid = false;
+
+        self.commands.push(Command::SetIndexBuffer {
+            buffer,
             format,
-            binding.offset as i32,
-            binding.size,
-        )
+            offset: binding.offset as i32,
+            size: binding.size,
+        });
     }
 
     #[inline]
     pub(crate) fn set_viewport(
-        &self,
-        gl: &glow::Context,
+        &mut self,
         x: i32,
         y: i32,
         w: i32,
@@ -118,58 +602,597 @@ impl CommandEncoder {
         min_depth: f32,
         max_depth: f32,
     ) {
-        self.state.set_viewport(gl, x, y, w, h);
+        self.commands.push(Command::SetViewport {
+            x,
+            y,
+            w,
+            h,
+            min_depth,
+            max_depth,
+        });
+    }
 
-        self.state.set_depth_range(gl, min_depth, max_depth);
+    #[inline]
+    pub(crate) fn set_scissor_rect(&mut self, x: i32, y: i32, w: i32, h: i32) {
+        self.commands.push(Command::SetScissorRect { x, y, w, h });
     }
 
     #[inline]
-    pub(crate) fn set_scissor_rect(&self, gl: &glow::Context, x: i32, y: i32, w: i32, h: i32) {
-        self.state.set_scissor(gl, x, y, w, h);
+    pub(crate) fn set_stencil_reference(&mut self, value: u32) {
+        self.commands.push(Command::SetStencilReference(value));
     }
 
     #[inline]
-    pub(crate) fn set_stencil_reference(&self, gl: &glow::Context, value: u32) {
-        self.state.set_stencil_reference(gl, value as i32);
+    pub(crate) fn set_blend_constants(&mut self, color: &[f32; 4]) {
+        self.commands.push(Command::SetBlendConstants(*color));
     }
 
+    // offset 和 data 的 长度 都 4 字节 对齐
     #[inline]
-    pub(crate) fn set_blend_constants(&self, gl: &glow::Context, color: &[f32; 4]) {
-        self.state.set_blend_color(gl, color);
+    pub(crate) fn set_push_constants(&mut self, stages: wgt::ShaderStages, offset: u32, data: &[u8]) {
+        if self.is_pipeline_invalid {
+            return;
+        }
+
+        // 必须 落在 管线 layout 声明的 某个 范围 里，且 阶段 匹配
+        let end = offset + data.len() as u32;
+        let is_covered = self.push_constant_ranges.iter().any(|r| {
+            r.stages.contains(stages) && r.range.start <= offset && end <= r.range.end
+        });
+        if !is_covered {
+            log::error!(
+                "set_push_constants: {:?} {}..{} is not in push_constant_ranges {:?}, skipped",
+                stages,
+                offset,
+                end,
+                self.push_constant_ranges
+            );
+            return;
+        }
+
+        let data = data
+            .chunks_exact(4)
+            .map(|c| u32::from_ne_bytes([c[0], c[1], c[2], c[3]]))
+            .collect();
+
+        self.commands.push(Command::SetPushConstants { offset, data });
     }
 
     #[inline]
     pub(crate) fn draw(
-        &self,
-        gl: &glow::Context,
+        &mut self,
         start_vertex: u32,
         vertex_count: u32,
         first_instance: u32,
         instance_count: u32,
     ) {
-        self.state
-            .draw(gl, self.private_caps, start_vertex, vertex_count, first_instance, instance_count, );
+        if self.is_draw_skipped() {
+            return;
+        }
+
+        self.commands.push(Command::Draw {
+            start_vertex,
+            vertex_count,
+            first_instance,
+            instance_count,
+        });
     }
 
     #[inline]
     pub(crate) fn draw_indexed(
-        &self,
-        gl: &glow::Context,
+        &mut self,
         start_index: u32,
         index_count: u32,
         base_vertex: i32,
-        start_instance: u32,
+        first_instance: u32,
         instance_count: u32,
     ) {
-        // debug_assert!(start_instance == 0);
-        debug_assert!(base_vertex == 0);
-
-        self.state.draw_indexed(
-            gl,
-            start_index as i32,
-            index_count as i32,
-            start_instance,
-            instance_count as i32,
+        if self.is_draw_skipped() || self.is_index_buffer_invalid {
+            return;
+        }
+
+        self.commands.push(Command::DrawIndexed {
+            start_index,
+            index_count,
+            base_vertex,
+            first_instance,
+            instance_count,
+        });
+    }
+
+    #[inline]
+    pub(crate) fn draw_indirect(&mut self, buffer: &super::Buffer, offset: i32) {
+        if self.is_draw_skipped() {
+            return;
+        }
+
+        self.commands.push(Command::DrawIndirect {
+            buffer: buffer.clone(),
+            offset,
+        });
+    }
+
+    #[inline]
+    pub(crate) fn draw_indexed_indirect(&mut self, buffer: &super::Buffer, offset: i32) {
+        if self.is_draw_skipped() || self.is_index_buffer_invalid {
+            return;
+        }
+
+        self.commands.push(Command::DrawIndexedIndirect {
+            buffer: buffer.clone(),
+            offset,
+        });
+    }
+
+    #[inline]
+    pub(crate) fn dispatch(&mut self, count: [u32; 3]) {
+        if self.is_draw_skipped() {
+            return;
+        }
+
+        self.commands.push(Command::Dispatch(count));
+    }
+
+    #[inline]
+    pub(crate) fn dispatch_indirect(&mut self, buffer: &super::Buffer, offset: i32) {
+        if self.is_draw_skipped() {
+            return;
+        }
+
+        self.commands.push(Command::DispatchIndirect {
+            buffer: buffer.clone(),
+            offset,
+        });
+    }
+
+    // 和 WebGPU 一样，执行后 管线、绑定组、顶点 / 索引 都 清空，要 重新 设置 才能 绘制
+    #[inline]
+    pub(crate) fn execute_bundle(&mut self, bundle: &RenderBundle) {
+        if self.is_pass_invalid {
+            return;
+        }
+
+        if !bundle.commands.is_empty() {
+            self.commands.push(Command::ExecuteBundle(bundle.clone()));
+        }
+
+        self.is_pipeline_invalid = true;
+        self.push_constant_ranges = Share::from(Vec::new());
+        self.reset_invalid_slots();
+    }
+
+    #[inline]
+    pub(crate) fn copy_buffer_to_buffer(
+        &mut self,
+        src: &super::Buffer,
+        src_offset: i32,
+        dst: &super::Buffer,
+        dst_offset: i32,
+        size: i32,
+    ) {
+        self.commands.push(Command::CopyBufferToBuffer {
+            src: src.clone(),
+            src_offset,
+            dst: dst.clone(),
+            dst_offset,
+            size,
+        });
+    }
+
+    #[inline]
+    pub(crate) fn copy_buffer_to_texture(
+        &mut self,
+        src: &super::super::ImageCopyBuffer,
+        dst: &super::super::ImageCopyTexture,
+        size: wgt::Extent3d,
+    ) {
+        self.commands.push(Command::CopyBufferToTexture {
+            src: CopyBuffer::from(src),
+            dst: CopyTexture::from(dst),
+            size,
+        });
+    }
+
+    #[inline]
+    pub(crate) fn copy_texture_to_buffer(
+        &mut self,
+        src: &super::super::ImageCopyTexture,
+        dst: &super::super::ImageCopyBuffer,
+        size: wgt::Extent3d,
+    ) {
+        self.commands.push(Command::CopyTextureToBuffer {
+            src: CopyTexture::from(src),
+            dst: CopyBuffer::from(dst),
+            size,
+        });
+    }
+
+    #[inline]
+    pub(crate) fn copy_texture_to_texture(
+        &mut self,
+        src: &super::super::ImageCopyTexture,
+        dst: &super::super::ImageCopyTexture,
+        size: wgt::Extent3d,
+    ) {
+        self.commands.push(Command::CopyTextureToTexture {
+            src: CopyTexture::from(src),
+            dst: CopyTexture::from(dst),
+            size,
+        });
+    }
+}
+
+/// 录制的 指令
+/// 资源 都是 强引用，保证 回放 之前 不会被 释放
+#[derive(Debug, Clone)]
+pub(crate) enum Command {
+    BeginRenderPass(RenderPassDesc),
+    EndRenderPass,
+    // 把 当前 渲染目标 的 第 index 个 多重采样 颜色附件 解析到 target
+    ResolveColor {
+        index: u32,
+        target: super::TextureView,
+    },
+    // glInvalidateFramebuffer 的 附件 列表
+    InvalidateAttachments(Box<[u32]>),
+    Flush,
+
+    ExecuteBundle(RenderBundle),
+
+    SetBindGroup {
+        index: u32,
+        group_id: u32,
+        contents: Box<[super::RawBinding]>,
+        dynamic_offsets: Box<[wgt::DynamicOffset]>,
+    },
+    SetRenderPipeline(super::RenderPipeline),
+    SetVertexBuffer {
+        index: u32,
+        buffer: super::Buffer,
+        offset: i32,
+        size: Option<BufferSize>,
+    },
+    SetIndexBuffer {
+        buffer: super::Buffer,
+        format: wgt::IndexFormat,
+        offset: i32,
+        size: Option<BufferSize>,
+    },
+    SetViewport {
+        x: i32,
+        y: i32,
+        w: i32,
+        h: i32,
+        min_depth: f32,
+        max_depth: f32,
+    },
+    SetScissorRect {
+        x: i32,
+        y: i32,
+        w: i32,
+        h: i32,
+    },
+    SetStencilReference(u32),
+    SetBlendConstants([f32; 4]),
+    // offset 是 字节，数据 按 u32 存
+    SetPushConstants {
+        offset: u32,
+        data: Box<[u32]>,
+    },
+
+    Draw {
+        start_vertex: u32,
+        vertex_count: u32,
+        first_instance: u32,
+        instance_count: u32,
+    },
+    DrawIndexed {
+        start_index: u32,
+        index_count: u32,
+        base_vertex: i32,
+        first_instance: u32,
+        instance_count: u32,
+    },
+    DrawIndirect {
+        buffer: super::Buffer,
+        offset: i32,
+    },
+    DrawIndexedIndirect {
+        buffer: super::Buffer,
+        offset: i32,
+    },
+
+    SetComputePipeline(super::ComputePipeline),
+    // 三个 维度 的 工作组 数量
+    Dispatch([u32; 3]),
+    DispatchIndirect {
+        buffer: super::Buffer,
+        offset: i32,
+    },
+
+    CopyBufferToBuffer {
+        src: super::Buffer,
+        src_offset: i32,
+        dst: super::Buffer,
+        dst_offset: i32,
+        size: i32,
+    },
+    CopyBufferToTexture {
+        src: CopyBuffer,
+        dst: CopyTexture,
+        size: wgt::Extent3d,
+    },
+    CopyTextureToBuffer {
+        src: CopyTexture,
+        dst: CopyBuffer,
+        size: wgt::Extent3d,
+    },
+    CopyTextureToTexture {
+        src: CopyTexture,
+        dst: CopyTexture,
+        size: wgt::Extent3d,
+    },
+
+    // 查询集 的 第 index 个 查询
+    BeginQuery(super::QuerySet, u32),
+    // glEndQuery 的 target
+    EndQuery(u32),
+    TimestampQuery(super::QuerySet, u32),
+    // 查询结果 按 u64 写到 dst
+    ResolveQuerySet {
+        query_set: super::QuerySet,
+        range: Range<u32>,
+        dst: super::Buffer,
+        dst_offset: i32,
+    },
+}
+
+/// 录制好的 一组 渲染指令，可以 在 多个 渲染通道 中 反复 回放
+/// 录制结束时 去掉 冗余的 状态设置，回放时 直接 执行
+#[derive(Debug, Clone)]
+pub(crate) struct RenderBundle {
+    commands: Share<[Command]>,
+}
+
+impl RenderBundle {
+    fn new(commands: Vec<Command>) -> Self {
+        profiling::scope!("hal::RenderBundle::new");
+
+        let mut pipeline: Option<super::RenderPipeline> = None;
+        let mut bind_groups: Vec<Option<(u32, Box<[wgt::DynamicOffset]>)>> = vec![];
+        let mut vertex_buffers: Vec<Option<(glow::Buffer, i32, Option<BufferSize>)>> = vec![];
+        let mut index_buffer: Option<(glow::Buffer, wgt::IndexFormat, i32, Option<BufferSize>)> =
+            None;
+
+        let mut r = Vec::with_capacity(commands.len());
+
+        for command in commands {
+            let is_redundant = match &command {
+                Command::SetRenderPipeline(p) => {
+                    let same = pipeline
+                        .as_ref()
+                        .map_or(false, |old| Share::ptr_eq(&old.0, &p.0));
+                    pipeline = Some(p.clone());
+                    same
+                }
+                Command::SetBindGroup {
+                    index,
+                    group_id,
+                    dynamic_offsets,
+                    ..
+                } => {
+                    let new = Some((*group_id, dynamic_offsets.clone()));
+                    replace_if_changed(&mut bind_groups, *index as usize, new)
+                }
+                Command::SetVertexBuffer {
+                    index,
+                    buffer,
+                    offset,
+                    size,
+                } => {
+                    let new = Some((buffer.0.raw, *offset, *size));
+                    replace_if_changed(&mut vertex_buffers, *index as usize, new)
+                }
+                Command::SetIndexBuffer {
+                    buffer,
+                    format,
+                    offset,
+                    size,
+                } => {
+                    let new = Some((buffer.0.raw, *format, *offset, *size));
+                    let same = index_buffer == new;
+                    index_buffer = new;
+                    same
+                }
+                _ => false,
+            };
+
+            if is_redundant {
+                continue;
+            }
+
+            r.push(command);
+        }
+
+        // 执行 bundle 之后 这些 状态 会 清空，最后一次 绘制 之后的 设置 没用
+        let last_draw = r.iter().rposition(|c| {
+            matches!(
+                c,
+                Command::Draw { .. }
+                    | Command::DrawIndexed { .. }
+                    | Command::DrawIndirect { .. }
+                    | Command::DrawIndexedIndirect { .. }
+            )
+        });
+        let mut i = last_draw.map_or(0, |i| i + 1);
+        while i < r.len() {
+            if matches!(
+                r[i],
+                Command::SetRenderPipeline(_)
+                    | Command::SetBindGroup { .. }
+                    | Command::SetVertexBuffer { .. }
+                    | Command::SetIndexBuffer { .. }
+            ) {
+                r.remove(i);
+            } else {
+                i += 1;
+            }
+        }
+
+        Self {
+            commands: Share::from(r),
+        }
+    }
+}
+
+// 和 旧值 相同 返回 true
+fn replace_if_changed<T: PartialEq>(slots: &mut Vec<Option<T>>, index: usize, new: Option<T>) -> bool {
+    if slots.len() <= index {
+        slots.resize_with(index + 1, || None);
+    }
+
+    if slots[index] == new {
+        true
+    } else {
+        slots[index] = new;
+        false
+    }
+}
+
+/// ImageCopyBuffer / ImageCopyTexture 的 录制版本，持有 资源 的 强引用
+pub(crate) type CopyBuffer = wgt::ImageCopyBuffer<super::Buffer>;
+pub(crate) type CopyTexture = wgt::ImageCopyTexture<super::Texture>;
+
+impl From<&super::super::ImageCopyBuffer<'_>> for CopyBuffer {
+    fn from(value: &super::super::ImageCopyBuffer<'_>) -> Self {
+        Self {
+            buffer: value.buffer.inner.clone().unwrap(),
+            layout: value.layout,
+        }
+    }
+}
+
+impl From<&super::super::ImageCopyTexture<'_>> for CopyTexture {
+    fn from(value: &super::super::ImageCopyTexture<'_>) -> Self {
+        Self {
+            texture: value.texture.inner.clone().unwrap(),
+            mip_level: value.mip_level,
+            origin: value.origin,
+            aspect: value.aspect,
+        }
+    }
+}
+
+// StoreOp::Discard 的 附件；深度 / 模板 分开处理，只 丢弃 标记了 Discard 的 那一部分
+fn discard_attachments(desc: &super::super::RenderPassDescriptor) -> Vec<u32> {
+    let mut attachments = vec![];
+
+    for (i, c) in desc.color_attachments.iter().enumerate() {
+        let Some(c) = c.as_ref() else {
+            continue;
+        };
+
+        if c.ops.store != StoreOp::Discard {
+            continue;
+        }
+
+        // 默认帧缓冲 的 附件 名字 不一样
+        attachments.push(match c.view.inner.as_ref().unwrap().inner.inner {
+            super::TextureInner::NativeRenderBuffer => glow::COLOR,
+            _ => glow::COLOR_ATTACHMENT0 + i as u32,
+        });
+    }
+
+    if let Some(ds) = desc.depth_stencil_attachment.as_ref() {
+        let is_native = matches!(
+            ds.view.inner.as_ref().unwrap().inner.inner,
+            super::TextureInner::NativeRenderBuffer
         );
+
+        let is_discard = |store: Option<StoreOp>| store == Some(StoreOp::Discard);
+
+        if is_discard(ds.depth_ops.map(|o| o.store)) {
+            attachments.push(if is_native {
+                glow::DEPTH
+            } else {
+                glow::DEPTH_ATTACHMENT
+            });
+        }
+
+        if is_discard(ds.stencil_ops.map(|o| o.store)) {
+            attachments.push(if is_native {
+                glow::STENCIL
+            } else {
+                glow::STENCIL_ATTACHMENT
+            });
+        }
+    }
+
+    attachments
+}
+
+/// RenderPassDescriptor 的 录制版本，不含 生命周期
+#[derive(Debug, Clone)]
+pub(crate) struct RenderPassDesc {
+    pub(crate) color_attachments: Box<[Option<ColorAttachment>]>,
+    pub(crate) depth_stencil_attachment: Option<DepthStencilAttachment>,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct ColorAttachment {
+    pub(crate) view: super::TextureView,
+    pub(crate) resolve_target: Option<super::TextureView>,
+    pub(crate) ops: Operations<Color>,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct DepthStencilAttachment {
+    pub(crate) view: super::TextureView,
+    pub(crate) depth_ops: Option<Operations<f32>>,
+    pub(crate) stencil_ops: Option<Operations<u32>>,
+}
+
+impl RenderPassDesc {
+    // 通道 会 写 所有 附件 和 resolve_target
+    fn mark_written(&self) {
+        for c in self.color_attachments.iter().flatten() {
+            c.view.inner.mark_written();
+            if let Some(r) = c.resolve_target.as_ref() {
+                r.inner.mark_written();
+            }
+        }
+        if let Some(ds) = self.depth_stencil_attachment.as_ref() {
+            ds.view.inner.mark_written();
+        }
+    }
+}
+
+impl From<&super::super::RenderPassDescriptor<'_, '_>> for RenderPassDesc {
+    fn from(desc: &super::super::RenderPassDescriptor<'_, '_>) -> Self {
+        let color_attachments = desc
+            .color_attachments
+            .iter()
+            .map(|c| {
+                c.as_ref().map(|c| ColorAttachment {
+                    view: c.view.inner.clone().unwrap(),
+                    resolve_target: c.resolve_target.map(|r| r.inner.clone().unwrap()),
+                    ops: c.ops,
+                })
+            })
+            .collect();
+
+        let depth_stencil_attachment =
+            desc.depth_stencil_attachment
+                .as_ref()
+                .map(|ds| DepthStencilAttachment {
+                    view: ds.view.inner.clone().unwrap(),
+                    depth_ops: ds.depth_ops,
+                    stencil_ops: ds.stencil_ops,
+                });
+
+        Self {
+            color_attachments,
+            depth_stencil_attachment,
+        }
     }
 }
@@ -42,6 +42,14 @@ impl Device {
         super::Texture::with_window_surface(width, height, format)
     }
 
+    #[inline]
+    pub(crate) fn create_query_set(
+        &self,
+        desc: &super::super::QuerySetDescriptor,
+    ) -> Result<super::QuerySet, super::super::DeviceError> {
+        super::QuerySet::new(&self.adapter, desc)
+    }
+
     #[inline]
     pub(crate) fn create_sampler(
         &self,
@@ -54,7 +62,7 @@ impl Device {
     pub(crate) fn create_command_encoder(
         &self,
         desc: &super::super::CommandEncoderDescriptor,
-    ) -> Result<super::CommandEncoder, super::super::DeviceError> {
+    ) -> super::CommandEncoder {
         super::CommandEncoder::new(self.state.clone(), &self.adapter, desc)
     }
 
@@ -98,6 +106,15 @@ impl Device {
         let imp = super::RenderPipelineImpl::new(&self.state, &self.adapter, &self.features, desc)?;
         Ok(super::RenderPipeline(Share::new(imp)))
     }
+
+    #[inline]
+    pub(crate) fn create_compute_pipeline(
+        &self,
+        desc: &super::super::ComputePipelineDescriptor,
+    ) -> Result<super::ComputePipeline, super::PipelineError> {
+        let imp = super::ComputePipelineImpl::new(&self.state, &self.adapter, &self.features, desc)?;
+        Ok(super::ComputePipeline(Share::new(imp)))
+    }
     pub(crate) fn unmake_current(&self){
         self.adapter.unmake_current();
     }
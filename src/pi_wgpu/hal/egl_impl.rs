@@ -32,7 +32,15 @@ pub(crate) struct AdapterContextImpl {
     downlevel: wgt::DownlevelCapabilities,
 
     max_texture_size: u32,
+    // 默认帧缓冲 是否 有 alpha 通道，由 pi_egl 选中 的 EGL Config 决定
+    surface_has_alpha: bool,
+    // 默认帧缓冲 是 10 位 / 半浮点 时 额外 提供 的 surface 格式
+    wide_surface_formats: Vec<wgt::TextureFormat>,
+    // EGL Config 的 (EGL_MIN_SWAP_INTERVAL, EGL_MAX_SWAP_INTERVAL)
+    swap_interval_range: (i32, i32),
     shading_language_version: naga::back::glsl::Version,
+    // compute shader 的 glsl 版本，None 表示 不支持 compute
+    compute_shading_language_version: Option<naga::back::glsl::Version>,
     info: AdapterInfo,
 }
 
@@ -64,6 +72,58 @@ impl AdapterContext {
         self.egl.as_ref().borrow().present(surface);
     }
 
+    // eglSwapInterval 作用于 当前 绑定 的 surface，先 make_current
+    #[inline]
+    pub(crate) fn set_swap_interval(&self, surface: &pi_egl::Surface, interval: i32) {
+        let _lock = self.lock(Some(surface));
+
+        self.egl.as_ref().borrow().set_swap_interval(interval);
+    }
+
+    // EGL 只能 设置 交换间隔：1 = Fifo，0 = Immediate
+    // 没有 Mailbox；EGL 不接受 负的 间隔，也 没有 FifoRelaxed
+    // Config 的 最小 间隔 大于 0 时，驱动 会 把 0 截到 最小值，不能 提供 Immediate
+    // WebGL2 由 浏览器 的 requestAnimationFrame 决定，只有 Fifo
+    #[inline]
+    pub(crate) fn present_modes(&self) -> Vec<wgt::PresentMode> {
+        let (min, _max) = self
+            .imp
+            .as_ref()
+            .borrow()
+            .as_ref()
+            .unwrap()
+            .swap_interval_range;
+
+        if min <= 0 {
+            vec![wgt::PresentMode::Fifo, wgt::PresentMode::Immediate]
+        } else {
+            vec![wgt::PresentMode::Fifo]
+        }
+    }
+
+    // 只有 默认帧缓冲 带 alpha 时，窗口系统 才能 透明合成
+    // Flip-Y 的 blit 输出 总是 预乘 的 颜色，PostMultiplied 在 blit 里 预乘
+    #[inline]
+    pub(crate) fn alpha_modes(&self) -> Vec<wgt::CompositeAlphaMode> {
+        let has_alpha = self
+            .imp
+            .as_ref()
+            .borrow()
+            .as_ref()
+            .unwrap()
+            .surface_has_alpha;
+
+        if has_alpha {
+            vec![
+                wgt::CompositeAlphaMode::Opaque,
+                wgt::CompositeAlphaMode::PreMultiplied,
+                wgt::CompositeAlphaMode::PostMultiplied,
+            ]
+        } else {
+            vec![wgt::CompositeAlphaMode::Opaque]
+        }
+    }
+
     #[inline]
     pub(crate) fn create_surface<W: HasWindowHandle + HasDisplayHandle>(
         &self,
@@ -147,6 +207,16 @@ impl AdapterContext {
             .shading_language_version
     }
 
+    #[inline]
+    pub(crate) fn compute_shading_language_version(&self) -> Option<naga::back::glsl::Version> {
+        self.imp
+            .as_ref()
+            .borrow()
+            .as_ref()
+            .unwrap()
+            .compute_shading_language_version
+    }
+
     #[inline]
     pub(crate) fn info(&self) -> Ref<AdapterInfo> {
         self.imp
@@ -163,28 +233,34 @@ impl AdapterContext {
         &self,
         _surface: &super::Surface,
     ) -> Option<super::SurfaceCapabilities> {
+        // sRGB 格式 渲染到 sRGB 的 离屏纹理，blit 时 在 shader 里 编码 到 默认帧缓冲
         let mut formats = vec![
             wgt::TextureFormat::Rgba8Unorm,
             #[cfg(not(target_arch = "wasm32"))]
             wgt::TextureFormat::Bgra8Unorm,
+            wgt::TextureFormat::Rgba8UnormSrgb,
+            #[cfg(not(target_arch = "wasm32"))]
+            wgt::TextureFormat::Bgra8UnormSrgb,
         ];
 
-        if self
-            .imp
-            .as_ref()
-            .borrow()
-            .as_ref()
-            .unwrap()
+        let imp = self.imp.as_ref().borrow();
+        let imp = imp.as_ref().unwrap();
+
+        formats.extend_from_slice(&imp.wide_surface_formats);
+
+        // 离屏纹理 能 渲染 半浮点 时，blit 到 8 位 的 默认帧缓冲 也 可以 用 Rgba16Float
+        if imp
             .private_caps
             .contains(super::PrivateCapabilities::COLOR_BUFFER_HALF_FLOAT)
+            && !formats.contains(&wgt::TextureFormat::Rgba16Float)
         {
             formats.push(wgt::TextureFormat::Rgba16Float)
         }
 
         Some(super::SurfaceCapabilities {
             formats,
-            present_modes: vec![wgt::PresentMode::Fifo], //TODO
-            composite_alpha_modes: vec![wgt::CompositeAlphaMode::Opaque], //TODO
+            present_modes: self.present_modes(),
+            composite_alpha_modes: self.alpha_modes(),
             swap_chain_sizes: 2..=2,
             current_extent: None,
             extents: wgt::Extent3d {
@@ -315,56 +391,150 @@ impl AdapterContextImpl {
 
         // ========== 3. glsl shader 版本
 
-        let shading_language_version = {
-            let sl_version = unsafe { gl.get_parameter_string(glow::SHADING_LANGUAGE_VERSION) };
-
-            // log::info!("GLSL version: {}", &sl_version);
-
-            let (sl_major, sl_minor) = Self::parse_version(&sl_version).unwrap();
+        let sl_version = unsafe { gl.get_parameter_string(glow::SHADING_LANGUAGE_VERSION) };
+        // log::info!("GLSL version: {}", &sl_version);
+        let (sl_major, sl_minor) = Self::parse_version(&sl_version).unwrap();
 
-            let value = sl_major as u16 * 100 + sl_minor as u16 * 10;
-            naga::back::glsl::Version::Embedded {
-                version: value,
-                is_webgl: cfg!(target_arch = "wasm32"),
-            }
+        // 渲染 shader 统一 用 300 es，桌面 GL 靠 ARB_ES3_compatibility
+        let shading_language_version = naga::back::glsl::Version::Embedded {
+            version: 300,
+            is_webgl: cfg!(target_arch = "wasm32"),
         };
 
         // ANGLE provides renderer strings like: "ANGLE (Apple, Apple M1 Pro, OpenGL 4.1)"
         let is_angle = renderer.contains("ANGLE");
 
-        // 和 storage 有关的，webgl2 都不支持
-        let supports_storage = false;
-        let vertex_ssbo_false_zero = false;
-        let vertex_shader_storage_blocks = 0;
-        let fragment_shader_storage_blocks = 0;
-        let vertex_shader_storage_textures = 0;
-        let fragment_shader_storage_textures = 0;
-        let max_storage_block_size = 0;
-        let max_storage_buffers_per_shader_stage = 0;
-        let max_storage_textures_per_shader_stage = 0;
-
         // draw_index 能使用的最大索引的数量
         let max_element_index = unsafe { gl.get_parameter_i32(glow::MAX_ELEMENT_INDEX) } as u32;
 
+        // (GLES 版本, 桌面 GL 版本)，WebGL2 都 没有
+        let supported = |es: (u32, u32), desktop: (u32, u32)| {
+            let v = gl.version();
+            !cfg!(target_arch = "wasm32")
+                && if v.is_embedded {
+                    (v.major, v.minor) >= es
+                } else {
+                    (v.major, v.minor) >= desktop
+                }
+        };
+
+        // 间接绘制
+        let supports_indirect = supported((3, 1), (4, 0));
+        // glDrawElementsBaseVertex；没有 时 改 VAO 的 属性偏移
+        let supports_base_vertex = supported((3, 2), (3, 2))
+            || extensions.contains("GL_OES_draw_elements_base_vertex")
+            || extensions.contains("GL_EXT_draw_elements_base_vertex");
+        // glDraw*BaseInstance：GLES 只有 扩展；没有 时 改 VAO 的 实例属性偏移
+        let supports_base_instance = (!gl.version().is_embedded && supported((3, 0), (4, 2)))
+            || extensions.contains("GL_ARB_base_instance")
+            || extensions.contains("GL_EXT_base_instance");
+        // glDispatchCompute / glMemoryBarrier
+        let supports_compute = supported((3, 1), (4, 3));
+
+        // compute shader：GLES 用 310 es，桌面 GL 用 430
+        let compute_shading_language_version = if !supports_compute {
+            None
+        } else if gl.version().is_embedded {
+            Some(naga::back::glsl::Version::Embedded {
+                version: 310,
+                is_webgl: false,
+            })
+        } else {
+            Some(naga::back::glsl::Version::Desktop(430))
+        };
+        log::info!(
+            "GL Version: {}.{}, GLSL Version: {}.{}, compute = {:?}",
+            ver.0,
+            ver.1,
+            sl_major,
+            sl_minor,
+            compute_shading_language_version
+        );
+
+        // SSBO / image load store：GLES 3.1 / GL 4.3，WebGL2 都不支持
+        let supports_storage = supported((3, 1), (4, 3));
+        let (vertex_shader_storage_blocks, fragment_shader_storage_blocks) = if supports_storage {
+            unsafe {
+                (
+                    gl.get_parameter_i32(glow::MAX_VERTEX_SHADER_STORAGE_BLOCKS) as u32,
+                    gl.get_parameter_i32(glow::MAX_FRAGMENT_SHADER_STORAGE_BLOCKS) as u32,
+                )
+            }
+        } else {
+            (0, 0)
+        };
+        let (vertex_shader_storage_textures, fragment_shader_storage_textures) = if supports_storage
+        {
+            unsafe {
+                (
+                    gl.get_parameter_i32(glow::MAX_VERTEX_IMAGE_UNIFORMS) as u32,
+                    gl.get_parameter_i32(glow::MAX_FRAGMENT_IMAGE_UNIFORMS) as u32,
+                )
+            }
+        } else {
+            (0, 0)
+        };
+        let max_storage_block_size = if supports_storage {
+            unsafe { gl.get_parameter_i32(glow::MAX_SHADER_STORAGE_BLOCK_SIZE) as u32 }
+        } else {
+            0
+        };
+
+        // WORKAROUND: In order to work around an issue with GL on RPI4 and similar, we ignore a
+        // zero vertex ssbo count if there are vertex sstos. (more info:
+        // https://github.com/gfx-rs/wgpu/pull/1607#issuecomment-874938961) The hardware does not
+        // want us to write to these SSBOs, but GLES cannot express that. We detect this case and
+        // disable writing to SSBOs.
+        let vertex_ssbo_false_zero =
+            vertex_shader_storage_blocks == 0 && vertex_shader_storage_textures != 0;
+        if vertex_ssbo_false_zero {
+            // We only care about fragment here as the 0 is a lie.
+            log::warn!("Max vertex shader SSBO == 0 and SSTO != 0. Interpreting as false zero.");
+        }
+
+        let max_storage_buffers_per_shader_stage = if vertex_ssbo_false_zero {
+            fragment_shader_storage_blocks
+        } else {
+            vertex_shader_storage_blocks.min(fragment_shader_storage_blocks)
+        };
+        let max_storage_textures_per_shader_stage =
+            vertex_shader_storage_textures.min(fragment_shader_storage_textures);
+
         let mut downlevel_flags = wgt::DownlevelFlags::empty()
             | wgt::DownlevelFlags::NON_POWER_OF_TWO_MIPMAPPED_TEXTURES
             // TODO | wgt::DownlevelFlags::CUBE_ARRAY_TEXTURES
             | wgt::DownlevelFlags::COMPARISON_SAMPLERS;
 
         // WebGL2 不支持: 计算着色器
-        downlevel_flags.set(wgt::DownlevelFlags::COMPUTE_SHADERS, false);
+        downlevel_flags.set(wgt::DownlevelFlags::COMPUTE_SHADERS, supports_compute);
         // WebGL2 不支持: Storage
-        downlevel_flags.set(wgt::DownlevelFlags::FRAGMENT_WRITABLE_STORAGE, false);
+        downlevel_flags.set(
+            wgt::DownlevelFlags::FRAGMENT_WRITABLE_STORAGE,
+            max_storage_block_size != 0
+                && (fragment_shader_storage_blocks != 0 || fragment_shader_storage_textures != 0),
+        );
         // WebGL2 不支持: Storage
-        downlevel_flags.set(wgt::DownlevelFlags::VERTEX_STORAGE, false);
+        downlevel_flags.set(
+            wgt::DownlevelFlags::VERTEX_STORAGE,
+            max_storage_block_size != 0
+                && max_storage_buffers_per_shader_stage != 0
+                && (vertex_shader_storage_blocks != 0 || vertex_ssbo_false_zero),
+        );
         // WebGL2 不支持: Storage
-        downlevel_flags.set(wgt::DownlevelFlags::FRAGMENT_STORAGE, false);
-        // WebGL2 不支持: 间接渲染
-        downlevel_flags.set(wgt::DownlevelFlags::INDIRECT_EXECUTION, false);
-        // WebGL2 不支持: base_vertex
-        downlevel_flags.set(wgt::DownlevelFlags::BASE_VERTEX, false);
-        // WebGL2 不支持: 为每个ColorAttachment 单独指定 Blend
-        downlevel_flags.set(wgt::DownlevelFlags::INDEPENDENT_BLEND, false);
+        downlevel_flags.set(wgt::DownlevelFlags::FRAGMENT_STORAGE, supports_storage);
+        // WebGL2 / GLES 3.0 不支持: 间接渲染，draw_indirect 退化为 从 GPU 回读 参数
+        downlevel_flags.set(wgt::DownlevelFlags::INDIRECT_EXECUTION, supports_indirect);
+        // base_vertex：只 报告 原生 支持；没有 时 用 VAO 属性偏移 模拟，负的 base_vertex 可能 跳过 绘制
+        downlevel_flags.set(wgt::DownlevelFlags::BASE_VERTEX, supports_base_vertex);
+        // 为每个ColorAttachment 单独指定 Blend：GL 4.0 / GLES 3.2 核心，其他 看扩展
+        downlevel_flags.set(
+            wgt::DownlevelFlags::INDEPENDENT_BLEND,
+            supported((3, 2), (4, 0))
+                || extensions.contains("GL_ARB_draw_buffers_blend")
+                || extensions.contains("GL_OES_draw_buffers_indexed")
+                || extensions.contains("GL_EXT_draw_buffers_indexed")
+                || extensions.contains("OES_draw_buffers_indexed"),
+        );
         // 各向异性过滤，看扩展
         downlevel_flags.set(
             wgt::DownlevelFlags::ANISOTROPIC_FILTERING,
@@ -389,9 +559,10 @@ impl AdapterContextImpl {
         );
 
         // TODO | wgt::Features::CLEAR_TEXTURE
-        // TODO | wgt::Features::PUSH_CONSTANTS
-        let mut features =
-            wgt::Features::empty() | wgt::Features::TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES;
+        // PUSH_CONSTANTS 模拟 成 普通 uniform，draw 时 上传
+        let mut features = wgt::Features::empty()
+            | wgt::Features::TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES
+            | wgt::Features::PUSH_CONSTANTS;
 
         // 不支持：纹理坐标寻址 Border
         features.set(
@@ -400,12 +571,26 @@ impl AdapterContextImpl {
         );
         // 不支持：Depth Clip
         features.set(wgt::Features::DEPTH_CLIP_CONTROL, false);
-        // 不支持：Storage
-        features.set(wgt::Features::VERTEX_WRITABLE_STORAGE, false);
+        // VS 写 Storage
+        features.set(
+            wgt::Features::VERTEX_WRITABLE_STORAGE,
+            supports_storage && vertex_shader_storage_blocks != 0 && !vertex_ssbo_false_zero,
+        );
         // 不支持: 扩展 "OVR_multiview2"
         features.set(wgt::Features::MULTIVIEW, false);
         // 不支持: 集合着色器
         features.set(wgt::Features::SHADER_PRIMITIVE_INDEX, false);
+        // 时间戳 查询
+        let timer_query = extensions.contains("GL_EXT_disjoint_timer_query")
+            || extensions.contains("GL_ARB_timer_query")
+            || extensions.contains("EXT_disjoint_timer_query_webgl2");
+        features.set(wgt::Features::TIMESTAMP_QUERY, timer_query);
+        // 间接参数 里 的 first_instance：GL 4.0 / 4.1 / GLES 3.1 的 glDraw*Indirect 要求 为 0
+        // 没有 glDraw*Indirect 时 从 CPU 端拷贝 取 参数，和 普通 draw 一样 处理
+        features.set(
+            wgt::Features::INDIRECT_FIRST_INSTANCE,
+            supports_base_instance || !supports_indirect,
+        );
         // DDS 支持
         let gles_bcn_exts = [
             "GL_EXT_texture_compression_s3tc_srgb",
@@ -463,10 +648,65 @@ impl AdapterContextImpl {
             false,
             // extensions.contains("GL_EXT_texture_shadow_lod"),
         );
-        private_caps.set(super::PrivateCapabilities::MEMORY_BARRIERS, false);
+        private_caps.set(super::PrivateCapabilities::MEMORY_BARRIERS, supports_compute);
+        private_caps.set(super::PrivateCapabilities::SHADER_STORAGE, supports_storage);
         private_caps.set(super::PrivateCapabilities::VERTEX_BUFFER_LAYOUT, false);
         private_caps.set(super::PrivateCapabilities::INDEX_BUFFER_ROLE_CHANGE, false);
-        private_caps.set(super::PrivateCapabilities::GET_BUFFER_SUB_DATA, false);
+        // 桌面 GL 和 WebGL2 有 glGetBufferSubData；GLES 3.0 用 glMapBufferRange 回读
+        private_caps.set(
+            super::PrivateCapabilities::GET_BUFFER_SUB_DATA,
+            cfg!(target_arch = "wasm32") || !version.contains(" ES "),
+        );
+        // GLES 3.0 / WebGL2 都有 glInvalidateFramebuffer；桌面 GL 要 4.3 或 扩展
+        private_caps.set(
+            super::PrivateCapabilities::INVALIDATE_FRAMEBUFFER,
+            cfg!(target_arch = "wasm32")
+                || version.contains(" ES ")
+                || extensions.contains("GL_ARB_invalidate_subdata"),
+        );
+        // 查询结果 直接 写到 buffer
+        private_caps.set(
+            super::PrivateCapabilities::QUERY_BUFFERS,
+            extensions.contains("GL_ARB_query_buffer_object")
+                || extensions.contains("GL_AMD_query_buffer_object"),
+        );
+        // glGetQueryObjectui64v：桌面 GL 3.3 核心，GLES 要 计时器 扩展；WebGL2 没有
+        private_caps.set(
+            super::PrivateCapabilities::QUERY_64BIT,
+            !cfg!(target_arch = "wasm32") && (!version.contains(" ES ") || timer_query),
+        );
+        private_caps.set(super::PrivateCapabilities::DRAW_INDIRECT, supports_indirect);
+        private_caps.set(
+            super::PrivateCapabilities::DRAW_BASE_VERTEX,
+            supports_base_vertex,
+        );
+        private_caps.set(
+            super::PrivateCapabilities::FULLY_FEATURED_INSTANCING,
+            supports_base_instance,
+        );
+        // sRGB 纹理 的 线性视图：采样 时 跳过 解码
+        private_caps.set(
+            super::PrivateCapabilities::TEXTURE_SRGB_DECODE,
+            extensions.contains("GL_EXT_texture_sRGB_decode")
+                || extensions.contains("EXT_texture_sRGB_decode"),
+        );
+        // 桌面 GL：BGRA 的 像素数据 可以 直接 上传，sRGB 附件 要 打开 FRAMEBUFFER_SRGB 才 编码
+        // GLES / WebGL2：BGRA 在 CPU 上 交换 R / B，sRGB 附件 总是 编码
+        // GL_EXT_texture_format_BGRA8888 只 允许 BGRA 上传 到 BGRA8_EXT 存储 的 纹理，
+        // Bgra8 纹理 用 RGBA8 / SRGB8_ALPHA8 存储，有 这个 扩展 也 不能 直接 上传
+        private_caps.set(
+            super::PrivateCapabilities::TEXTURE_FORMAT_BGRA,
+            !gl.version().is_embedded,
+        );
+        // 回读 BGRA：GLES 要 GL_EXT_read_format_bgra，否则 按 RGBA 读 再 交换 R / B
+        private_caps.set(
+            super::PrivateCapabilities::READ_FORMAT_BGRA,
+            !gl.version().is_embedded || extensions.contains("GL_EXT_read_format_bgra"),
+        );
+        private_caps.set(
+            super::PrivateCapabilities::FRAMEBUFFER_SRGB,
+            !gl.version().is_embedded,
+        );
         let color_buffer_float = extensions.contains("GL_EXT_color_buffer_float")
             || extensions.contains("EXT_color_buffer_float");
 
@@ -484,20 +724,73 @@ impl AdapterContextImpl {
 
         let max_texture_size = unsafe { gl.get_parameter_i32(glow::MAX_TEXTURE_SIZE) } as u32;
 
+        // 此时 surface 是 当前 绑定 的，从 EGL Config 查 交换间隔 的 范围；查不到 时 只 认为 支持 1
+        let swap_interval_range = (
+            super::egl_native::current_config_attrib(super::egl_native::MIN_SWAP_INTERVAL)
+                .unwrap_or(1),
+            super::egl_native::current_config_attrib(super::egl_native::MAX_SWAP_INTERVAL)
+                .unwrap_or(1),
+        );
+
+        // 此时 surface 是 当前 绑定 的，查询 默认帧缓冲 的 alpha 位数
+        let surface_has_alpha =
+            Self::default_framebuffer_bits(gl, glow::FRAMEBUFFER_ATTACHMENT_ALPHA_SIZE) > 0;
+
+        // EGL Config 由 pi_egl 选择，这里 从 当前 surface 的 EGL Config 查 选中 的 是不是 宽色域 的：
+        // 10 位 定点 = RGB10_A2，16 位 浮点（EGL_EXT_pixel_format_float）= 半浮点
+        // 离屏纹理 要 能 渲染 同样 的 格式，blit 才 不会 丢 精度 / 截断 到 [0, 1]
+        // pi_egl 不会 主动 选 这样 的 Config，也 不能 在 创建 surface 时 指定 EGL_EXT_gl_colorspace_*，
+        // 要 等 pi_egl 支持 后 才 能 真正 拿到 宽色域 的 交换链；颜色空间 总是 sRGB
+        let config_red_size =
+            super::egl_native::current_config_attrib(super::egl_native::RED_SIZE).unwrap_or(8);
+        let config_is_float = super::egl_native::current_config_attrib(
+            super::egl_native::COLOR_COMPONENT_TYPE_EXT,
+        ) == Some(super::egl_native::COLOR_COMPONENT_TYPE_FLOAT_EXT);
+
+        let mut wide_surface_formats = vec![];
+        if config_is_float
+            && config_red_size == 16
+            && (!gl.version().is_embedded || color_buffer_half_float || color_buffer_float)
+        {
+            wide_surface_formats.push(wgt::TextureFormat::Rgba16Float);
+            wide_surface_formats.push(wgt::TextureFormat::Rgb10a2Unorm);
+        } else if !config_is_float && config_red_size == 10 {
+            wide_surface_formats.push(wgt::TextureFormat::Rgb10a2Unorm);
+        }
+
         let max_texture_3d_size = unsafe { gl.get_parameter_i32(glow::MAX_3D_TEXTURE_SIZE) } as u32;
 
         let min_uniform_buffer_offset_alignment =
             (unsafe { gl.get_parameter_i32(glow::UNIFORM_BUFFER_OFFSET_ALIGNMENT) } as u32);
 
-        let min_storage_buffer_offset_alignment = 0;
+        let min_storage_buffer_offset_alignment = if supports_storage {
+            unsafe { gl.get_parameter_i32(glow::SHADER_STORAGE_BUFFER_OFFSET_ALIGNMENT) as u32 }
+        } else {
+            256
+        };
 
         let max_uniform_buffers_per_shader_stage =
             unsafe { gl.get_parameter_i32(glow::MAX_VERTEX_UNIFORM_BLOCKS) }
                 .min(unsafe { gl.get_parameter_i32(glow::MAX_FRAGMENT_UNIFORM_BLOCKS) })
                 as u32;
 
-        let supports_work_group_params = false;
-        let max_compute_workgroups_per_dimension = 0;
+        let max_compute_workgroups_per_dimension = if supports_compute {
+            unsafe {
+                gl.get_parameter_indexed_i32(glow::MAX_COMPUTE_WORK_GROUP_COUNT, 0)
+                    .min(gl.get_parameter_indexed_i32(glow::MAX_COMPUTE_WORK_GROUP_COUNT, 1))
+                    .min(gl.get_parameter_indexed_i32(glow::MAX_COMPUTE_WORK_GROUP_COUNT, 2))
+            } as u32
+        } else {
+            0
+        };
+        let max_compute_workgroup_size = |index: u32| {
+            if supports_compute {
+                unsafe { gl.get_parameter_indexed_i32(glow::MAX_COMPUTE_WORK_GROUP_SIZE, index) }
+                    as u32
+            } else {
+                0
+            }
+        };
 
         let limits = wgt::Limits {
             max_texture_dimension_1d: max_texture_size,
@@ -518,7 +811,7 @@ impl AdapterContextImpl {
             max_uniform_buffer_binding_size: unsafe {
                 gl.get_parameter_i32(glow::MAX_UNIFORM_BLOCK_SIZE)
             } as u32,
-            max_storage_buffer_binding_size: 0,
+            max_storage_buffer_binding_size: max_storage_block_size,
             max_vertex_buffers: if private_caps
                 .contains(super::PrivateCapabilities::VERTEX_BUFFER_LAYOUT)
             {
@@ -536,17 +829,25 @@ impl AdapterContextImpl {
             } else {
                 !0
             },
-            max_push_constant_size: 0, // super::MAX_PUSH_CONSTANTS as u32 * 4,
+            max_push_constant_size: super::MAX_PUSH_CONSTANTS as u32 * 4,
             min_uniform_buffer_offset_alignment,
             min_storage_buffer_offset_alignment,
             max_inter_stage_shader_components: unsafe {
                 gl.get_parameter_i32(glow::MAX_VARYING_COMPONENTS)
             } as u32,
-            max_compute_workgroup_storage_size: 0,
-            max_compute_invocations_per_workgroup: 0,
-            max_compute_workgroup_size_x: 0,
-            max_compute_workgroup_size_y: 0,
-            max_compute_workgroup_size_z: 0,
+            max_compute_workgroup_storage_size: if supports_compute {
+                unsafe { gl.get_parameter_i32(glow::MAX_COMPUTE_SHARED_MEMORY_SIZE) as u32 }
+            } else {
+                0
+            },
+            max_compute_invocations_per_workgroup: if supports_compute {
+                unsafe { gl.get_parameter_i32(glow::MAX_COMPUTE_WORK_GROUP_INVOCATIONS) as u32 }
+            } else {
+                0
+            },
+            max_compute_workgroup_size_x: max_compute_workgroup_size(0),
+            max_compute_workgroup_size_y: max_compute_workgroup_size(1),
+            max_compute_workgroup_size_z: max_compute_workgroup_size(2),
             max_compute_workgroups_per_dimension,
             max_buffer_size: i32::MAX as u64,
             max_non_sampler_bindings: std::u32::MAX,
@@ -569,7 +870,11 @@ impl AdapterContextImpl {
             limits,
             downlevel,
             max_texture_size,
+            surface_has_alpha,
+            wide_surface_formats,
+            swap_interval_range,
             shading_language_version,
+            compute_shading_language_version,
             info,
         }
     }
@@ -660,6 +965,21 @@ impl AdapterContextImpl {
         }
     }
 
+    // 默认帧缓冲 颜色 的 位数；GL_ALPHA_BITS / GL_RED_BITS 在 桌面 core profile 里 已经 移除
+    fn default_framebuffer_bits(gl: &glow::Context, pname: u32) -> i32 {
+        // 默认帧缓冲 的 挂接点：桌面 GL 是 BACK_LEFT，GLES / WebGL2 是 BACK
+        let attachment = if gl.version().is_embedded {
+            glow::BACK
+        } else {
+            glow::BACK_LEFT
+        };
+
+        unsafe {
+            gl.bind_framebuffer(glow::FRAMEBUFFER, None);
+            gl.get_framebuffer_attachment_parameter_i32(glow::FRAMEBUFFER, attachment, pname)
+        }
+    }
+
     /// According to the OpenGL specification, the version information is
     /// expected to follow the following syntax:
     ///
@@ -724,10 +1044,14 @@ impl AdapterContextImpl {
         });
 
         match (major, minor) {
-            (Some(_major), Some(_minor)) => Ok((
+            (Some(major), Some(minor)) => Ok((
                 // Return WebGL 2.0 version as OpenGL ES 3.0
-                if is_webgl && !is_glsl { 3 } else { 3 },
-                0,
+                if is_webgl && !is_glsl {
+                    major + 1
+                } else {
+                    major
+                },
+                minor,
             )),
             _ => {
                 log::warn!("Unable to extract the version from '{}'", version);
@@ -875,4 +1199,12 @@ impl Egl {
     fn present(&self, surface: &pi_egl::Surface) {
         self.instance.swap_buffers(surface);
     }
+
+    // 调用者 保证 目标 surface 是 当前的；WebGL2 没有 交换间隔
+    #[inline]
+    fn set_swap_interval(&self, interval: i32) {
+        if !cfg!(target_arch = "wasm32") && !super::egl_native::swap_interval(interval) {
+            log::error!("hal::Egl::set_swap_interval: eglSwapInterval({}) failed", interval);
+        }
+    }
 }
@@ -0,0 +1,107 @@
+//! pi_egl 没有 暴露 EGL Config 和 交换间隔 的 查询，
+//! 这里 不 另外 加载 libEGL，只 取 pi_egl 已经 加载 的 那一份，查询 当前 绑定 的 surface
+//! 这样 拿到的 display / surface 和 pi_egl 是 同一套；找不到 时 查询 全部 返回 None
+//!
+//! 调用者 保证 目标 surface 是 当前的；WebGL2 没有 EGL，全部 返回 None
+
+// EGL Config 属性，wasm 上 也要 能 编译，不 直接 用 khronos_egl 的
+pub(crate) const MIN_SWAP_INTERVAL: i32 = 0x303B;
+pub(crate) const MAX_SWAP_INTERVAL: i32 = 0x303C;
+pub(crate) const RED_SIZE: i32 = 0x3024;
+// EGL_EXT_pixel_format_float，不支持 这个 扩展 时 查询 失败，返回 None
+pub(crate) const COLOR_COMPONENT_TYPE_EXT: i32 = 0x3339;
+pub(crate) const COLOR_COMPONENT_TYPE_FLOAT_EXT: i32 = 0x333B;
+
+#[cfg(not(target_arch = "wasm32"))]
+type EglInstance = khronos_egl::DynamicInstance<khronos_egl::EGL1_4>;
+
+#[cfg(not(target_arch = "wasm32"))]
+lazy_static! {
+    static ref EGL: Option<EglInstance> = {
+        match loaded_egl().map(|lib| unsafe { EglInstance::load_required_from(lib) }) {
+            Some(Ok(egl)) => Some(egl),
+            Some(Err(e)) => {
+                log::warn!("hal::egl_native: EGL symbols loaded by pi_egl are not found, {:?}", e);
+                None
+            }
+            None => None,
+        }
+    };
+}
+
+// pi_egl 加载 libEGL 时 可能 用到 的 名字
+#[cfg(unix)]
+const EGL_NAMES: &[&str] = if cfg!(any(target_os = "macos", target_os = "ios")) {
+    &["libEGL.dylib"]
+} else {
+    &["libEGL.so.1", "libEGL.so"]
+};
+
+// RTLD_NOLOAD：只 返回 进程 中 已经 加载 的 库，不会 加载 第二份
+// 都 没有 时 从 进程 的 全局符号 里 找，pi_egl 静态链接 EGL 时 走 这里
+#[cfg(unix)]
+fn loaded_egl() -> Option<libloading::Library> {
+    use libloading::os::unix::{Library, RTLD_NOW};
+
+    for name in EGL_NAMES {
+        if let Ok(lib) = unsafe { Library::open(Some(name), RTLD_NOW | libc::RTLD_NOLOAD) } {
+            return Some(lib.into());
+        }
+    }
+
+    Some(Library::this().into())
+}
+
+// GetModuleHandleEx：只 取 pi_egl 已经 加载 的 libEGL.dll
+#[cfg(windows)]
+fn loaded_egl() -> Option<libloading::Library> {
+    match libloading::os::windows::Library::open_already_loaded("libEGL.dll") {
+        Ok(lib) => Some(lib.into()),
+        Err(e) => {
+            log::warn!("hal::egl_native: libEGL.dll is not loaded by pi_egl, {:?}", e);
+            None
+        }
+    }
+}
+
+// 当前 surface 的 EGL Config 的 属性
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) fn current_config_attrib(attrib: i32) -> Option<i32> {
+    let egl = EGL.as_ref()?;
+
+    let display = egl.get_current_display()?;
+    let surface = egl.get_current_surface(khronos_egl::DRAW)?;
+
+    let id = egl
+        .query_surface(display, surface, khronos_egl::CONFIG_ID)
+        .ok()?;
+    let config = egl
+        .choose_first_config(display, &[khronos_egl::CONFIG_ID, id, khronos_egl::NONE])
+        .ok()??;
+
+    egl.get_config_attrib(display, config, attrib).ok()
+}
+
+#[cfg(target_arch = "wasm32")]
+pub(crate) fn current_config_attrib(_attrib: i32) -> Option<i32> {
+    None
+}
+
+// eglSwapInterval，作用于 当前 surface；失败 返回 false
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) fn swap_interval(interval: i32) -> bool {
+    let egl = match EGL.as_ref() {
+        Some(egl) => egl,
+        None => return false,
+    };
+
+    match egl.get_current_display() {
+        Some(display) => egl.swap_interval(display, interval).is_ok(),
+        None => false,
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+pub(crate) fn swap_interval(_interval: i32) -> bool {
+    false
+}
@@ -17,8 +17,12 @@ use super::{
     RasterState, RasterStateImpl, RenderTarget, ShaderID, StencilState, StencilStateImpl, VBState,
 };
 
+// (vs, fs) 编译后 的 shader id；compute 是 (cs, cs)
 pub(crate) type ProgramID = (ShaderID, ShaderID);
 
+// 一个 ShaderModule 可以有 多个 入口，按 (模块, 阶段, 入口) 分别 编译
+pub(crate) type ShaderKey = (ShaderID, naga::ShaderStage, String);
+
 #[derive(Debug, PartialEq, Eq, Hash, Deref)]
 pub struct VertexArrayAsset(pub glow::VertexArray);
 
@@ -48,14 +52,16 @@ pub(crate) struct GLCache {
 
     shader_binding_map: super::ShaderBindingMap,
     // vao_map: Share<AssetMgr<VertexArrayAsset, VaoGarbage>>,
-    vao_map: XHashMap<u64, glow::VertexArray>,
+    // 值: (vao, 属性 偏移 用的 first_instance 和 base_vertex)
+    vao_map: XHashMap<u64, (glow::VertexArray, (u32, i32))>,
     garbage_vao: Share<TrustCell<Vec<glow::VertexArray>>>,
     buffer_vao_map: XHashMap<glow::Buffer, Vec<u64>>, // buffer 与vao资源key的对应关系
     fbo_map: XHashMap<RenderTarget, glow::Framebuffer>,
     shader_map: XHashMap<ShaderID, ShaderInner>,
+    shader_key_map: XHashMap<ShaderKey, ShaderID>,
 
     program_map: XHashMap<ProgramID, ShareWeak<super::ProgramImpl>>,
-    bs_map: XHashMap<BlendStateImpl, ShareWeak<BlendState>>,
+    bs_map: XHashMap<Box<[Option<BlendStateImpl>]>, ShareWeak<BlendState>>,
     rs_map: XHashMap<RasterStateImpl, ShareWeak<RasterState>>,
     ds_map: XHashMap<DepthStateImpl, ShareWeak<DepthState>>,
     ss_map: XHashMap<StencilStateImpl, ShareWeak<StencilState>>,
@@ -64,7 +70,12 @@ pub(crate) struct GLCache {
 
 impl GLCache {
     #[inline]
-    pub(crate) fn new(max_uniform_buffer_bindings: usize, max_textures_slots: usize) -> Self {
+    pub(crate) fn new(
+        max_uniform_buffer_bindings: usize,
+        max_textures_slots: usize,
+        max_storage_buffer_bindings: usize,
+        max_image_units: usize,
+    ) -> Self {
         let garbage_vao = Share::new(TrustCell::new(Vec::new()));
         // let vao_map = AssetMgr::new(VaoGarbage(garbage_vao.clone()), false, 20 * 1024, 10 * 1000); // 过期时间：10分钟
         // alloter.register(vao_map.clone(), 20 * 1024, 30 * 1024);
@@ -78,6 +89,7 @@ impl GLCache {
             buffer_vao_map: Default::default(),
             fbo_map: Default::default(),
             shader_map: Default::default(),
+            shader_key_map: Default::default(),
 
             program_map: Default::default(),
 
@@ -89,6 +101,8 @@ impl GLCache {
             shader_binding_map: super::ShaderBindingMap::new(
                 max_uniform_buffer_bindings,
                 max_textures_slots,
+                max_storage_buffer_bindings,
+                max_image_units,
             ),
         }
     }
@@ -138,13 +152,27 @@ impl GLCache {
     }
 
     #[inline]
-    pub(crate) fn insert_shader(&mut self, id: ShaderID, inner: ShaderInner) {
-        self.shader_map.insert(id, inner);
+    pub(crate) fn get_shader_id(&self, key: &ShaderKey) -> Option<ShaderID> {
+        self.shader_key_map.get(key).copied()
     }
 
     #[inline]
-    pub(crate) fn remove_shader(&mut self, id: ShaderID) {
-        self.shader_map.remove(&id);
+    pub(crate) fn insert_shader(&mut self, key: ShaderKey, id: ShaderID, inner: ShaderInner) {
+        self.shader_key_map.insert(key, id);
+        self.shader_map.insert(id, inner);
+    }
+
+    // 删除 模块 的 所有 阶段 / 入口
+    pub(crate) fn remove_shader(&mut self, module_id: ShaderID) {
+        let shader_map = &mut self.shader_map;
+        self.shader_key_map.retain(|key, id| {
+            if key.0 == module_id {
+                shader_map.remove(id);
+                false
+            } else {
+                true
+            }
+        });
     }
 
     #[inline]
@@ -157,6 +185,16 @@ impl GLCache {
         self.shader_binding_map.get_or_insert_sampler(binding)
     }
 
+    #[inline]
+    pub(crate) fn update_ssbo(&mut self, binding: super::PiResourceBinding) -> u32 {
+        self.shader_binding_map.get_or_insert_ssbo(binding)
+    }
+
+    #[inline]
+    pub(crate) fn update_image(&mut self, binding: super::PiResourceBinding) -> u32 {
+        self.shader_binding_map.get_or_insert_image(binding)
+    }
+
     pub(crate) fn get_or_insert_rs(&mut self, rs: RasterStateImpl) -> Share<RasterState> {
         profiling::scope!("hal::GLCache::get_or_insert_rs");
         // 尝试获取一个存在的Weak引用并升级
@@ -177,7 +215,7 @@ impl GLCache {
 
     pub(crate) fn get_or_insert_bs(
         &mut self,
-        bs: super::BlendStateImpl,
+        bs: Box<[Option<super::BlendStateImpl>]>,
     ) -> Share<super::BlendState> {
         profiling::scope!("hal::GLCache::get_or_insert_bs");
 
@@ -261,55 +299,31 @@ impl GLCache {
                 gl.bind_framebuffer(glow::FRAMEBUFFER, Some(*fbo));
             },
             None => unsafe {
-                if let hal::GLTextureInfo::NativeRenderBuffer = &render_target.colors {
+                if render_target.is_native() {
                     gl.bind_framebuffer(glow::FRAMEBUFFER, None);
                 } else {
                     let fbo = gl.create_framebuffer().unwrap();
 
                     gl.bind_framebuffer(glow::FRAMEBUFFER, Some(fbo));
 
-                    match &render_target.colors {
-                        hal::GLTextureInfo::NativeRenderBuffer => unreachable!(),
-                        hal::GLTextureInfo::Renderbuffer(raw) => {
-                            gl.framebuffer_renderbuffer(
-                                glow::FRAMEBUFFER,
-                                glow::COLOR_ATTACHMENT0,
-                                glow::RENDERBUFFER,
-                                Some(*raw),
-                            );
-                        }
-                        hal::GLTextureInfo::Texture(raw) => {
-                            gl.framebuffer_texture_2d(
-                                glow::FRAMEBUFFER,
-                                glow::COLOR_ATTACHMENT0,
-                                glow::TEXTURE_2D,
-                                Some(*raw),
-                                0,
-                            );
+                    // 没有 颜色附件 的 槽位 用 NONE 占位，片元着色器 的 location 和 槽位 一一对应
+                    let mut draw_buffers = Vec::with_capacity(render_target.colors.len());
+                    for (i, color) in render_target.colors.iter().enumerate() {
+                        match color {
+                            Some(color) => {
+                                let attachment = glow::COLOR_ATTACHMENT0 + i as u32;
+                                Self::attach_fbo(gl, attachment, color);
+                                draw_buffers.push(attachment);
+                            }
+                            None => draw_buffers.push(glow::NONE),
                         }
                     }
+                    // draw_buffers 是 fbo 的 状态，创建时 设置 一次 即可
+                    gl.draw_buffers(&draw_buffers);
 
                     if let Some(depth_stencil) = &render_target.depth_stencil {
-                        match depth_stencil {
-                            hal::GLTextureInfo::NativeRenderBuffer => unreachable!(),
-                            hal::GLTextureInfo::Renderbuffer(raw) => {
-                                gl.framebuffer_renderbuffer(
-                                    glow::FRAMEBUFFER,
-                                    glow::DEPTH_ATTACHMENT, // GL_STENCIL_ATTACHMENT 会 自动绑定
-                                    glow::RENDERBUFFER,
-                                    Some(*raw),
-                                );
-                            }
-                            hal::GLTextureInfo::Texture(raw) => {
-                                gl.framebuffer_texture_2d(
-                                    glow::FRAMEBUFFER,
-                                    glow::DEPTH_ATTACHMENT, // GL_STENCIL_ATTACHMENT 会 自动绑定
-                                    glow::TEXTURE_2D,
-                                    Some(*raw),
-                                    0,
-                                );
-                            }
-                        }
+                        // GL_STENCIL_ATTACHMENT 会 自动绑定
+                        Self::attach_fbo(gl, glow::DEPTH_ATTACHMENT, depth_stencil);
                     }
                     let status = gl.check_framebuffer_status(glow::FRAMEBUFFER);
                     if status != glow::FRAMEBUFFER_COMPLETE {
@@ -322,6 +336,54 @@ impl GLCache {
         }
     }
 
+    unsafe fn attach_fbo(
+        gl: &glow::Context,
+        attachment: u32,
+        info: &super::RenderTargetAttachment,
+    ) {
+        match &info.raw {
+            hal::GLTextureInfo::NativeRenderBuffer => unreachable!(),
+            hal::GLTextureInfo::Renderbuffer(raw) => {
+                gl.framebuffer_renderbuffer(
+                    glow::FRAMEBUFFER,
+                    attachment,
+                    glow::RENDERBUFFER,
+                    Some(*raw),
+                );
+            }
+            hal::GLTextureInfo::Texture(raw) => match info.target {
+                glow::TEXTURE_2D => {
+                    gl.framebuffer_texture_2d(
+                        glow::FRAMEBUFFER,
+                        attachment,
+                        glow::TEXTURE_2D,
+                        Some(*raw),
+                        info.mip_level as i32,
+                    );
+                }
+                glow::TEXTURE_CUBE_MAP => {
+                    gl.framebuffer_texture_2d(
+                        glow::FRAMEBUFFER,
+                        attachment,
+                        hal::CUBEMAP_FACES[info.layer as usize],
+                        Some(*raw),
+                        info.mip_level as i32,
+                    );
+                }
+                // TEXTURE_2D_ARRAY / TEXTURE_3D
+                _ => {
+                    gl.framebuffer_texture_layer(
+                        glow::FRAMEBUFFER,
+                        attachment,
+                        Some(*raw),
+                        info.mip_level as i32,
+                        info.layer as i32,
+                    );
+                }
+            },
+        }
+    }
+
     #[inline]
     pub(crate) fn restore_current_vao(&self, gl: &glow::Context) {
         match &self.vao {
@@ -334,13 +396,31 @@ impl GLCache {
         };
     }
 
-    pub(crate) fn bind_vao(&mut self, gl: &glow::Context, geometry: &super::GeometryState) {
+    // 返回 false 表示 模拟 base_vertex 后 属性 偏移 为 负，这次 绘制 要 跳过
+    pub(crate) fn bind_vao(&mut self, gl: &glow::Context, geometry: &super::GeometryState) -> bool {
         profiling::scope!("hal::GLCache::bind_vao");
 
+        // glVertexAttribPointer 的 偏移 不能 为 负
+        for (i, attrib) in geometry.attributes.info.iter().enumerate() {
+            if let Some(attrib) = attrib {
+                let offset = Self::attrib_offset(geometry, attrib);
+                if offset < 0 {
+                    log::error!(
+                        "hal::GLCache::bind_vao: vertex attribute {} offset {} is negative, base_vertex = {}, draw is skipped",
+                        i,
+                        offset,
+                        geometry.base_vertex
+                    );
+                    return false;
+                }
+            }
+        }
+
         let hash = geometry.hash;
+        let shift = (geometry.first_instance, geometry.base_vertex);
 
-        match self.vao_map.get(&hash) {
-            Some(vao) => unsafe {
+        match self.vao_map.get_mut(&hash) {
+            Some((vao, vao_shift)) => unsafe {
                 let need_update = match &self.vao {
                     Some(v) => *v != *vao,
                     None => true,
@@ -350,13 +430,26 @@ impl GLCache {
                     gl.bind_vertex_array(Some(*vao));
                     self.vao = Some(*vao);
                 }
+
+                // 同一个 VAO 复用 于 所有 first_instance / base_vertex，变了 就 重新 设置 属性 偏移
+                if *vao_shift != shift {
+                    *vao_shift = shift;
+
+                    for (i, attrib) in geometry.attributes.info.iter().enumerate() {
+                        if let Some(attrib) = attrib {
+                            let vb = geometry.vbs[attrib.buffer_slot].as_ref().unwrap();
+                            gl.bind_buffer(glow::ARRAY_BUFFER, Some(vb.raw));
+                            Self::vertex_attrib_pointer(gl, i as u32, attrib, Self::attrib_offset(geometry, attrib));
+                        }
+                    }
+                }
             },
             None => unsafe {
                 let vao = gl.create_vertex_array().unwrap();
                 gl.bind_vertex_array(Some(vao));
 
                 // let vao = self.vao_map.insert(hash, VertexArrayAsset(vao)).unwrap();
-                self.vao_map.insert(hash, vao.clone());
+                self.vao_map.insert(hash, (vao.clone(), shift));
                 self.vao = Some(vao);
 
                 
@@ -392,7 +485,6 @@ impl GLCache {
                                     let rr = r.get_mut();
                                     if let Some(r) = rr.last() {
                                         if *r != hash {
-                                            // log::warn!("Creating new VAO: {:?}, first_instance: {:?},  len: {:?},  ib: {:?}, vbs: {:?}", hash, geometry.first_instance, rr.len(), &geometry.ib,  &vb);
                                             rr.push(hash);
                                         }
                                     }
@@ -400,34 +492,7 @@ impl GLCache {
                                 std::collections::hash_map::Entry::Vacant(r) => {r.insert(vec![hash]);},
                             };
 
-                            let mut offset = attrib.attrib_offset + vb.offset;
-                            if attrib.is_buffer_instance {
-                                offset +=  geometry.first_instance as i32 * attrib.attrib_stride;
-                            }
-
-                            // log::warn!("bind buffer: {:?}", (offset, i, attrib.attrib_stride, attrib.element_count, &attrib.attrib_kind, vb.raw));
-
-                            match attrib.attrib_kind {
-                                super::VertexAttribKind::Float => {
-                                    gl.vertex_attrib_pointer_f32(
-                                        i,
-                                        attrib.element_count,
-                                        attrib.element_format,
-                                        true, // always normalized
-                                        attrib.attrib_stride,
-                                        offset,
-                                    );
-                                }
-                                super::VertexAttribKind::Integer => {
-                                    gl.vertex_attrib_pointer_i32(
-                                        i,
-                                        attrib.element_count,
-                                        attrib.element_format,
-                                        attrib.attrib_stride,
-                                        offset,
-                                    );
-                                }
-                            }
+                            Self::vertex_attrib_pointer(gl, i, attrib, Self::attrib_offset(geometry, attrib));
 
                             // 实例化
                             let step = if attrib.is_buffer_instance { 1 } else { 0 };
@@ -437,6 +502,50 @@ impl GLCache {
                 }
             },
         }
+
+        true
+    }
+
+    // 驱动 不支持 base_instance / base_vertex 时，挪 属性 的 起始偏移 来 模拟
+    fn attrib_offset(geometry: &super::GeometryState, attrib: &super::AttributeInfo) -> i32 {
+        let vb = geometry.vbs[attrib.buffer_slot].as_ref().unwrap();
+
+        let mut offset = attrib.attrib_offset + vb.offset;
+        if attrib.is_buffer_instance {
+            offset += geometry.first_instance as i32 * attrib.attrib_stride;
+        } else {
+            offset += geometry.base_vertex * attrib.attrib_stride;
+        }
+        offset
+    }
+
+    unsafe fn vertex_attrib_pointer(
+        gl: &glow::Context,
+        i: u32,
+        attrib: &super::AttributeInfo,
+        offset: i32,
+    ) {
+        match attrib.attrib_kind {
+            super::VertexAttribKind::Float => {
+                gl.vertex_attrib_pointer_f32(
+                    i,
+                    attrib.element_count,
+                    attrib.element_format,
+                    true, // always normalized
+                    attrib.attrib_stride,
+                    offset,
+                );
+            }
+            super::VertexAttribKind::Integer => {
+                gl.vertex_attrib_pointer_i32(
+                    i,
+                    attrib.element_count,
+                    attrib.element_format,
+                    attrib.attrib_stride,
+                    offset,
+                );
+            }
+        }
     }
 
     pub(crate) fn remove_render_buffer(&mut self, gl: &glow::Context, rb: glow::Renderbuffer) {
@@ -444,21 +553,7 @@ impl GLCache {
 
         let set = self
             .fbo_map
-            .extract_if(|k, _fbo| {
-                if let super::GLTextureInfo::Renderbuffer(b) = &k.colors {
-                    if *b == rb {
-                        return true;
-                    }
-                }
-
-                if let Some(super::GLTextureInfo::Renderbuffer(b)) = k.depth_stencil.as_ref() {
-                    if *b == rb {
-                        return true;
-                    }
-                }
-
-                return false;
-            })
+            .extract_if(|k, _fbo| k.contains(&super::GLTextureInfo::Renderbuffer(rb)))
             .map(|(_, v)| v)
             .collect::<XHashSet<_>>();
 
@@ -474,21 +569,7 @@ impl GLCache {
 
         let set = self
             .fbo_map
-            .extract_if(|k, _| {
-                if let super::GLTextureInfo::Texture(t) = &k.colors {
-                    if *t == texture {
-                        return true;
-                    }
-                }
-
-                if let Some(super::GLTextureInfo::Texture(t)) = k.depth_stencil.as_ref() {
-                    if *t == texture {
-                        return true;
-                    }
-                }
-
-                return false;
-            })
+            .extract_if(|k, _| k.contains(&super::GLTextureInfo::Texture(texture)))
             .map(|(_, v)| v)
             .collect::<XHashSet<_>>();
 
@@ -509,7 +590,7 @@ impl GLCache {
         if bind_target == glow::ARRAY_BUFFER || bind_target == glow::ELEMENT_ARRAY_BUFFER {
             if let Some(r) = self.buffer_vao_map.remove(&buffer) {
                 for hash in r.into_iter() {
-                    if let Some(vao) = self.vao_map.get(&hash) {
+                    if let Some((vao, _)) = self.vao_map.get(&hash) {
                         unsafe {
                             // log::warn!("delete VAO: {:?}, buffer: {:?}", hash, &buffer);
                             gl.delete_vertex_array(*vao);
@@ -526,7 +607,7 @@ impl GLCache {
                 }
             }
         } else {
-            unreachable!();
+            // COPY_READ_BUFFER / COPY_WRITE_BUFFER 不会 绑定到 VAO，没有 缓存
         }
 
         // let set: XHashSet<glow::VertexArray> = if bind_target == glow::ARRAY_BUFFER {
@@ -585,6 +666,7 @@ pub(crate) struct GeometryState {
     pub(crate) vbs: Box<[Option<VBState>]>, // 长度 为 attributes.vb_count
     pub(crate) ib: Option<glow::Buffer>,
     pub(crate) first_instance: u32,
+    pub(crate) base_vertex: i32,
     pub(crate) hash: u64,
 }
 impl GeometryState {
@@ -592,18 +674,20 @@ impl GeometryState {
         vbs: Box<[Option<VBState>]>,
         ib: Option<glow::Buffer>,
         first_instance: u32,
+        base_vertex: i32,
     ) -> Self {
         let mut state = DefaultHasher::default();
         attributes.hash(&mut state);
         vbs.hash(&mut state);
         ib.hash(&mut state);
-        first_instance.hash(&mut state);
+        // first_instance / base_vertex 不参与 hash，VAO 只 重新 设置 属性 偏移，避免 每个 值 建 一个 VAO
 
         Self {
             attributes,
             vbs,
             ib,
             first_instance,
+            base_vertex,
             hash: state.finish()
         }
     }
@@ -619,6 +703,7 @@ pub(crate) struct ShaderInner {
     pub(crate) raw: glow::Shader,
     pub(crate) shader_type: u32, // glow::VERTEX_SHADER,
     pub(crate) bg_set_info: Box<[Box<[super::PiBindEntry]>]>,
+    pub(crate) push_constant_items: Box<[super::PushConstantItem]>,
 }
 
 const CLEAR_DURATION: u64 = 20;
@@ -202,9 +202,9 @@ pub(super) fn describe_texture_format(
         Tf::Rg16Float => (glow::RG16F, glow::RG, glow::HALF_FLOAT),
         Tf::Rgba8Unorm => (glow::RGBA8, glow::RGBA, glow::UNSIGNED_BYTE),
         Tf::Rgba8UnormSrgb => (glow::SRGB8_ALPHA8, glow::RGBA, glow::UNSIGNED_BYTE),
-        Tf::Bgra8UnormSrgb => (glow::SRGB8_ALPHA8, glow::BGRA, glow::UNSIGNED_BYTE), //TODO?
+        Tf::Bgra8UnormSrgb => (glow::SRGB8_ALPHA8, glow::BGRA, glow::UNSIGNED_BYTE), // GLES 上传 时 交换 成 RGBA
         Tf::Rgba8Snorm => (glow::RGBA8_SNORM, glow::RGBA, glow::BYTE),
-        Tf::Bgra8Unorm => (glow::RGBA8, glow::BGRA, glow::UNSIGNED_BYTE), //TODO?
+        Tf::Bgra8Unorm => (glow::RGBA8, glow::BGRA, glow::UNSIGNED_BYTE), // GLES 上传 时 交换 成 RGBA
         Tf::Rgba8Uint => (glow::RGBA8UI, glow::RGBA_INTEGER, glow::UNSIGNED_BYTE),
         Tf::Rgba8Sint => (glow::RGBA8I, glow::RGBA_INTEGER, glow::BYTE),
         Tf::Rgb10a2Uint => (
@@ -389,6 +389,15 @@ pub(crate) fn map_address_mode(mode: wgt::AddressMode) -> u32 {
     }
 }
 
+#[inline]
+pub(crate) fn map_storage_access(access: wgt::StorageTextureAccess) -> u32 {
+    match access {
+        wgt::StorageTextureAccess::ReadOnly => glow::READ_ONLY,
+        wgt::StorageTextureAccess::WriteOnly => glow::WRITE_ONLY,
+        wgt::StorageTextureAccess::ReadWrite => glow::READ_WRITE,
+    }
+}
+
 pub(super) fn map_texture_format(
     texture_format: wgt::TextureFormat,
 ) -> super::TextureFormatDesc {
@@ -419,9 +428,9 @@ pub(super) fn map_texture_format(
         Tf::Rg16Float => (glow::RG16F, glow::RG, glow::HALF_FLOAT),
         Tf::Rgba8Unorm => (glow::RGBA8, glow::RGBA, glow::UNSIGNED_BYTE),
         Tf::Rgba8UnormSrgb => (glow::SRGB8_ALPHA8, glow::RGBA, glow::UNSIGNED_BYTE),
-        Tf::Bgra8UnormSrgb => (glow::SRGB8_ALPHA8, glow::BGRA, glow::UNSIGNED_BYTE), //TODO?
+        Tf::Bgra8UnormSrgb => (glow::SRGB8_ALPHA8, glow::BGRA, glow::UNSIGNED_BYTE), // GLES 上传 时 交换 成 RGBA
         Tf::Rgba8Snorm => (glow::RGBA8_SNORM, glow::RGBA, glow::BYTE),
-        Tf::Bgra8Unorm => (glow::RGBA8, glow::BGRA, glow::UNSIGNED_BYTE), //TODO?
+        Tf::Bgra8Unorm => (glow::RGBA8, glow::BGRA, glow::UNSIGNED_BYTE), // GLES 上传 时 交换 成 RGBA
         Tf::Rgba8Uint => (glow::RGBA8UI, glow::RGBA_INTEGER, glow::UNSIGNED_BYTE),
         Tf::Rgba8Sint => (glow::RGBA8I, glow::RGBA_INTEGER, glow::BYTE),
         Tf::Rgb10a2Unorm => (
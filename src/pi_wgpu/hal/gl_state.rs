@@ -17,7 +17,7 @@ use naga::{
 use pi_share::{Share, ShareCell, ShareWeak};
 
 use super::{
-    super::{hal, wgt, BufferSize, ColorWrites}, gl_cache::GLCache, gl_conv as conv, PiBindingType, PrivateCapabilities, ShaderID, VertexAttribKind
+    super::{hal, wgt, BufferSize}, gl_cache::GLCache, gl_conv as conv, PiBindingType, PrivateCapabilities, ShaderID, VertexAttribKind
 };
 
 #[derive(Clone)]
@@ -34,8 +34,8 @@ impl std::fmt::Debug for GLState {
 
 impl GLState {
     #[inline]
-    pub(crate) fn new(gl: &glow::Context) -> Self {
-        let imp = GLStateImpl::new(&gl);
+    pub(crate) fn new(gl: &glow::Context, private_caps: PrivateCapabilities) -> Self {
+        let imp = GLStateImpl::new(&gl, private_caps);
 
         Self {
             is_ios18: imp.is_ios18,
@@ -222,7 +222,10 @@ impl GLState {
     }
 
     #[inline]
-    pub(crate) fn get_or_insert_bs(&self, bs: super::BlendStateImpl) -> Share<super::BlendState> {
+    pub(crate) fn get_or_insert_bs(
+        &self,
+        bs: Box<[Option<super::BlendStateImpl>]>,
+    ) -> Share<super::BlendState> {
         // log::trace!(
         //     "========== GLState::get_or_insert_bs lock, thread_id = {:?}",
         //     thread::current().id()
@@ -247,7 +250,14 @@ impl GLState {
         gl: &glow::Context,
         vs_id: ShaderID,
         fs_id: ShaderID,
-    ) -> Result<(glow::Program, Box<[Box<[super::PiBindEntry]>]>), super::ShaderError> {
+    ) -> Result<
+        (
+            glow::Program,
+            Box<[Box<[super::PiBindEntry]>]>,
+            Box<[super::PushConstantDesc]>,
+        ),
+        super::ShaderError,
+    > {
         // log::trace!(
         //     "========== GLState::create_program lock, thread_id = {:?}",
         //     thread::current().id()
@@ -266,6 +276,23 @@ impl GLState {
         r
     }
 
+    #[inline]
+    pub(crate) fn create_compute_program(
+        &self,
+        gl: &glow::Context,
+        cs_id: ShaderID,
+    ) -> Result<
+        (
+            glow::Program,
+            Box<[Box<[super::PiBindEntry]>]>,
+            Box<[super::PushConstantDesc]>,
+        ),
+        super::ShaderError,
+    > {
+        let s = self.imp.as_ref().borrow();
+        s.create_compute_program(gl, cs_id)
+    }
+
     #[inline]
     pub(crate) fn compile_shader(
         &self,
@@ -278,7 +305,7 @@ impl GLState {
         entry_point: String,
         multiview: Option<std::num::NonZeroU32>,
         naga_options: &naga::back::glsl::Options,
-    ) -> Result<(), super::ShaderError> {
+    ) -> Result<ShaderID, super::ShaderError> {
         // log::trace!(
         //     "========== GLState::compile_shader lock, thread_id = {:?}",
         //     thread::current().id()
@@ -466,6 +493,118 @@ impl GLState {
         // );
     }
 
+    #[inline]
+    pub(crate) fn get_buffer_sub_data(
+        &self,
+        gl: &glow::Context,
+        buffer: &super::BufferImpl,
+        offset: i32,
+        data: &mut [u8],
+    ) -> bool {
+        profiling::scope!("hal::GLState::get_buffer_sub_data");
+
+        {
+            let imp = &mut self.imp.as_ref().borrow();
+            imp.get_buffer_sub_data(gl, buffer, offset, data)
+        }
+    }
+
+    #[inline]
+    pub(crate) fn resolve_color(&self, gl: &glow::Context, index: u32, target: &super::TextureView) {
+        profiling::scope!("hal::GLState::resolve_color");
+
+        {
+            let imp = &mut self.imp.as_ref().borrow();
+            imp.resolve_color(gl, index, target)
+        }
+    }
+
+    #[inline]
+    pub(crate) fn copy_buffer_to_buffer(
+        &self,
+        gl: &glow::Context,
+        src: &super::Buffer,
+        src_offset: i32,
+        dst: &super::Buffer,
+        dst_offset: i32,
+        size: i32,
+    ) {
+        profiling::scope!("hal::GLState::copy_buffer_to_buffer");
+
+        {
+            let imp = &mut self.imp.as_ref().borrow();
+            imp.copy_buffer_to_buffer(gl, src, src_offset, dst, dst_offset, size)
+        }
+    }
+
+    #[inline]
+    pub(crate) fn copy_buffer_to_texture(
+        &self,
+        gl: &glow::Context,
+        private_caps: PrivateCapabilities,
+        src: &super::CopyBuffer,
+        dst: &super::CopyTexture,
+        size: &wgt::Extent3d,
+    ) {
+        profiling::scope!("hal::GLState::copy_buffer_to_texture");
+
+        {
+            let imp = &mut self.imp.as_ref().borrow_mut();
+            imp.copy_buffer_to_texture(gl, private_caps, src, dst, size)
+        }
+    }
+
+    #[inline]
+    pub(crate) fn copy_texture_to_buffer(
+        &self,
+        gl: &glow::Context,
+        private_caps: PrivateCapabilities,
+        src: &super::CopyTexture,
+        dst: &super::CopyBuffer,
+        size: &wgt::Extent3d,
+    ) {
+        profiling::scope!("hal::GLState::copy_texture_to_buffer");
+
+        {
+            let imp = &mut self.imp.as_ref().borrow();
+            imp.copy_texture_to_buffer(gl, private_caps, src, dst, size)
+        }
+    }
+
+    #[inline]
+    pub(crate) fn copy_texture_to_texture(
+        &self,
+        gl: &glow::Context,
+        src: &super::CopyTexture,
+        dst: &super::CopyTexture,
+        size: &wgt::Extent3d,
+    ) {
+        profiling::scope!("hal::GLState::copy_texture_to_texture");
+
+        {
+            let imp = &mut self.imp.as_ref().borrow_mut();
+            imp.copy_texture_to_texture(gl, src, dst, size)
+        }
+    }
+
+    #[inline]
+    pub(crate) fn resolve_query_set(
+        &self,
+        gl: &glow::Context,
+        private_caps: PrivateCapabilities,
+        query_set: &super::QuerySet,
+        range: std::ops::Range<u32>,
+        dst: &super::Buffer,
+        dst_offset: i32,
+    ) {
+        profiling::scope!("hal::GLState::resolve_query_set");
+
+        {
+            let imp = &mut self.imp.as_ref().borrow();
+            imp.resolve_query_set(gl, private_caps, query_set, range, dst, dst_offset)
+        }
+    }
+
     #[inline]
     pub(crate) fn set_render_pipeline(&self, gl: &glow::Context, pipeline: &super::RenderPipeline) {
         profiling::scope!("hal::GLState::set_render_pipeline");
@@ -486,11 +625,72 @@ impl GLState {
         // );
     }
 
+    #[inline]
+    pub(crate) fn reset_bindings(&self) {
+        profiling::scope!("hal::GLState::reset_bindings");
+
+        {
+            let imp = &mut self.imp.as_ref().borrow_mut();
+            imp.reset_bindings();
+        }
+    }
+
+    #[inline]
+    pub(crate) fn set_push_constants(&self, offset: u32, data: &[u32]) {
+        profiling::scope!("hal::GLState::set_push_constants");
+
+        {
+            let imp = &mut self.imp.as_ref().borrow_mut();
+            imp.set_push_constants(offset, data);
+        }
+    }
+
+    #[inline]
+    pub(crate) fn set_compute_pipeline(&self, gl: &glow::Context, pipeline: &super::ComputePipeline) {
+        profiling::scope!("hal::GLState::set_compute_pipeline");
+
+        {
+            let imp = &mut self.imp.as_ref().borrow_mut();
+            imp.set_compute_pipeline(gl, pipeline);
+        }
+    }
+
+    #[inline]
+    pub(crate) fn dispatch(
+        &self,
+        gl: &glow::Context,
+        private_caps: PrivateCapabilities,
+        count: [u32; 3],
+    ) {
+        profiling::scope!("hal::GLState::dispatch");
+
+        {
+            let imp = &mut self.imp.as_ref().borrow_mut();
+            imp.dispatch(gl, private_caps, count);
+        }
+    }
+
+    #[inline]
+    pub(crate) fn dispatch_indirect(
+        &self,
+        gl: &glow::Context,
+        private_caps: PrivateCapabilities,
+        buffer: &super::Buffer,
+        offset: i32,
+    ) {
+        profiling::scope!("hal::GLState::dispatch_indirect");
+
+        {
+            let imp = &mut self.imp.as_ref().borrow_mut();
+            imp.dispatch_indirect(gl, private_caps, buffer, offset);
+        }
+    }
+
     #[inline]
     pub(crate) fn set_render_target(
         &self,
         gl: &glow::Context,
-        desc: &super::super::RenderPassDescriptor,
+        desc: &super::RenderPassDesc,
     ) {
         profiling::scope!("hal::GLState::set_render_target");
 
@@ -515,7 +715,7 @@ impl GLState {
     pub(crate) fn set_bind_group(
         &self,
         index: u32,
-        bind_group: &super::BindGroup,
+        contents: &[super::RawBinding],
         dynamic_offsets: &[wgt::DynamicOffset],
     ) {
         profiling::scope!("hal::GLState::set_bind_group");
@@ -528,7 +728,7 @@ impl GLState {
         {
             let imp = &mut self.imp.as_ref().borrow_mut();
 
-            imp.set_bind_group(index, bind_group, dynamic_offsets);
+            imp.set_bind_group(index, contents, dynamic_offsets);
         }
 
         // log::trace!(
@@ -626,8 +826,10 @@ impl GLState {
     pub(crate) fn draw_indexed(
         &self,
         gl: &glow::Context,
+        private_caps: PrivateCapabilities,
         start_index: i32,
         index_count: i32,
+        base_vertex: i32,
         first_instance: u32,
         instance_count: i32,
     ) {
@@ -641,7 +843,15 @@ impl GLState {
         {
             let imp = &mut self.imp.as_ref().borrow_mut();
 
-            imp.draw_indexed(gl, start_index, index_count, first_instance, instance_count);
+            imp.draw_indexed(
+                gl,
+                private_caps,
+                start_index,
+                index_count,
+                base_vertex,
+                first_instance,
+                instance_count,
+            );
         }
 
         // log::trace!(
@@ -650,6 +860,40 @@ impl GLState {
         // );
     }
 
+    #[inline]
+    pub(crate) fn draw_indirect(
+        &self,
+        gl: &glow::Context,
+        private_caps: PrivateCapabilities,
+        buffer: &super::Buffer,
+        offset: i32,
+    ) {
+        profiling::scope!("hal::GLState::draw_indirect");
+
+        {
+            let imp = &mut self.imp.as_ref().borrow_mut();
+
+            imp.draw_indirect(gl, private_caps, buffer, offset);
+        }
+    }
+
+    #[inline]
+    pub(crate) fn draw_indexed_indirect(
+        &self,
+        gl: &glow::Context,
+        private_caps: PrivateCapabilities,
+        buffer: &super::Buffer,
+        offset: i32,
+    ) {
+        profiling::scope!("hal::GLState::draw_indexed_indirect");
+
+        {
+            let imp = &mut self.imp.as_ref().borrow_mut();
+
+            imp.draw_indexed_indirect(gl, private_caps, buffer, offset);
+        }
+    }
+
     #[inline]
     pub(crate) fn set_viewport(&self, gl: &glow::Context, x: i32, y: i32, w: i32, h: i32) {
         profiling::scope!("hal::GLState::set_viewport");
@@ -768,10 +1012,14 @@ pub(crate) struct GLStateImpl {
     max_textures_slots: usize,          // glow::MAX_TEXTURE_IMAGE_UNITS
     max_color_attachments: usize,       // glow::MAX_COLOR_ATTACHMENTS
     max_uniform_buffer_bindings: usize, // glow::MAX_UNIFORM_BUFFER_BINDINGS 同时帮到Program的UBO的最大数量
+    max_storage_buffer_bindings: usize, // glow::MAX_SHADER_STORAGE_BUFFER_BINDINGS，不支持 时 为 0
+    max_image_units: usize,             // glow::MAX_IMAGE_UNITS，不支持 时 为 0
 
     // 全局 GL 状态
     // VAO = render_pipeline.attributes + vertex_buffers + index_buffer
     render_pipeline: Option<super::RenderPipeline>,
+    // compute 通道 中 当前的 管线；和 render_pipeline 同时 只有 一个 有值
+    compute_pipeline: Option<super::ComputePipeline>,
     vertex_buffers: Box<[Option<VBState>]>, // 长度 不会 超过 max_attribute_slots
     index_buffer: Option<IBState>,
 
@@ -794,6 +1042,14 @@ pub(crate) struct GLStateImpl {
     // 长度 不会 超过 max_uniform_buffer_bindings
     ubos: Box<[Option<UBOState>]>,
 
+    // 长度 不会 超过 max_storage_buffer_bindings
+    ssbos: Box<[Option<UBOState>]>,
+
+    // 长度 不会 超过 max_image_units
+    images: Box<[Option<ImageState>]>,
+    // 可写 的 storage texture，每次 draw / dispatch 都 算 写入 了 一次
+    writable_images: Box<[Option<ShareWeak<super::TextureImpl>>]>,
+
     active_texture_unit: u32, // 当前激活的纹理单元
 
     // 长度 不会 超过 max_textures_slots
@@ -805,6 +1061,37 @@ pub(crate) struct GLStateImpl {
     >,
 
     group_dirty: usize,
+
+    // push constant 的 数据，按 u32 存；draw 时 上传 到 当前 program 的 uniform
+    push_constants: [u32; super::MAX_PUSH_CONSTANTS],
+    // 数据 或 program 变了，下次 draw 要 重新 上传
+    push_constants_dirty: bool,
+
+    // 渲染目标 是 默认帧缓冲：不 翻转 y，视口 / 裁剪 的 原点 在 左下角
+    is_native_target: bool,
+    // 当前 渲染目标 的 高度，翻转 视口 / 裁剪 用
+    target_height: i32,
+    // 渲染目标 或 program 变了，下次 draw 要 重新 设置 y 翻转 和 正面 绕序
+    y_flip_dirty: bool,
+
+    // 桌面 GL 要 切换 FRAMEBUFFER_SRGB：离屏 打开，sRGB 附件 才 编码；默认帧缓冲 关闭
+    toggle_framebuffer_srgb: bool,
+    is_framebuffer_srgb: bool,
+
+    // 纹理对象 上 当前 的 视图参数，只有 绑定 时 换了 视图 才 重新 设置
+    texture_params: XHashMap<glow::Texture, TextureParams>,
+}
+
+// EXT_texture_sRGB_decode
+const TEXTURE_SRGB_DECODE_EXT: u32 = 0x8A48;
+const DECODE_EXT: u32 = 0x8A49;
+const SKIP_DECODE_EXT: u32 = 0x8A4A;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct TextureParams {
+    base_level: u32,
+    max_level: u32,
+    srgb_decode: Option<bool>,
 }
 
 
@@ -815,9 +1102,19 @@ struct UBOState {
     size: i32,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct ImageState {
+    texture: glow::Texture,
+    level: u32,
+    layered: bool,
+    layer: u32,
+    access: u32,
+    format: u32,
+}
+
 impl GLStateImpl {
 
-    fn new(gl: &glow::Context) -> Self {
+    fn new(gl: &glow::Context, private_caps: PrivateCapabilities) -> Self {
         // 一个 Program 能同时接受的 UBO 绑定的个数
         // PC Chrome 浏览器 24
         // MAX_VERTEX_UNIFORM_BLOCKS / MAX_FRAGMENT_UNIFORM_BLOCKS 各 12 个
@@ -836,10 +1133,32 @@ impl GLStateImpl {
 
         // 一个 FS 能接受的最多 颜色 Attachement 的 数量
         // PC Chrome 浏览器 8
-        let max_color_attachments =
-            unsafe { gl.get_parameter_i32(glow::MAX_COLOR_ATTACHMENTS) as usize };
+        // glDrawBuffers 的 数量 可能 更少
+        let max_color_attachments = unsafe {
+            gl.get_parameter_i32(glow::MAX_COLOR_ATTACHMENTS)
+                .min(gl.get_parameter_i32(glow::MAX_DRAW_BUFFERS)) as usize
+        }
+        .min(super::MAX_COLOR_ATTACHMENTS);
 
-        let cache = GLCache::new(max_uniform_buffer_bindings, max_textures_slots);
+        // SSBO / image 单元：GLES 3.1 / GL 4.3 才有，WebGL2 没有
+        let (max_storage_buffer_bindings, max_image_units) =
+            if private_caps.contains(PrivateCapabilities::SHADER_STORAGE) {
+                unsafe {
+                    (
+                        gl.get_parameter_i32(glow::MAX_SHADER_STORAGE_BUFFER_BINDINGS) as usize,
+                        gl.get_parameter_i32(glow::MAX_IMAGE_UNITS) as usize,
+                    )
+                }
+            } else {
+                (0, 0)
+            };
+
+        let cache = GLCache::new(
+            max_uniform_buffer_bindings,
+            max_textures_slots,
+            max_storage_buffer_bindings,
+            max_image_units,
+        );
 
         let is_depth_test_enable = false;
         Self::apply_depth_test_enable(gl, is_depth_test_enable);
@@ -848,6 +1167,8 @@ impl GLStateImpl {
         Self::apply_stencil_test_enable(gl, is_stencil_test_enable);
 
         let ubos = vec![None; max_uniform_buffer_bindings];
+        let ssbos = vec![None; max_storage_buffer_bindings];
+        let images = vec![None; max_image_units];
         let textures = vec![(None, None); max_textures_slots];
 
         #[allow(unused_mut)]
@@ -866,12 +1187,15 @@ impl GLStateImpl {
 
             max_attribute_slots,
             max_uniform_buffer_bindings,
+            max_storage_buffer_bindings,
+            max_image_units,
             max_textures_slots,
             max_color_attachments,
 
             cache,
 
             render_pipeline: None,
+            compute_pipeline: None,
             vertex_buffers: vec![None; max_attribute_slots].into_boxed_slice(),
 
             index_buffer: None,
@@ -892,8 +1216,23 @@ impl GLStateImpl {
             stencil_ref: 0,
 
             ubos: ubos.into_boxed_slice(),
+            ssbos: ssbos.into_boxed_slice(),
+            writable_images: vec![None; images.len()].into_boxed_slice(),
+            images: images.into_boxed_slice(),
             textures: textures.into_boxed_slice(),
             group_dirty: 0,
+
+            push_constants: [0; super::MAX_PUSH_CONSTANTS],
+            push_constants_dirty: false,
+
+            is_native_target: false,
+            target_height: 0,
+            y_flip_dirty: false,
+
+            toggle_framebuffer_srgb: private_caps.contains(PrivateCapabilities::FRAMEBUFFER_SRGB),
+            is_framebuffer_srgb: false,
+
+            texture_params: XHashMap::default(),
         }
     }
 
@@ -904,6 +1243,11 @@ impl GLStateImpl {
         self.is_stencil_test_enable = false;
         Self::apply_stencil_test_enable(gl, self.is_stencil_test_enable);
 
+        if self.toggle_framebuffer_srgb {
+            self.is_framebuffer_srgb = false;
+            unsafe { gl.disable(glow::FRAMEBUFFER_SRGB) };
+        }
+
         // 重置清屏颜色
         let clear_color = wgt::Color::default();
         if self.clear_color != clear_color {
@@ -955,9 +1299,16 @@ impl GLStateImpl {
         for i in self.ubos.iter_mut() {
             *i = None;
         }
+        for i in self.ssbos.iter_mut() {
+            *i = None;
+        }
+        for i in self.images.iter_mut() {
+            *i = None;
+        }
        
         
         self.render_pipeline = None;
+        self.compute_pipeline = None;
         self.index_buffer = None;
         self.active_texture_unit = 0;
     }
@@ -990,93 +1341,1109 @@ impl GLStateImpl {
 
             self.cache.restore_current_vao(gl);
         }
+
+        buffer.write_shadow(offset, data);
     }
 
-    fn set_render_pipeline(&mut self, gl: &glow::Context, pipeline: &super::RenderPipeline) {
-        if self.render_pipeline.is_none() {
-            // 旧的没有，全部设置
-            profiling::scope!("hal::GLState::apply_render_pipeline");
+    // 回读 失败（GLES 3.0 的 glMapBufferRange 返回 空指针）时 返回 false
+    #[inline]
+    fn get_buffer_sub_data(
+        &self,
+        gl: &glow::Context,
+        buffer: &super::BufferImpl,
+        offset: i32,
+        data: &mut [u8],
+    ) -> bool {
+        let has_get_buffer_sub_data = buffer
+            .adapter
+            .private_caps()
+            .contains(PrivateCapabilities::GET_BUFFER_SUB_DATA);
+
+        let is_read = unsafe {
+            gl.bind_vertex_array(None);
 
-            let new = pipeline.0.as_ref();
+            gl.bind_buffer(buffer.gl_target, Some(buffer.raw));
 
-            Self::apply_alpha_to_coverage(gl, new.alpha_to_coverage_enabled);
-            Self::apply_color_mask(gl, &new.color_writes);
+            let is_read = if has_get_buffer_sub_data {
+                gl.get_buffer_sub_data(buffer.gl_target, offset, data);
+                true
+            } else {
+                // GLES 3.0 没有 glGetBufferSubData，用 glMapBufferRange 读
+                let ptr = gl.map_buffer_range(
+                    buffer.gl_target,
+                    offset,
+                    data.len() as i32,
+                    glow::MAP_READ_BIT,
+                );
+                if ptr.is_null() {
+                    log::error!(
+                        "hal::GLState::get_buffer_sub_data: glMapBufferRange failed, buffer{:?}",
+                        buffer.raw
+                    );
+                    false
+                } else {
+                    std::ptr::copy_nonoverlapping(ptr, data.as_mut_ptr(), data.len());
+                    gl.unmap_buffer(buffer.gl_target);
+                    true
+                }
+            };
 
-            Self::apply_program(gl, Some(&new.program));
+            self.cache.restore_current_vao(gl);
 
-            Self::apply_raster(gl, &new.rs.imp);
-            Self::apply_depth(gl, &new.ds.imp);
-            Self::apply_stencil(gl, self.stencil_ref, &new.ss.imp);
-            Self::apply_blend(gl, &new.bs.imp);
-        } else {
-            // 有旧的，比较 Arc 指针
+            is_read
+        };
 
-            let old = self.render_pipeline.as_ref().unwrap();
-            if Share::ptr_eq(&pipeline.0, &old.0) {
-                return;
+        is_read
+    }
+
+    // GPU 写了 buffer 的 [offset, offset + len) 后，从 GPU 回读，同步 CPU 端拷贝
+    fn sync_shadow(&self, gl: &glow::Context, buffer: &super::BufferImpl, offset: i32, len: i32) {
+        if buffer.has_shadow() {
+            let mut data = vec![0; len as usize];
+            if self.get_buffer_sub_data(gl, buffer, offset, &mut data) {
+                buffer.write_shadow(offset, &data);
             }
+        }
+    }
 
-            let new = pipeline.0.as_ref();
-            let old = old.0.as_ref();
+    // 通道 结束时，当前 绑定的 FRAMEBUFFER 还是 多重采样 的 渲染目标
+    // 用 glBlitFramebuffer 解析到 resolve_target
+    fn resolve_color(&self, gl: &glow::Context, index: u32, target: &super::TextureView) {
+        let texture = target.inner.as_ref();
 
-            if new.alpha_to_coverage_enabled != old.alpha_to_coverage_enabled {
-                Self::apply_alpha_to_coverage(gl, new.alpha_to_coverage_enabled);
-            }
+        let mip_level = target.mip_levels.start;
+        let width = (texture.copy_size.width >> mip_level).max(1) as i32;
+        let height = (texture.copy_size.height >> mip_level).max(1) as i32;
 
-            if new.color_writes != old.color_writes {
-                Self::apply_color_mask(gl, &new.color_writes);
-            }
+        // 窗口表面 直接 解析到 默认 帧缓冲
+        let draw_fbo = match texture.inner {
+            super::TextureInner::NativeRenderBuffer => None,
+            _ => Some(unsafe { gl.create_framebuffer().unwrap() }),
+        };
 
-            if new.program.get_raw() != old.program.get_raw() {
-                Self::apply_program(gl, Some(&new.program));
-            }
+        let render_fbo = unsafe { gl.get_parameter_framebuffer(glow::READ_FRAMEBUFFER_BINDING) };
 
-            if !Share::ptr_eq(&new.rs, &old.rs) {
-                Self::set_raster(gl, &new.rs.imp, &old.rs.imp);
+        unsafe {
+            gl.read_buffer(glow::COLOR_ATTACHMENT0 + index);
+            gl.bind_framebuffer(glow::DRAW_FRAMEBUFFER, draw_fbo);
+        }
+
+        if draw_fbo.is_some() {
+            Self::attach_copy_texture(
+                gl,
+                glow::DRAW_FRAMEBUFFER,
+                glow::COLOR_ATTACHMENT0,
+                texture,
+                mip_level,
+                target.array_layers.start,
+            );
+        }
+
+        // blit 受 裁剪测试 影响
+        let is_scissor = unsafe { gl.is_enabled(glow::SCISSOR_TEST) };
+        unsafe {
+            gl.disable(glow::SCISSOR_TEST);
+
+            gl.blit_framebuffer(
+                0,
+                0,
+                width,
+                height,
+                0,
+                0,
+                width,
+                height,
+                glow::COLOR_BUFFER_BIT,
+                glow::NEAREST,
+            );
+        }
+        #[cfg(all(target_arch = "wasm32", feature = "geterror"))]
+        unsafe {
+            match gl.get_error() {
+                glow::NO_ERROR => {}
+                err => log::error!("OpenGL error: {:?}", err),
             }
+        }
 
-            if !Share::ptr_eq(&new.ds, &old.ds) {
-                Self::set_depth(gl, &new.ds.imp, &old.ds.imp);
+        unsafe {
+            if is_scissor {
+                gl.enable(glow::SCISSOR_TEST);
             }
 
-            if !Share::ptr_eq(&new.ss, &old.ss) {
-                Self::set_stencil(gl, self.stencil_ref, &new.ss.imp, &old.ss.imp);
+            // READ_BUFFER 是 fbo 的 状态，恢复，否则 之后 从 这个 fbo 回读 的 是 别的 附件
+            if index != 0 {
+                gl.read_buffer(glow::COLOR_ATTACHMENT0);
             }
 
-            if !Share::ptr_eq(&new.bs, &old.bs) {
-                Self::set_blend(gl, &new.bs.imp, &old.bs.imp);
+            // 渲染目标 重新 绑回 DRAW_FRAMEBUFFER，后面的 invalidate_framebuffer 还要用
+            gl.bind_framebuffer(glow::DRAW_FRAMEBUFFER, render_fbo);
+            if let Some(fbo) = draw_fbo {
+                gl.delete_framebuffer(fbo);
             }
         }
-
-        self.render_pipeline = Some(pipeline.clone());
     }
 
-    // 设置 FBO，设置 Viewport & Scissor，清屏
-    fn set_render_target(&mut self, gl: &glow::Context, desc: &super::super::RenderPassDescriptor) {
-        // TODO 不支持 多目标 渲染
-        assert!(desc.color_attachments.len() == 1);
+    fn copy_buffer_to_buffer(
+        &self,
+        gl: &glow::Context,
+        src: &super::Buffer,
+        src_offset: i32,
+        dst: &super::Buffer,
+        dst_offset: i32,
+        size: i32,
+    ) {
+        let (src, dst) = (src.0.as_ref(), dst.0.as_ref());
+
+        // COPY_READ_BUFFER / COPY_WRITE_BUFFER 不影响 VAO 和 其他 绑定点
+        unsafe {
+            gl.bind_buffer(glow::COPY_READ_BUFFER, Some(src.raw));
+            gl.bind_buffer(glow::COPY_WRITE_BUFFER, Some(dst.raw));
+
+            gl.copy_buffer_sub_data(
+                glow::COPY_READ_BUFFER,
+                glow::COPY_WRITE_BUFFER,
+                src_offset,
+                dst_offset,
+                size,
+            );
+
+            gl.bind_buffer(glow::COPY_READ_BUFFER, None);
+            gl.bind_buffer(glow::COPY_WRITE_BUFFER, None);
+        }
+
+        self.sync_shadow(gl, dst, dst_offset, size);
+    }
+
+    // 用 PBO 上传：buffer 绑定到 PIXEL_UNPACK_BUFFER，tex_sub_image 的 数据 是 buffer 中的 偏移
+    fn copy_buffer_to_texture(
+        &mut self,
+        gl: &glow::Context,
+        private_caps: PrivateCapabilities,
+        src: &super::CopyBuffer,
+        dst: &super::CopyTexture,
+        size: &wgt::Extent3d,
+    ) {
+        let texture = dst.texture.0.as_ref();
+        let (raw, target) = match &texture.inner {
+            super::TextureInner::Texture { raw, target, .. } => (*raw, *target),
+            // COPY_DST 的 纹理 不会 创建成 Renderbuffer
+            _ => unreachable!(),
+        };
+
+        let format = texture.format;
+        let format_desc = &texture.format_desc;
+        let layout = &src.layout;
+
+        let (block_width, block_height) = format.block_dimensions();
+        let block_size = format.block_copy_size(Some(dst.aspect)).unwrap();
+
+        let block_rows = (size.height + block_height - 1) / block_height;
+        let row_bytes = (size.width + block_width - 1) / block_width * block_size;
+        let bytes_per_row = layout.bytes_per_row.unwrap_or(row_bytes);
+        let rows_per_image = layout.rows_per_image.unwrap_or(block_rows);
+        let bytes_per_image = bytes_per_row * rows_per_image;
+
+        unsafe {
+            gl.bind_buffer(glow::PIXEL_UNPACK_BUFFER, Some(src.buffer.0.raw));
+
+            gl.active_texture(glow::TEXTURE0 + self.active_texture_unit);
+            gl.bind_texture(target, Some(raw));
+
+            gl.pixel_store_i32(glow::UNPACK_ALIGNMENT, 1);
+        }
+
+        if !format.is_compressed() {
+            // 行长 / 层高 以 像素 为单位
+            unsafe {
+                gl.pixel_store_i32(
+                    glow::UNPACK_ROW_LENGTH,
+                    (bytes_per_row / block_size * block_width) as i32,
+                );
+                gl.pixel_store_i32(
+                    glow::UNPACK_IMAGE_HEIGHT,
+                    (rows_per_image * block_height) as i32,
+                );
+            }
+
+            let offset = layout.offset as u32;
+
+            // GLES 不接受 BGRA 的 像素数据：从 buffer 回读，交换 R / B 后 直接 上传
+            let swizzled = if format_desc.external == glow::BGRA
+                && !private_caps.contains(PrivateCapabilities::TEXTURE_FORMAT_BGRA)
+            {
+                let len = bytes_per_image * (size.depth_or_array_layers - 1)
+                    + bytes_per_row * (block_rows - 1)
+                    + row_bytes;
+
+                let mut data = vec![0; len as usize];
+                self.get_buffer_sub_data(gl, src.buffer.0.as_ref(), offset as i32, &mut data);
+                super::swap_red_blue(&mut data);
+                unsafe { gl.bind_buffer(glow::PIXEL_UNPACK_BUFFER, None) };
+                Some(data)
+            } else {
+                None
+            };
+            let external = match swizzled {
+                Some(_) => glow::RGBA,
+                None => format_desc.external,
+            };
+            let swizzled = swizzled.as_deref();
+            let unpack = move |start: u32| match swizzled {
+                Some(data) => glow::PixelUnpackData::Slice(&data[(start - offset) as usize..]),
+                None => glow::PixelUnpackData::BufferOffset(start),
+            };
+
+            match target {
+                glow::TEXTURE_3D | glow::TEXTURE_2D_ARRAY => unsafe {
+                    gl.tex_sub_image_3d(
+                        target,
+                        dst.mip_level as i32,
+                        dst.origin.x as i32,
+                        dst.origin.y as i32,
+                        dst.origin.z as i32,
+                        size.width as i32,
+                        size.height as i32,
+                        size.depth_or_array_layers as i32,
+                        external,
+                        format_desc.data_type,
+                        unpack(offset),
+                    );
+                },
+                glow::TEXTURE_2D | glow::TEXTURE_CUBE_MAP => {
+                    for layer in 0..size.depth_or_array_layers {
+                        let face_target = if target == glow::TEXTURE_CUBE_MAP {
+                            super::CUBEMAP_FACES[(dst.origin.z + layer) as usize]
+                        } else {
+                            target
+                        };
+
+                        unsafe {
+                            gl.tex_sub_image_2d(
+                                face_target,
+                                dst.mip_level as i32,
+                                dst.origin.x as i32,
+                                dst.origin.y as i32,
+                                size.width as i32,
+                                size.height as i32,
+                                external,
+                                format_desc.data_type,
+                                unpack(offset + layer * bytes_per_image),
+                            );
+                        }
+                    }
+                }
+                _ => unreachable!(),
+            }
+            #[cfg(all(target_arch = "wasm32", feature = "geterror"))]
+            unsafe {
+                match gl.get_error() {
+                    glow::NO_ERROR => {}
+                    err => log::error!("OpenGL error: {:?}", err),
+                }
+            }
+
+            unsafe {
+                gl.pixel_store_i32(glow::UNPACK_ROW_LENGTH, 0);
+                gl.pixel_store_i32(glow::UNPACK_IMAGE_HEIGHT, 0);
+            }
+        } else {
+            // 压缩纹理 不能用 UNPACK_ROW_LENGTH，行 有 填充 时，按 块行 逐行上传
+            let rows = if bytes_per_row == row_bytes {
+                vec![(0, block_rows)]
+            } else {
+                (0..block_rows).map(|r| (r, 1)).collect()
+            };
+
+            for layer in 0..size.depth_or_array_layers {
+                for (row, count) in rows.iter() {
+                    let y = row * block_height;
+                    let height = (count * block_height).min(size.height - y);
+
+                    let start =
+                        layout.offset as u32 + layer * bytes_per_image + row * bytes_per_row;
+                    let data = glow::CompressedPixelUnpackData::BufferRange(
+                        start..start + count * row_bytes,
+                    );
+
+                    match target {
+                        glow::TEXTURE_3D | glow::TEXTURE_2D_ARRAY => unsafe {
+                            gl.compressed_tex_sub_image_3d(
+                                target,
+                                dst.mip_level as i32,
+                                dst.origin.x as i32,
+                                (dst.origin.y + y) as i32,
+                                (dst.origin.z + layer) as i32,
+                                size.width as i32,
+                                height as i32,
+                                1,
+                                format_desc.internal,
+                                data,
+                            );
+                        },
+                        glow::TEXTURE_2D | glow::TEXTURE_CUBE_MAP => {
+                            let face_target = if target == glow::TEXTURE_CUBE_MAP {
+                                super::CUBEMAP_FACES[(dst.origin.z + layer) as usize]
+                            } else {
+                                target
+                            };
+
+                            unsafe {
+                                gl.compressed_tex_sub_image_2d(
+                                    face_target,
+                                    dst.mip_level as i32,
+                                    dst.origin.x as i32,
+                                    (dst.origin.y + y) as i32,
+                                    size.width as i32,
+                                    height as i32,
+                                    format_desc.internal,
+                                    data,
+                                );
+                            }
+                        }
+                        _ => unreachable!(),
+                    }
+                }
+            }
+        }
+
+        unsafe {
+            gl.pixel_store_i32(glow::UNPACK_ALIGNMENT, 4);
+            gl.bind_buffer(glow::PIXEL_UNPACK_BUFFER, None);
+        }
+
+        self.restore_current_texture(gl, self.active_texture_unit, target);
+    }
+
+    // 纹理 挂到 临时 fbo 上，glReadPixels 读到 PIXEL_PACK_BUFFER
+    fn copy_texture_to_buffer(
+        &self,
+        gl: &glow::Context,
+        private_caps: PrivateCapabilities,
+        src: &super::CopyTexture,
+        dst: &super::CopyBuffer,
+        size: &wgt::Extent3d,
+    ) {
+        let texture = src.texture.0.as_ref();
+        let buffer = dst.buffer.0.as_ref();
+
+        let format = texture.format;
+        let format_desc = &texture.format_desc;
+        let layout = &dst.layout;
+
+        assert!(
+            format.has_color_aspect() && !format.is_compressed(),
+            "hal::GLState::copy_texture_to_buffer: only uncompressed color formats can be read back, format = {:?}",
+            format
+        );
+
+        let block_size = format.block_copy_size(Some(src.aspect)).unwrap();
+        let row_bytes = size.width * block_size;
+        let bytes_per_row = layout.bytes_per_row.unwrap_or(row_bytes);
+        let rows_per_image = layout.rows_per_image.unwrap_or(size.height);
+        let bytes_per_image = bytes_per_row * rows_per_image;
+
+        // 窗口表面 直接 读 默认 帧缓冲
+        let fbo = match texture.inner {
+            super::TextureInner::NativeRenderBuffer => None,
+            _ => Some(unsafe { gl.create_framebuffer().unwrap() }),
+        };
+
+        // 不能 按 BGRA 读 时，按 RGBA 读到 CPU 端 后 交换 R / B，再 写回 buffer
+        let swizzle = format_desc.external == glow::BGRA
+            && !private_caps.contains(PrivateCapabilities::READ_FORMAT_BGRA);
+        let external = if swizzle {
+            glow::RGBA
+        } else {
+            format_desc.external
+        };
+
+        unsafe {
+            gl.bind_framebuffer(glow::READ_FRAMEBUFFER, fbo);
+            gl.read_buffer(if fbo.is_some() {
+                glow::COLOR_ATTACHMENT0
+            } else {
+                glow::BACK
+            });
+
+            gl.pixel_store_i32(glow::PACK_ALIGNMENT, 1);
+            gl.pixel_store_i32(glow::PACK_ROW_LENGTH, (bytes_per_row / block_size) as i32);
+
+            if !swizzle {
+                gl.bind_buffer(glow::PIXEL_PACK_BUFFER, Some(buffer.raw));
+            }
+        }
+
+        for layer in 0..size.depth_or_array_layers {
+            if fbo.is_some() {
+                Self::attach_copy_texture(
+                    gl,
+                    glow::READ_FRAMEBUFFER,
+                    glow::COLOR_ATTACHMENT0,
+                    texture,
+                    src.mip_level,
+                    src.origin.z + layer,
+                );
+            }
+
+            let offset = layout.offset as u32 + layer * bytes_per_image;
+
+            if swizzle {
+                // 最后一行 不含 填充，填充 部分 保留 buffer 原来 的 内容
+                let len = bytes_per_row * (size.height - 1) + row_bytes;
+                let mut data = vec![0; len as usize];
+                self.get_buffer_sub_data(gl, buffer, offset as i32, &mut data);
+
+                unsafe {
+                    gl.read_pixels(
+                        src.origin.x as i32,
+                        src.origin.y as i32,
+                        size.width as i32,
+                        size.height as i32,
+                        external,
+                        format_desc.data_type,
+                        glow::PixelPackData::Slice(&mut data),
+                    );
+                }
+
+                super::swap_red_blue(&mut data);
+
+                self.set_buffer_sub_data(gl, buffer, offset as i32, &data);
+            } else {
+                unsafe {
+                    gl.read_pixels(
+                        src.origin.x as i32,
+                        src.origin.y as i32,
+                        size.width as i32,
+                        size.height as i32,
+                        external,
+                        format_desc.data_type,
+                        glow::PixelPackData::BufferOffset(offset),
+                    );
+                }
+            }
+        }
+        #[cfg(all(target_arch = "wasm32", feature = "geterror"))]
+        unsafe {
+            match gl.get_error() {
+                glow::NO_ERROR => {}
+                err => log::error!("OpenGL error: {:?}", err),
+            }
+        }
+
+        unsafe {
+            gl.pixel_store_i32(glow::PACK_ROW_LENGTH, 0);
+            gl.pixel_store_i32(glow::PACK_ALIGNMENT, 4);
+
+            if !swizzle {
+                gl.bind_buffer(glow::PIXEL_PACK_BUFFER, None);
+            }
+
+            gl.bind_framebuffer(glow::READ_FRAMEBUFFER, None);
+            if let Some(fbo) = fbo {
+                gl.delete_framebuffer(fbo);
+            }
+        }
+
+        // 交换 R / B 的 路径 已经 经过 set_buffer_sub_data
+        if !swizzle {
+            let len = bytes_per_image * (size.depth_or_array_layers - 1)
+                + bytes_per_row * (size.height - 1)
+                + row_bytes;
+            self.sync_shadow(gl, buffer, layout.offset as i32, len as i32);
+        }
+    }
+
+    // 颜色 纹理 目标：glCopyTexSubImage；深度模板 或 Renderbuffer 目标：glBlitFramebuffer
+    fn copy_texture_to_texture(
+        &mut self,
+        gl: &glow::Context,
+        src: &super::CopyTexture,
+        dst: &super::CopyTexture,
+        size: &wgt::Extent3d,
+    ) {
+        let src_texture = src.texture.0.as_ref();
+        let dst_texture = dst.texture.0.as_ref();
+
+        let format = src_texture.format;
+        let attachment = Self::copy_attachment(format);
+
+        let read_fbo = match src_texture.inner {
+            super::TextureInner::NativeRenderBuffer => None,
+            _ => Some(unsafe { gl.create_framebuffer().unwrap() }),
+        };
+
+        unsafe {
+            gl.bind_framebuffer(glow::READ_FRAMEBUFFER, read_fbo);
+            if read_fbo.is_some() && attachment == glow::COLOR_ATTACHMENT0 {
+                gl.read_buffer(glow::COLOR_ATTACHMENT0);
+            }
+        }
+
+        match &dst_texture.inner {
+            super::TextureInner::Texture { raw, target, .. } if format.has_color_aspect() => {
+                let (raw, target) = (*raw, *target);
+
+                unsafe {
+                    gl.active_texture(glow::TEXTURE0 + self.active_texture_unit);
+                    gl.bind_texture(target, Some(raw));
+                }
+
+                for layer in 0..size.depth_or_array_layers {
+                    if read_fbo.is_some() {
+                        Self::attach_copy_texture(
+                            gl,
+                            glow::READ_FRAMEBUFFER,
+                            attachment,
+                            src_texture,
+                            src.mip_level,
+                            src.origin.z + layer,
+                        );
+                    }
+
+                    match target {
+                        glow::TEXTURE_2D | glow::TEXTURE_CUBE_MAP => {
+                            let face_target = if target == glow::TEXTURE_CUBE_MAP {
+                                super::CUBEMAP_FACES[(dst.origin.z + layer) as usize]
+                            } else {
+                                target
+                            };
+
+                            unsafe {
+                                gl.copy_tex_sub_image_2d(
+                                    face_target,
+                                    dst.mip_level as i32,
+                                    dst.origin.x as i32,
+                                    dst.origin.y as i32,
+                                    src.origin.x as i32,
+                                    src.origin.y as i32,
+                                    size.width as i32,
+                                    size.height as i32,
+                                );
+                            }
+                        }
+                        _ => unsafe {
+                            gl.copy_tex_sub_image_3d(
+                                target,
+                                dst.mip_level as i32,
+                                dst.origin.x as i32,
+                                dst.origin.y as i32,
+                                (dst.origin.z + layer) as i32,
+                                src.origin.x as i32,
+                                src.origin.y as i32,
+                                size.width as i32,
+                                size.height as i32,
+                            );
+                        },
+                    }
+                }
+
+                self.restore_current_texture(gl, self.active_texture_unit, target);
+            }
+            _ => {
+                let mask = if attachment == glow::COLOR_ATTACHMENT0 {
+                    glow::COLOR_BUFFER_BIT
+                } else {
+                    let mut mask = 0;
+                    if format.has_depth_aspect() {
+                        mask |= glow::DEPTH_BUFFER_BIT;
+                    }
+                    if format.has_stencil_aspect() {
+                        mask |= glow::STENCIL_BUFFER_BIT;
+                    }
+                    mask
+                };
+
+                let draw_fbo = match dst_texture.inner {
+                    super::TextureInner::NativeRenderBuffer => None,
+                    _ => Some(unsafe { gl.create_framebuffer().unwrap() }),
+                };
+
+                // blit 受 裁剪测试 影响
+                let is_scissor = unsafe { gl.is_enabled(glow::SCISSOR_TEST) };
+                unsafe {
+                    gl.disable(glow::SCISSOR_TEST);
+                    gl.bind_framebuffer(glow::DRAW_FRAMEBUFFER, draw_fbo);
+                }
+
+                for layer in 0..size.depth_or_array_layers {
+                    if read_fbo.is_some() {
+                        Self::attach_copy_texture(
+                            gl,
+                            glow::READ_FRAMEBUFFER,
+                            attachment,
+                            src_texture,
+                            src.mip_level,
+                            src.origin.z + layer,
+                        );
+                    }
+                    if draw_fbo.is_some() {
+                        Self::attach_copy_texture(
+                            gl,
+                            glow::DRAW_FRAMEBUFFER,
+                            attachment,
+                            dst_texture,
+                            dst.mip_level,
+                            dst.origin.z + layer,
+                        );
+                    }
+
+                    unsafe {
+                        gl.blit_framebuffer(
+                            src.origin.x as i32,
+                            src.origin.y as i32,
+                            (src.origin.x + size.width) as i32,
+                            (src.origin.y + size.height) as i32,
+                            dst.origin.x as i32,
+                            dst.origin.y as i32,
+                            (dst.origin.x + size.width) as i32,
+                            (dst.origin.y + size.height) as i32,
+                            mask,
+                            glow::NEAREST,
+                        );
+                    }
+                }
+
+                unsafe {
+                    gl.bind_framebuffer(glow::DRAW_FRAMEBUFFER, None);
+                    if let Some(fbo) = draw_fbo {
+                        gl.delete_framebuffer(fbo);
+                    }
+
+                    if is_scissor {
+                        gl.enable(glow::SCISSOR_TEST);
+                    }
+                }
+            }
+        }
+        #[cfg(all(target_arch = "wasm32", feature = "geterror"))]
+        unsafe {
+            match gl.get_error() {
+                glow::NO_ERROR => {}
+                err => log::error!("OpenGL error: {:?}", err),
+            }
+        }
+
+        unsafe {
+            gl.bind_framebuffer(glow::READ_FRAMEBUFFER, None);
+            if let Some(fbo) = read_fbo {
+                gl.delete_framebuffer(fbo);
+            }
+        }
+    }
+
+    // 每个 查询结果 按 u64 写到 dst_offset 开始的 位置
+    // glGetQueryObject 会 等到 结果 可用 为止
+    fn resolve_query_set(
+        &self,
+        gl: &glow::Context,
+        private_caps: PrivateCapabilities,
+        query_set: &super::QuerySet,
+        range: std::ops::Range<u32>,
+        dst: &super::Buffer,
+        dst_offset: i32,
+    ) {
+        let queries = &query_set.0.raw[range.start as usize..range.end as usize];
+        let dst = dst.0.as_ref();
+
+        // 有 QUERY_BUFFER 时 结果 直接 写到 buffer，不经过 CPU
+        if private_caps.contains(PrivateCapabilities::QUERY_BUFFERS) {
+            unsafe {
+                gl.bind_buffer(glow::QUERY_BUFFER, Some(dst.raw));
+                for (i, &query) in queries.iter().enumerate() {
+                    let offset = dst_offset as usize + i * wgt::QUERY_SIZE as usize;
+                    gl.get_query_parameter_u64_with_offset(query, glow::QUERY_RESULT, offset);
+                }
+                gl.bind_buffer(glow::QUERY_BUFFER, None);
+            }
+
+            let len = queries.len() as i32 * wgt::QUERY_SIZE as i32;
+            self.sync_shadow(gl, dst, dst_offset, len);
+            return;
+        }
+
+        let mut results = vec![0u64; queries.len()];
+        for (result, &query) in results.iter_mut().zip(queries) {
+            unsafe {
+                if private_caps.contains(PrivateCapabilities::QUERY_64BIT) {
+                    gl.get_query_parameter_u64_with_offset(
+                        query,
+                        glow::QUERY_RESULT,
+                        result as *mut u64 as usize,
+                    );
+                } else {
+                    // WebGL2 / GLES 只有 32位 的 结果
+                    *result = gl.get_query_parameter_u32(query, glow::QUERY_RESULT) as u64;
+                }
+            }
+        }
+
+        let data: Vec<u8> = results.iter().flat_map(|v| v.to_le_bytes()).collect();
+        self.set_buffer_sub_data(gl, dst, dst_offset, &data);
+    }
+
+    // 拷贝时 纹理 挂到 fbo 的 哪个 挂接点
+    fn copy_attachment(format: wgt::TextureFormat) -> u32 {
+        if format.is_combined_depth_stencil_format() {
+            glow::DEPTH_STENCIL_ATTACHMENT
+        } else if format.has_depth_aspect() {
+            glow::DEPTH_ATTACHMENT
+        } else if format.has_stencil_aspect() {
+            glow::STENCIL_ATTACHMENT
+        } else {
+            glow::COLOR_ATTACHMENT0
+        }
+    }
+
+    // 把 src 的 一层 / 一面 拷贝 到 单层视图 的 2D 纹理，layered 时 把 layers 拷贝 到 2D 数组，返回 (拷贝 的 纹理, 目标)
+    // 调用前 要 激活 目标 纹理单元，返回时 拷贝 绑定 在 它 的 TEXTURE_2D / TEXTURE_2D_ARRAY 上
+    // 颜色：glCopyTexSubImage2D / 3D；深度模板：glBlitFramebuffer
+    // src 上次 拷贝 之后 没有 写入 时，直接 用 上次 的 拷贝
+    fn update_layer_copy(
+        gl: &glow::Context,
+        src: &super::TextureImpl,
+        copy: &ShareCell<Option<(super::Texture, u32)>>,
+        mip_levels: std::ops::Range<u32>,
+        layers: std::ops::Range<u32>,
+        layered: bool,
+    ) -> (glow::Texture, u32) {
+        let version = src.version();
+
+        let mut copy = copy.borrow_mut();
+        let is_new = copy.is_none();
+        let (copy, copied_version) = copy.get_or_insert_with(|| {
+            let layer_count = layers.len() as u32;
+            (super::Texture::new_layer_copy(gl, src, mip_levels.clone(), layer_count, layered), version)
+        });
+        let (raw, target) = match &copy.0.inner {
+            super::TextureInner::Texture { raw, target, .. } => (*raw, *target),
+            _ => unreachable!(),
+        };
+
+        if !is_new && *copied_version == version {
+            unsafe { gl.bind_texture(target, Some(raw)) };
+            return (raw, target);
+        }
+        *copied_version = version;
+
+        let attachment = Self::copy_attachment(src.format);
+
+        unsafe {
+            // 渲染通道 中 也会 走到 这里，拷贝完 要 还原 fbo
+            let old_read_fbo = gl.get_parameter_framebuffer(glow::READ_FRAMEBUFFER_BINDING);
+            let old_draw_fbo = gl.get_parameter_framebuffer(glow::DRAW_FRAMEBUFFER_BINDING);
+
+            let read_fbo = gl.create_framebuffer().unwrap();
+            gl.bind_framebuffer(glow::READ_FRAMEBUFFER, Some(read_fbo));
+            gl.bind_texture(target, Some(raw));
+
+            let draw_fbo = if attachment == glow::COLOR_ATTACHMENT0 {
+                gl.read_buffer(glow::COLOR_ATTACHMENT0);
+                None
+            } else {
+                let fbo = gl.create_framebuffer().unwrap();
+                gl.bind_framebuffer(glow::DRAW_FRAMEBUFFER, Some(fbo));
+                Some(fbo)
+            };
+
+            // blit 受 裁剪测试 影响
+            let is_scissor = draw_fbo.is_some() && gl.is_enabled(glow::SCISSOR_TEST);
+            if is_scissor {
+                gl.disable(glow::SCISSOR_TEST);
+            }
+
+            for (i, mip) in mip_levels.enumerate() {
+                let width = (src.copy_size.width >> mip).max(1) as i32;
+                let height = (src.copy_size.height >> mip).max(1) as i32;
+
+                for (j, layer) in layers.clone().enumerate() {
+                    Self::attach_copy_texture(gl, glow::READ_FRAMEBUFFER, attachment, src, mip, layer);
+
+                    if draw_fbo.is_none() {
+                        if layered {
+                            gl.copy_tex_sub_image_3d(target, i as i32, 0, 0, j as i32, 0, 0, width, height);
+                        } else {
+                            gl.copy_tex_sub_image_2d(target, i as i32, 0, 0, 0, 0, width, height);
+                        }
+                    } else {
+                        if layered {
+                            gl.framebuffer_texture_layer(
+                                glow::DRAW_FRAMEBUFFER,
+                                attachment,
+                                Some(raw),
+                                i as i32,
+                                j as i32,
+                            );
+                        } else {
+                            gl.framebuffer_texture_2d(
+                                glow::DRAW_FRAMEBUFFER,
+                                attachment,
+                                target,
+                                Some(raw),
+                                i as i32,
+                            );
+                        }
+
+                        let mut mask = 0;
+                        if src.format.has_depth_aspect() {
+                            mask |= glow::DEPTH_BUFFER_BIT;
+                        }
+                        if src.format.has_stencil_aspect() {
+                            mask |= glow::STENCIL_BUFFER_BIT;
+                        }
+                        gl.blit_framebuffer(
+                            0, 0, width, height, 0, 0, width, height, mask, glow::NEAREST,
+                        );
+                    }
+                }
+            }
+
+            if is_scissor {
+                gl.enable(glow::SCISSOR_TEST);
+            }
+
+            gl.bind_framebuffer(glow::READ_FRAMEBUFFER, old_read_fbo);
+            gl.delete_framebuffer(read_fbo);
+            if let Some(fbo) = draw_fbo {
+                gl.bind_framebuffer(glow::DRAW_FRAMEBUFFER, old_draw_fbo);
+                gl.delete_framebuffer(fbo);
+            }
+        }
+        #[cfg(all(target_arch = "wasm32", feature = "geterror"))]
+        unsafe {
+            match gl.get_error() {
+                glow::NO_ERROR => {}
+                err => log::error!("OpenGL error: {:?}", err),
+            }
+        }
+
+        (raw, target)
+    }
+
+    // 当前 program 用到的 纹理 中，同一张 纹理 有 多个 不同 视图参数 的，返回 这些 纹理
+    // 能 拷贝 的 视图（只选 部分 mip 的 2D 视图）会 改为 采样 拷贝，剩下的 依然 冲突 时 报错
+    fn find_mip_conflicts(
+        bind_group_set: &[Option<BindGroupState>],
+        program: &super::ProgramImpl,
+        reorder: &[Box<[usize]>],
+    ) -> Vec<glow::Texture> {
+        // (纹理, 视图参数, 能否 拷贝)
+        let mut views: Vec<(glow::Texture, TextureParams, bool)> = vec![];
 
-        let color = desc.color_attachments[0].as_ref().unwrap();
+        for (i, bindings) in program.uniforms.iter().enumerate() {
+            let bg = match &bind_group_set[i] {
+                Some(bg) => bg,
+                None => continue,
+            };
+
+            for j in 0..bindings.len() {
+                if let RawBindingState::Texture {
+                    raw,
+                    mip_levels,
+                    srgb_decode,
+                    layer_copy: None,
+                    mip_copy,
+                    ..
+                } = &bg.bgs[reorder[i][j]]
+                {
+                    let inner = raw.upgrade().unwrap();
+                    if let hal::TextureInner::Texture { raw, .. } = &inner.inner {
+                        let params = TextureParams {
+                            base_level: mip_levels.start,
+                            max_level: mip_levels.end - 1,
+                            srgb_decode: *srgb_decode,
+                        };
+                        views.push((*raw, params, mip_copy.is_some()));
+                    }
+                }
+            }
+        }
+
+        let mut conflicts: Vec<glow::Texture> = vec![];
+        for (i, (raw, params, _)) in views.iter().enumerate() {
+            if conflicts.contains(raw) {
+                continue;
+            }
+
+            let others = &views[i + 1..];
+            if !others.iter().any(|(r, p, _)| r == raw && p != params) {
+                continue;
+            }
+            conflicts.push(*raw);
+
+            // 不能 拷贝 的 视图 之间 还有 不同 的 参数
+            let mut fixed = views
+                .iter()
+                .filter(|(r, _, can_copy)| r == raw && !can_copy)
+                .map(|(_, p, _)| p);
+            if let Some(first) = fixed.next() {
+                if fixed.any(|p| p != first) {
+                    log::error!(
+                        "texture{:?} is bound with different mip ranges / sRGB decode in one draw, only the last view is sampled correctly",
+                        raw
+                    );
+                }
+            }
+        }
+
+        conflicts
+    }
 
-        // TODO 不支持 多重采样
-        assert!(color.resolve_target.is_none());
+    // 视图 的 mip 范围 / sRGB 解码，纹理 要 已经 绑定 在 当前 纹理单元 上
+    fn apply_texture_params(gl: &glow::Context, target: u32, params: &TextureParams, is_ios18: bool) {
+        unsafe {
+            gl.tex_parameter_i32(target, glow::TEXTURE_BASE_LEVEL, params.base_level as i32);
+            // ios18 上 MAX_LEVEL 必须 保持 1000，见 Texture::new
+            if !is_ios18 {
+                gl.tex_parameter_i32(target, glow::TEXTURE_MAX_LEVEL, params.max_level as i32);
+            }
+            if let Some(decode) = params.srgb_decode {
+                let value = if decode { DECODE_EXT } else { SKIP_DECODE_EXT };
+                gl.tex_parameter_i32(target, TEXTURE_SRGB_DECODE_EXT, value as i32);
+            }
+        }
+    }
+
+    // 把 纹理 的 某个 mip / 层 挂到 fbo 上
+    fn attach_copy_texture(
+        gl: &glow::Context,
+        fbo_target: u32,
+        attachment: u32,
+        texture: &super::TextureImpl,
+        mip_level: u32,
+        layer: u32,
+    ) {
+        match &texture.inner {
+            super::TextureInner::NativeRenderBuffer => unreachable!(),
+            super::TextureInner::Renderbuffer { raw, .. } => unsafe {
+                gl.framebuffer_renderbuffer(
+                    fbo_target,
+                    attachment,
+                    glow::RENDERBUFFER,
+                    Some(*raw),
+                );
+            },
+            super::TextureInner::Texture { raw, target, .. } => match *target {
+                glow::TEXTURE_2D => unsafe {
+                    gl.framebuffer_texture_2d(
+                        fbo_target,
+                        attachment,
+                        glow::TEXTURE_2D,
+                        Some(*raw),
+                        mip_level as i32,
+                    );
+                },
+                glow::TEXTURE_CUBE_MAP => unsafe {
+                    gl.framebuffer_texture_2d(
+                        fbo_target,
+                        attachment,
+                        super::CUBEMAP_FACES[layer as usize],
+                        Some(*raw),
+                        mip_level as i32,
+                    );
+                },
+                _ => unsafe {
+                    gl.framebuffer_texture_layer(
+                        fbo_target,
+                        attachment,
+                        Some(*raw),
+                        mip_level as i32,
+                        layer as i32,
+                    );
+                },
+            },
+        }
+    }
+
+    fn set_render_pipeline(&mut self, gl: &glow::Context, pipeline: &super::RenderPipeline) {
+        // compute 之后 render_pipeline 已经 清空，下面 会 全部 重新设置
+        self.compute_pipeline = None;
+
+        if self.render_pipeline.is_none() {
+            // 旧的没有，全部设置
+            profiling::scope!("hal::GLState::apply_render_pipeline");
+
+            let new = pipeline.0.as_ref();
+
+            Self::apply_alpha_to_coverage(gl, new.alpha_to_coverage_enabled);
+
+            Self::apply_program(gl, Some(&new.program));
+
+            Self::apply_raster(gl, &new.rs.imp);
+            Self::apply_depth(gl, &new.ds.imp);
+            Self::apply_stencil(gl, self.stencil_ref, &new.ss.imp);
+            Self::apply_blend(gl, &new.bs);
+        } else {
+            // 有旧的，比较 Arc 指针
+
+            let old = self.render_pipeline.as_ref().unwrap();
+            if Share::ptr_eq(&pipeline.0, &old.0) {
+                return;
+            }
+
+            let new = pipeline.0.as_ref();
+            let old = old.0.as_ref();
+
+            if new.alpha_to_coverage_enabled != old.alpha_to_coverage_enabled {
+                Self::apply_alpha_to_coverage(gl, new.alpha_to_coverage_enabled);
+            }
+
+            if new.program.get_raw() != old.program.get_raw() {
+                Self::apply_program(gl, Some(&new.program));
+            }
+
+            if !Share::ptr_eq(&new.rs, &old.rs) {
+                Self::set_raster(gl, &new.rs.imp, &old.rs.imp);
+            }
+
+            if !Share::ptr_eq(&new.ds, &old.ds) {
+                Self::set_depth(gl, &new.ds.imp, &old.ds.imp);
+            }
+
+            if !Share::ptr_eq(&new.ss, &old.ss) {
+                Self::set_stencil(gl, self.stencil_ref, &new.ss.imp, &old.ss.imp);
+            }
+
+            if !Share::ptr_eq(&new.bs, &old.bs) {
+                Self::set_blend(gl, &new.bs, &old.bs);
+            }
+        }
+
+        // uniform 的 值 存在 program 上，换了 管线 就 重新 上传
+        self.push_constants_dirty = true;
+        self.y_flip_dirty = true;
+        self.render_pipeline = Some(pipeline.clone());
+    }
+
+    // 设置 FBO，设置 Viewport & Scissor，清屏
+    fn set_render_target(&mut self, gl: &glow::Context, desc: &super::RenderPassDesc) {
+        assert!(
+            desc.color_attachments.len() <= self.max_color_attachments,
+            "set_render_target: too many color attachments, max = {}",
+            self.max_color_attachments
+        );
 
         let (depth_stencil, depth_ops, stencil_ops) = match &desc.depth_stencil_attachment {
             None => (None, None, None),
             Some(ds) => (
-                GLTextureInfo::try_from(ds.view).ok(),
+                Some(super::RenderTargetAttachment::from(&ds.view)),
                 ds.depth_ops,
                 ds.stencil_ops,
             ),
         };
 
-        let colors: GLTextureInfo = color.view.into();
+        let colors: Box<[Option<super::RenderTargetAttachment>]> = desc
+            .color_attachments
+            .iter()
+            .map(|c| c.as_ref().map(|c| super::RenderTargetAttachment::from(&c.view)))
+            .collect();
 
         // 移除 所有 self.textures 中 含 colors 和 depth_stencil 的 纹理单元
-        self.reset_unit_texture(gl, &colors);
+        for c in colors.iter().flatten() {
+            self.reset_unit_texture(gl, &c.raw);
+        }
 
         match depth_stencil.as_ref() {
-            Some(ds) => self.reset_unit_texture(gl, ds),
+            Some(ds) => self.reset_unit_texture(gl, &ds.raw),
             None => {}
         };
 
@@ -1085,17 +2452,59 @@ impl GLStateImpl {
             colors,
         };
 
+        self.is_native_target = render_target.is_native();
+        self.y_flip_dirty = true;
+
+        let is_framebuffer_srgb = self.toggle_framebuffer_srgb && !self.is_native_target;
+        if is_framebuffer_srgb != self.is_framebuffer_srgb {
+            unsafe {
+                if is_framebuffer_srgb {
+                    gl.enable(glow::FRAMEBUFFER_SRGB);
+                } else {
+                    gl.disable(glow::FRAMEBUFFER_SRGB);
+                }
+            }
+            self.is_framebuffer_srgb = is_framebuffer_srgb;
+        }
+
         self.cache.bind_fbo(gl, &render_target);
 
-        // 视口 & 裁剪
-        let size = color.view.get_size();
-        self.set_viewport(gl, 0, 0, size.0 as i32, size.1 as i32);
-        self.set_scissor(gl, 0, 0, size.0 as i32, size.1 as i32);
+        // 视口 & 裁剪：附件 尺寸 都 一样，取 第一个
+        let view = desc
+            .color_attachments
+            .iter()
+            .flatten()
+            .map(|c| &c.view)
+            .next()
+            .or(desc.depth_stencil_attachment.as_ref().map(|ds| &ds.view))
+            .expect("set_render_target: render pass has no attachment");
+
+        // 渲染到 mip 时，尺寸 是 该级 mip 的 尺寸
+        let size = &view.inner.copy_size;
+        let mip_level = view.mip_levels.start;
+        let width = (size.width >> mip_level).max(1) as i32;
+        let height = (size.height >> mip_level).max(1) as i32;
+        self.target_height = height;
+        self.set_viewport(gl, 0, 0, width, height);
+        self.set_scissor(gl, 0, 0, width, height);
 
         // 清屏
+        let clear_colors: Vec<_> = desc
+            .color_attachments
+            .iter()
+            .enumerate()
+            .filter_map(|(i, c)| {
+                let c = c.as_ref()?;
+                match c.ops.load {
+                    super::super::LoadOp::Clear(color) => Some((i as u32, color, c.view.format)),
+                    super::super::LoadOp::Load => None,
+                }
+            })
+            .collect();
+
         self.clear_render_target(
             gl,
-            &color.ops.load,
+            &clear_colors,
             depth_ops.as_ref().map(|d| &d.load),
             stencil_ops.as_ref().map(|s| &s.load),
         );
@@ -1134,18 +2543,18 @@ impl GLStateImpl {
     fn set_bind_group(
         &mut self,
         index: u32,
-        bind_group: &super::BindGroup,
+        contents: &[super::RawBinding],
         dynamic_offsets: &[wgt::DynamicOffset],
     ) {
         assert!(index < super::MAX_BIND_GROUPS as u32);
 
-        let mut contents = Vec::with_capacity(bind_group.contents.len());
-        for b in bind_group.contents.iter() {
-            contents.push(b.into());
+        let mut bgs = Vec::with_capacity(contents.len());
+        for b in contents.iter() {
+            bgs.push(b.into());
         }
 
         let bg = BindGroupState {
-            bgs: contents.into_boxed_slice(),
+            bgs: bgs.into_boxed_slice(),
             dynamic_offsets: dynamic_offsets.to_vec().into_boxed_slice(),
         };
 
@@ -1153,6 +2562,18 @@ impl GLStateImpl {
         self.group_dirty |= 1 << index;
     }
 
+    // 执行 RenderBundle 前后 清空 绑定组 / 顶点 / 索引，和 WebGPU 一样 bundle 和 通道 互不 继承
+    // GL 端 绑定 的 program、纹理单元 不动，下次 设置 时 照常 比较
+    fn reset_bindings(&mut self) {
+        for bg in self.bind_group_set.iter_mut() {
+            *bg = None;
+        }
+        for vb in self.vertex_buffers.iter_mut() {
+            *vb = None;
+        }
+        self.index_buffer = None;
+    }
+
     #[inline]
     fn set_vertex_buffer(
         &mut self,
@@ -1212,16 +2633,30 @@ impl GLStateImpl {
         first_instance: u32,
         instance_count: u32,
     ) {
-        self.before_draw(gl, first_instance);
+        let supports_full_instancing =
+            private_caps.contains(PrivateCapabilities::FULLY_FEATURED_INSTANCING);
 
-        let rp = self.render_pipeline.as_ref().unwrap().0.as_ref();
+        // 不支持 base_instance 时，在 VAO 里 挪 实例属性 的 偏移
+        if !self.before_draw(gl, if supports_full_instancing { 0 } else { first_instance }, 0) {
+            return;
+        }
 
-        // let supports_full_instancing = private_caps.contains(PrivateCapabilities::FULLY_FEATURED_INSTANCING);
-        // let topology = rp.topology;
+        let rp = self.render_pipeline.as_ref().unwrap().0.as_ref();
 
-        // if instance_count == 1 {
-        //     unsafe { gl.draw_arrays(rp.topology, start_vertex as i32, vertex_count as i32) };
-        // } else {
+        // Don't use `gl.draw_arrays` for `instance_count == 1`.
+        // Angle has a bug where it doesn't consider the instance divisor when `DYNAMIC_DRAW` is used in `draw_arrays`.
+        // See https://github.com/gfx-rs/wgpu/issues/3578
+        if supports_full_instancing {
+            unsafe {
+                gl.draw_arrays_instanced_base_instance(
+                    rp.topology,
+                    first_vertex as i32,
+                    vertex_count as i32,
+                    instance_count as i32,
+                    first_instance,
+                )
+            }
+        } else {
             unsafe {
                 gl.draw_arrays_instanced(
                     rp.topology,
@@ -1230,73 +2665,273 @@ impl GLStateImpl {
                     instance_count as i32,
                 )
             };
-        // }
+        }
+
+        self.after_draw(gl);
+    }
+
+    fn draw_indexed(
+        &mut self,
+        gl: &glow::Context,
+        private_caps: PrivateCapabilities,
+        start_index: i32,
+        index_count: i32,
+        base_vertex: i32,
+        first_instance: u32,
+        instance_count: i32,
+    ) {
+        let supports_full_instancing =
+            private_caps.contains(PrivateCapabilities::FULLY_FEATURED_INSTANCING);
+        let supports_base_vertex = private_caps.contains(PrivateCapabilities::DRAW_BASE_VERTEX);
+
+        // 驱动 不支持 的 部分，在 VAO 里 挪 属性 的 偏移；负 的 base_vertex 挪 不了，跳过
+        if !self.before_draw(
+            gl,
+            if supports_full_instancing { 0 } else { first_instance },
+            if supports_base_vertex { 0 } else { base_vertex },
+        ) {
+            return;
+        }
+
+        let rp = self.render_pipeline.as_ref().unwrap().0.as_ref();
+
+        let Some(ib) = self.index_buffer.as_ref() else {
+            log::error!("draw_indexed: index buffer is not set, skipped");
+            return;
+        };
+
+        let offset = ib.offset + start_index * ib.ib_count;
+
+        if supports_full_instancing {
+            unsafe {
+                gl.draw_elements_instanced_base_vertex_base_instance(
+                    rp.topology,
+                    index_count,
+                    ib.ib_type,
+                    offset,
+                    instance_count,
+                    if supports_base_vertex { base_vertex } else { 0 },
+                    first_instance,
+                )
+            }
+        } else if supports_base_vertex && base_vertex != 0 {
+            if instance_count == 1 {
+                unsafe {
+                    gl.draw_elements_base_vertex(
+                        rp.topology,
+                        index_count,
+                        ib.ib_type,
+                        offset,
+                        base_vertex,
+                    )
+                }
+            } else {
+                unsafe {
+                    gl.draw_elements_instanced_base_vertex(
+                        rp.topology,
+                        index_count,
+                        ib.ib_type,
+                        offset,
+                        instance_count,
+                        base_vertex,
+                    )
+                }
+            }
+        } else if instance_count == 1 {
+            unsafe {
+                gl.draw_elements(rp.topology, index_count, ib.ib_type, offset);
+            }
+        } else {
+            unsafe {
+                gl.draw_elements_instanced(
+                    rp.topology,
+                    index_count,
+                    ib.ib_type,
+                    offset,
+                    instance_count,
+                )
+            }
+        }
+
+        self.after_draw(gl);
+    }
+
+    // 有 glDrawArraysIndirect 时 参数 留在 GPU
+    // GL 4.0 / 4.1 / GLES 3.1 的 参数 里 first_instance 是 保留字段，必须 为 0，见 Features::INDIRECT_FIRST_INSTANCE
+    // GLES 3.0 / WebGL2 从 CPU 端拷贝 取 参数，按 普通 draw 画
+    fn draw_indirect(
+        &mut self,
+        gl: &glow::Context,
+        private_caps: PrivateCapabilities,
+        buffer: &super::Buffer,
+        offset: i32,
+    ) {
+        let buffer = buffer.0.as_ref();
+
+        if private_caps.contains(PrivateCapabilities::DRAW_INDIRECT) {
+            if !self.before_draw(gl, 0, 0) {
+                return;
+            }
+
+            let rp = self.render_pipeline.as_ref().unwrap().0.as_ref();
+            unsafe {
+                gl.bind_buffer(glow::DRAW_INDIRECT_BUFFER, Some(buffer.raw));
+                gl.draw_arrays_indirect_offset(rp.topology, offset);
+                gl.bind_buffer(glow::DRAW_INDIRECT_BUFFER, None);
+            }
+
+            self.after_draw(gl);
+        } else {
+            // vertex_count, instance_count, first_vertex, first_instance
+            let args = self.read_indirect_args(gl, buffer, offset, 4);
+
+            self.draw(gl, private_caps, args[2], args[0], args[3], args[1]);
+        }
+    }
+
+    fn draw_indexed_indirect(
+        &mut self,
+        gl: &glow::Context,
+        private_caps: PrivateCapabilities,
+        buffer: &super::Buffer,
+        offset: i32,
+    ) {
+        let buffer = buffer.0.as_ref();
+
+        let Some(ib) = self.index_buffer.as_ref() else {
+            log::error!("draw_indexed_indirect: index buffer is not set, skipped");
+            return;
+        };
+
+        // glDrawElementsIndirect 没有 索引 的 字节偏移，只有 first_index
+        // set_index_buffer 带 偏移 时 也 走 回读
+        let has_ib_offset = ib.offset != 0;
+
+        if private_caps.contains(PrivateCapabilities::DRAW_INDIRECT) && !has_ib_offset {
+            if !self.before_draw(gl, 0, 0) {
+                return;
+            }
+
+            let rp = self.render_pipeline.as_ref().unwrap().0.as_ref();
+            let ib = self.index_buffer.as_ref().unwrap();
+
+            unsafe {
+                gl.bind_buffer(glow::DRAW_INDIRECT_BUFFER, Some(buffer.raw));
+                gl.draw_elements_indirect_offset(rp.topology, ib.ib_type, offset);
+                gl.bind_buffer(glow::DRAW_INDIRECT_BUFFER, None);
+            }
+
+            self.after_draw(gl);
+        } else {
+            // index_count, instance_count, first_index, base_vertex, first_instance
+            let args = self.read_indirect_args(gl, buffer, offset, 5);
+            self.draw_indexed(
+                gl,
+                private_caps,
+                args[2] as i32,
+                args[0] as i32,
+                args[3] as i32,
+                args[4],
+                args[1] as i32,
+            );
+        }
+    }
+
+    // 取 count 个 u32 的 间接绘制 参数
+    // GLES 3.0 / WebGL2 的 INDIRECT buffer 有 CPU 端拷贝，直接 取；否则 从 GPU 回读，参数 可能 是 compute / copy 写的
+    fn read_indirect_args(
+        &self,
+        gl: &glow::Context,
+        buffer: &super::BufferImpl,
+        offset: i32,
+        count: usize,
+    ) -> Vec<u32> {
+        let size = (count * 4) as i32;
+        let data = match buffer.read_shadow(offset, size) {
+            Some(data) => data,
+            None => {
+                let mut data = vec![0; count * 4];
+                self.get_buffer_sub_data(gl, buffer, offset, &mut data);
+                data
+            }
+        };
+
+        data.chunks_exact(4)
+            .map(|c| u32::from_ne_bytes([c[0], c[1], c[2], c[3]]))
+            .collect()
+    }
+
+    fn set_compute_pipeline(&mut self, gl: &glow::Context, pipeline: &super::ComputePipeline) {
+        if let Some(old) = self.compute_pipeline.as_ref() {
+            if Share::ptr_eq(&pipeline.0, &old.0) {
+                return;
+            }
+        }
+
+        Self::apply_program(gl, Some(&pipeline.0.program));
+
+        // 换了 program，下次 set_render_pipeline 要 全部 重新设置
+        self.render_pipeline = None;
+        self.compute_pipeline = Some(pipeline.clone());
+        self.push_constants_dirty = true;
+    }
+
+    fn dispatch(&mut self, gl: &glow::Context, private_caps: PrivateCapabilities, count: [u32; 3]) {
+        if self.compute_pipeline.is_none() {
+            log::error!("dispatch: compute pipeline is not set, skipped");
+            return;
+        }
+
+        self.update_uniforms(gl, true);
 
-        // if supports_full_instancing {
-        //     unsafe {
-        //         gl.draw_arrays_instanced_base_instance(
-        //             topology,
-        //             first_vertex as i32,
-        //             vertex_count as i32,
-        //             instance_count as i32,
-        //             first_instance,
-        //         )
-        //     }
-        // } else {
-
-        //     // Don't use `gl.draw_arrays` for `instance_count == 1`.
-        //     // Angle has a bug where it doesn't consider the instance divisor when `DYNAMIC_DRAW` is used in `draw_arrays`.
-        //     // See https://github.com/gfx-rs/wgpu/issues/3578
-        //     unsafe {
-        //         gl.draw_arrays_instanced(
-        //             topology,
-        //             first_vertex as i32,
-        //             vertex_count as i32,
-        //             instance_count as i32,
-        //         )
-        //     }
-        // };
+        self.update_push_constants(gl);
 
-        self.after_draw(gl);
+        unsafe {
+            gl.dispatch_compute(count[0], count[1], count[2]);
+        }
+
+        Self::after_dispatch(gl, private_caps);
     }
 
-    fn draw_indexed(
+    fn dispatch_indirect(
         &mut self,
         gl: &glow::Context,
-        start_index: i32,
-        index_count: i32,
-        first_instance: u32,
-        instance_count: i32,
+        private_caps: PrivateCapabilities,
+        buffer: &super::Buffer,
+        offset: i32,
     ) {
-        self.before_draw(gl, first_instance);
+        if self.compute_pipeline.is_none() {
+            log::error!("dispatch_indirect: compute pipeline is not set, skipped");
+            return;
+        }
 
-        let rp = self.render_pipeline.as_ref().unwrap().0.as_ref();
+        self.update_uniforms(gl, true);
 
-        let ib = self.index_buffer.as_ref().unwrap();
+        self.update_push_constants(gl);
 
-        let offset = ib.offset + start_index * ib.ib_count;
+        unsafe {
+            gl.bind_buffer(glow::DISPATCH_INDIRECT_BUFFER, Some(buffer.0.raw));
+            gl.dispatch_compute_indirect(offset);
+            gl.bind_buffer(glow::DISPATCH_INDIRECT_BUFFER, None);
+        }
 
-        if instance_count == 1 {
-            unsafe {
-                gl.draw_elements(rp.topology, index_count, ib.ib_type, offset);
-            }
-        } else {
+        Self::after_dispatch(gl, private_caps);
+    }
+
+    // 不 跟踪 资源 的 用途，每次 dispatch 之后 对 所有 访问 加 屏障
+    // 后面的 dispatch / draw / 拷贝 / 映射 都能 看到 compute 写入的 结果
+    #[inline]
+    fn after_dispatch(gl: &glow::Context, private_caps: PrivateCapabilities) {
+        if private_caps.contains(PrivateCapabilities::MEMORY_BARRIERS) {
             unsafe {
-                gl.draw_elements_instanced(
-                    rp.topology,
-                    index_count,
-                    ib.ib_type,
-                    offset,
-                    instance_count,
-                )
+                gl.memory_barrier(glow::ALL_BARRIER_BITS);
             }
         }
-
-        self.after_draw(gl);
     }
 
     #[inline]
     fn set_viewport(&mut self, gl: &glow::Context, x: i32, y: i32, w: i32, h: i32) {
+        let y = self.flip_rect_y(y, h);
         let vp = &mut self.viewport;
 
         // if x != vp.x || y != vp.y || w != vp.w || h != vp.h {
@@ -1311,6 +2946,7 @@ impl GLStateImpl {
 
     #[inline]
     fn set_scissor(&mut self, gl: &glow::Context, x: i32, y: i32, w: i32, h: i32) {
+        let y = self.flip_rect_y(y, h);
         let s = &mut self.scissor;
 
         // if !s.is_enable {
@@ -1385,15 +3021,17 @@ impl GLStateImpl {
         entry_point: String,
         multiview: Option<std::num::NonZeroU32>,
         naga_options: &naga::back::glsl::Options,
-    ) -> Result<(), super::ShaderError> {
-        // 如果编译过了，直接返回
-        if self.cache.get_shader(shader.id).is_some() {
-            return Ok(());
+    ) -> Result<ShaderID, super::ShaderError> {
+        // 同一个 模块 的 同一个 入口 编译过了，直接返回
+        let key = (shader.id, shader_stage, entry_point.clone());
+        if let Some(id) = self.cache.get_shader_id(&key) {
+            return Ok(id);
         }
 
         let mut module: Option<naga::Module> = None;
 
         let module_ref: &naga::Module = match &shader.input {
+            // wgsl / spirv 模块 可以有 多个 入口，靠 entry_point 区分 阶段
             super::ShaderInput::Naga(module) => module,
             super::ShaderInput::Glsl {
                 shader,
@@ -1427,6 +3065,19 @@ impl GLStateImpl {
 
         let info = get_shader_info(module_ref, features, downlevel)?;
 
+        // SSBO / Storage Texture 的 绑定点 只能 写在 shader 的 layout(binding) 里
+        // 和 UBO 一样 全局 分配，切换 program 后 已经 绑定 的 资源 依然 有效
+        let mut storage_options: Option<naga::back::glsl::Options> = None;
+        for (_, var) in module_ref.global_variables.iter() {
+            if let Some((_, glow_binding)) = self.storage_binding(module_ref, var)? {
+                storage_options
+                    .get_or_insert_with(|| naga_options.clone())
+                    .binding_map
+                    .insert(var.binding.clone().unwrap(), glow_binding as u8);
+            }
+        }
+        let naga_options = storage_options.as_ref().unwrap_or(naga_options);
+
         let (gl_str, reflection_info) = compile_naga_shader(
             module_ref,
             version,
@@ -1440,27 +3091,47 @@ impl GLStateImpl {
         let shader_type = match shader_stage {
             naga::ShaderStage::Vertex => glow::VERTEX_SHADER,
             naga::ShaderStage::Fragment => glow::FRAGMENT_SHADER,
-            naga::ShaderStage::Compute => unreachable!(),
+            naga::ShaderStage::Compute => glow::COMPUTE_SHADER,
         };
 
         let raw = compile_gl_shader(gl, gl_str.as_ref(), shader_type)?;
 
+        // push constant 的 成员，链接 之后 才能 取 location
+        let push_constant_items = reflection_info
+            .push_constant_items
+            .iter()
+            .map(|item| {
+                let ty = module_ref.types[item.ty].inner.clone();
+                super::PushConstantItem {
+                    access_path: item.access_path.clone(),
+                    size: ty.size(module_ref.to_ctx()),
+                    ty,
+                    offset: item.offset,
+                }
+            })
+            .collect();
+
         let bg_set_info = self.consume_naga_reflection(
             module_ref,
             &info.get_entry_point(entry_point_index),
             reflection_info,
         )?;
 
+        self.global_shader_id += 1;
+        let id = self.global_shader_id;
+
         self.cache.insert_shader(
-            shader.id,
+            key,
+            id,
             super::ShaderInner {
                 raw,
                 shader_type,
                 bg_set_info,
+                push_constant_items,
             },
         );
 
-        Ok(())
+        Ok(id)
     }
 
     fn create_program(
@@ -1468,18 +3139,61 @@ impl GLStateImpl {
         gl: &glow::Context,
         vs_id: ShaderID,
         fs_id: ShaderID,
-    ) -> Result<(glow::Program, Box<[Box<[super::PiBindEntry]>]>), super::ShaderError> {
+    ) -> Result<
+        (
+            glow::Program,
+            Box<[Box<[super::PiBindEntry]>]>,
+            Box<[super::PushConstantDesc]>,
+        ),
+        super::ShaderError,
+    > {
         let vs_inner = self.cache.get_shader(vs_id).unwrap();
         let fs_inner = self.cache.get_shader(fs_id).unwrap();
 
         assert!(vs_inner.shader_type == glow::VERTEX_SHADER);
         assert!(fs_inner.shader_type == glow::FRAGMENT_SHADER);
 
+        self.link_program(gl, &[vs_inner, fs_inner])
+    }
+
+    fn create_compute_program(
+        &self,
+        gl: &glow::Context,
+        cs_id: ShaderID,
+    ) -> Result<
+        (
+            glow::Program,
+            Box<[Box<[super::PiBindEntry]>]>,
+            Box<[super::PushConstantDesc]>,
+        ),
+        super::ShaderError,
+    > {
+        let cs_inner = self.cache.get_shader(cs_id).unwrap();
+
+        assert!(cs_inner.shader_type == glow::COMPUTE_SHADER);
+
+        self.link_program(gl, &[cs_inner])
+    }
+
+    // 链接 program，按 shader 的 反射信息 设置 UBO / 纹理 的 绑定点
+    fn link_program(
+        &self,
+        gl: &glow::Context,
+        shaders: &[&super::ShaderInner],
+    ) -> Result<
+        (
+            glow::Program,
+            Box<[Box<[super::PiBindEntry]>]>,
+            Box<[super::PushConstantDesc]>,
+        ),
+        super::ShaderError,
+    > {
         let raw = unsafe {
             let raw = gl.create_program().unwrap();
 
-            gl.attach_shader(raw, vs_inner.raw);
-            gl.attach_shader(raw, fs_inner.raw);
+            for shader in shaders {
+                gl.attach_shader(raw, shader.raw);
+            }
 
             gl.link_program(raw);
 
@@ -1508,11 +3222,9 @@ impl GLStateImpl {
             gl.use_program(Some(raw));
         }
 
-        vs_inner
-            .bg_set_info
+        shaders
             .iter()
-            .enumerate()
-            .chain(fs_inner.bg_set_info.iter().enumerate())
+            .flat_map(|shader| shader.bg_set_info.iter().enumerate())
             .for_each(|(index, bg)| {
                 if max_set < index as i32 {
                     max_set = index as i32;
@@ -1539,14 +3251,35 @@ impl GLStateImpl {
                             gl.uniform_1_i32(loc.as_ref(), entry.glow_binding as i32);
                         },
                         super::PiBindingType::Texture => {}
+                        // 绑定点 已经 写在 shader 的 layout(binding) 里
+                        super::PiBindingType::StorageBuffer
+                        | super::PiBindingType::StorageTexture => {}
                     }
                 });
             });
 
-        match self.render_pipeline.as_ref() {
-            Some(p) => Self::apply_program(gl, Some(&p.0.program)),
-            None => Self::apply_program(gl, None),
-        }
+        // push constant 的 location；没用到 的 成员 会被 驱动 优化掉，跳过
+        let push_constants = shaders
+            .iter()
+            .flat_map(|shader| shader.push_constant_items.iter())
+            .filter_map(|item| unsafe {
+                gl.get_uniform_location(raw, item.access_path.as_ref())
+                    .map(|location| super::PushConstantDesc {
+                        location,
+                        ty: item.ty.clone(),
+                        offset: item.offset,
+                        size: item.size,
+                    })
+            })
+            .collect();
+
+        // 恢复 当前 管线 的 program
+        let current = match (self.compute_pipeline.as_ref(), self.render_pipeline.as_ref()) {
+            (Some(p), _) => Some(&p.0.program),
+            (None, Some(p)) => Some(&p.0.program),
+            (None, None) => None,
+        };
+        Self::apply_program(gl, current);
 
         max_set += 1;
         let max_set = max_set as usize;
@@ -1557,7 +3290,58 @@ impl GLStateImpl {
             uniforms.push(v.into_boxed_slice());
         }
 
-        Ok((raw, uniforms.into_boxed_slice()))
+        Ok((raw, uniforms.into_boxed_slice(), push_constants))
+    }
+
+    // SSBO / Storage Texture 的 全局 绑定点；其他 资源 返回 None
+    fn storage_binding(
+        &mut self,
+        module: &naga::Module,
+        var: &naga::GlobalVariable,
+    ) -> Result<Option<(super::PiBindingType, u32)>, super::ShaderError> {
+        let br = match var.binding.as_ref() {
+            Some(br) => br,
+            None => return Ok(None),
+        };
+        let pi_br = super::PiResourceBinding {
+            group: br.group,
+            binding: br.binding,
+        };
+
+        match var.space {
+            naga::AddressSpace::Storage { .. } => {
+                if self.max_storage_buffer_bindings == 0 {
+                    return Err(super::ShaderError::Compilation(
+                        "storage buffers are not supported, need GLES 3.1 or GL 4.3".to_string(),
+                    ));
+                }
+
+                Ok(Some((
+                    super::PiBindingType::StorageBuffer,
+                    self.cache.update_ssbo(pi_br),
+                )))
+            }
+            naga::AddressSpace::Handle => match module.types[var.ty].inner {
+                naga::TypeInner::Image {
+                    class: naga::ImageClass::Storage { .. },
+                    ..
+                } => {
+                    if self.max_image_units == 0 {
+                        return Err(super::ShaderError::Compilation(
+                            "storage textures are not supported, need GLES 3.1 or GL 4.3"
+                                .to_string(),
+                        ));
+                    }
+
+                    Ok(Some((
+                        super::PiBindingType::StorageTexture,
+                        self.cache.update_image(pi_br),
+                    )))
+                }
+                _ => Ok(None),
+            },
+            _ => Ok(None),
+        }
     }
 
     fn consume_naga_reflection(
@@ -1569,17 +3353,22 @@ impl GLStateImpl {
         let mut r = [vec![], vec![], vec![], vec![]];
         let mut max_set: i32 = -1;
 
-        // UBO
+        // UBO / SSBO
         for (handle, name) in reflection_info.uniforms {
             let var = &module.global_variables[handle];
             let br = var.binding.as_ref().unwrap();
 
-            let pi_br = super::PiResourceBinding {
-                group: br.group,
-                binding: br.binding,
-            };
+            let (ty, glow_binding) = match self.storage_binding(module, var)? {
+                Some(r) => r,
+                None => {
+                    let pi_br = super::PiResourceBinding {
+                        group: br.group,
+                        binding: br.binding,
+                    };
 
-            let glow_binding = self.cache.update_ubo(pi_br);
+                    (super::PiBindingType::Buffer, self.cache.update_ubo(pi_br))
+                }
+            };
 
             if br.group as i32 > max_set {
                 max_set = br.group as i32;
@@ -1587,7 +3376,7 @@ impl GLStateImpl {
             let set = &mut r[br.group as usize];
             set.push(super::PiBindEntry {
                 binding: br.binding as usize,
-                ty: super::PiBindingType::Buffer,
+                ty,
 
                 glsl_name: name,
                 glow_binding,
@@ -1604,16 +3393,34 @@ impl GLStateImpl {
         // }
         let mut temp = XHashMap::default();
 
-        // Sampler / Texture
+        // Sampler / Texture / Storage Texture
         for (name, mapping) in reflection_info.texture_mapping {
-            assert!(mapping.sampler.is_some());
-
             let tex_var = &module.global_variables[mapping.texture];
             let tex_br = tex_var.binding.as_ref().unwrap();
             if tex_br.group as i32 > max_set {
                 max_set = tex_br.group as i32;
             }
 
+            // 没有 Sampler 的 只有 Storage Texture
+            if mapping.sampler.is_none() {
+                let (ty, glow_binding) = self
+                    .storage_binding(module, tex_var)?
+                    .expect("texture without sampler must be a storage texture");
+
+                let set = &mut r[tex_br.group as usize];
+                if !temp.contains_key(&(tex_br.group, tex_br.binding)) {
+                    set.push(super::PiBindEntry {
+                        binding: tex_br.binding as usize,
+                        ty,
+
+                        glsl_name: name,
+                        glow_binding,
+                    });
+                    temp.insert((tex_br.group, tex_br.binding), ());
+                }
+                continue;
+            }
+
             let pi_br = super::PiResourceBinding {
                 group: tex_br.group,
                 binding: tex_br.binding,
@@ -1665,18 +3472,164 @@ impl GLStateImpl {
         Ok(us.into_boxed_slice())
     }
 
+    // 返回 false 表示 这次 绘制 要 跳过
+    #[inline]
+    fn before_draw(&mut self, gl: &glow::Context, first_instance: u32, base_vertex: i32) -> bool {
+        if !self.update_vao(gl, first_instance, base_vertex) {
+            return false;
+        }
+
+        self.update_uniforms(gl, false);
+
+        self.update_push_constants(gl);
+
+        self.update_y_flip(gl);
+
+        true
+    }
+
+    // wgpu 的 矩形 原点 在 左上角；离屏 渲染 已经 翻转 了 y，只有 默认帧缓冲 要 换算
     #[inline]
-    fn before_draw(&mut self, gl: &glow::Context, first_instance: u32) {
-        self.update_vao(gl, first_instance);
+    fn flip_rect_y(&self, y: i32, h: i32) -> i32 {
+        if self.is_native_target {
+            self.target_height - y - h
+        } else {
+            y
+        }
+    }
+
+    // 离屏 渲染 翻转 y，纹理 的 第 0 行 是 画面 顶部，和 wgpu 一致
+    // 默认帧缓冲 不 翻转，正面 的 绕序 也 要 换回来，见 RenderPipelineImpl::create_rs
+    fn update_y_flip(&mut self, gl: &glow::Context) {
+        if !self.y_flip_dirty {
+            return;
+        }
+        self.y_flip_dirty = false;
+
+        let pipeline = self.render_pipeline.as_ref().unwrap().0.as_ref();
+
+        let front_face = pipeline.rs.imp.front_face;
+        let (y_flip, front_face) = if self.is_native_target {
+            let front_face = if front_face == glow::CW {
+                glow::CCW
+            } else {
+                glow::CW
+            };
+            (1.0, front_face)
+        } else {
+            (-1.0, front_face)
+        };
+
+        unsafe {
+            if let Some(location) = pipeline.program.0.y_flip.as_ref() {
+                gl.uniform_1_f32(Some(location), y_flip);
+            }
+            gl.front_face(front_face);
+        }
+    }
+
+    // offset 是 字节，4 字节 对齐
+    fn set_push_constants(&mut self, offset: u32, data: &[u32]) {
+        let start = offset as usize / 4;
+        self.push_constants[start..start + data.len()].copy_from_slice(data);
+
+        self.push_constants_dirty = true;
+    }
+
+    // 把 push constant 数据 用 glUniform* 上传 到 当前 program
+    fn update_push_constants(&mut self, gl: &glow::Context) {
+        if !self.push_constants_dirty {
+            return;
+        }
+        self.push_constants_dirty = false;
+
+        // render / compute 管线 同时 只有 一个 有值
+        let program = match (self.render_pipeline.as_ref(), self.compute_pipeline.as_ref()) {
+            (Some(rp), _) => &rp.0.program,
+            (None, Some(cp)) => &cp.0.program,
+            (None, None) => return,
+        };
 
-        self.update_uniforms(gl);
+        for desc in program.0.push_constants.iter() {
+            let start = desc.offset as usize / 4;
+            match self.push_constants.get(start..start + desc.size as usize / 4) {
+                Some(data) => Self::apply_push_constant(gl, desc, data),
+                None => log::error!(
+                    "push constant at offset {} exceeds {} bytes",
+                    desc.offset,
+                    super::MAX_PUSH_CONSTANTS * 4
+                ),
+            }
+        }
+    }
+
+    fn apply_push_constant(gl: &glow::Context, desc: &super::PushConstantDesc, data: &[u32]) {
+        use naga::{Scalar, TypeInner, VectorSize};
+
+        let location = Some(&desc.location);
+
+        let f = |count: usize| data[..count].iter().map(|v| f32::from_bits(*v)).collect::<Vec<_>>();
+        let i = |count: usize| data[..count].iter().map(|v| *v as i32).collect::<Vec<_>>();
+
+        unsafe {
+            match desc.ty {
+                TypeInner::Scalar(Scalar::F32) => gl.uniform_1_f32(location, f32::from_bits(data[0])),
+                TypeInner::Vector {
+                    size,
+                    scalar: Scalar::F32,
+                } => match size {
+                    VectorSize::Bi => gl.uniform_2_f32_slice(location, &f(2)),
+                    VectorSize::Tri => gl.uniform_3_f32_slice(location, &f(3)),
+                    VectorSize::Quad => gl.uniform_4_f32_slice(location, &f(4)),
+                },
+                TypeInner::Scalar(Scalar::I32) => gl.uniform_1_i32(location, data[0] as i32),
+                TypeInner::Vector {
+                    size,
+                    scalar: Scalar::I32,
+                } => match size {
+                    VectorSize::Bi => gl.uniform_2_i32_slice(location, &i(2)),
+                    VectorSize::Tri => gl.uniform_3_i32_slice(location, &i(3)),
+                    VectorSize::Quad => gl.uniform_4_i32_slice(location, &i(4)),
+                },
+                TypeInner::Scalar(Scalar::U32) => gl.uniform_1_u32(location, data[0]),
+                TypeInner::Vector {
+                    size,
+                    scalar: Scalar::U32,
+                } => match size {
+                    VectorSize::Bi => gl.uniform_2_u32_slice(location, &data[..2]),
+                    VectorSize::Tri => gl.uniform_3_u32_slice(location, &data[..3]),
+                    VectorSize::Quad => gl.uniform_4_u32_slice(location, &data[..4]),
+                },
+                TypeInner::Matrix {
+                    columns: VectorSize::Bi,
+                    rows: VectorSize::Bi,
+                    scalar: Scalar::F32,
+                } => gl.uniform_matrix_2_f32_slice(location, false, &f(4)),
+                TypeInner::Matrix {
+                    columns: VectorSize::Tri,
+                    rows: VectorSize::Tri,
+                    scalar: Scalar::F32,
+                } => {
+                    // 每 列 vec3 按 16 字节 对齐，去掉 填充
+                    let m = f(12);
+                    let packed = [m[0], m[1], m[2], m[4], m[5], m[6], m[8], m[9], m[10]];
+                    gl.uniform_matrix_3_f32_slice(location, false, &packed);
+                }
+                TypeInner::Matrix {
+                    columns: VectorSize::Quad,
+                    rows: VectorSize::Quad,
+                    scalar: Scalar::F32,
+                } => gl.uniform_matrix_4_f32_slice(location, false, &f(16)),
+                _ => log::error!("push constant type {:?} is not supported", desc.ty),
+            }
+        }
     }
 
     #[inline]
     fn after_draw(&mut self, _gl: &glow::Context) {}
 
     // 根据 render_pipeline.attributes + vertex_buffers 更新 vao
-    fn update_vao(&mut self, gl: &glow::Context, first_instance: u32) {
+    fn update_vao(&mut self, gl: &glow::Context, first_instance: u32, base_vertex: i32) -> bool {
         profiling::scope!("hal::GLState::update_vao");
 
         let rp = self.render_pipeline.as_ref().unwrap().0.as_ref();
@@ -1697,7 +3650,11 @@ impl GLStateImpl {
 
         for attrib in rp.attributes.info.iter() {
             if let Some(a) = attrib {
-                vbs[a.buffer_slot] = self.vertex_buffers[a.buffer_slot].clone();
+                let Some(vb) = self.vertex_buffers[a.buffer_slot].clone() else {
+                    log::error!("draw: vertex buffer of slot {} is not set, skipped", a.buffer_slot);
+                    return false;
+                };
+                vbs[a.buffer_slot] = Some(vb);
             }
         }
 
@@ -1706,13 +3663,15 @@ impl GLStateImpl {
             vbs,
             self.index_buffer.as_ref().map(|ib| ib.raw),
             first_instance,
+            base_vertex,
         );
 
-        self.cache.bind_vao(gl, &geometry);
-        
+        let is_bound = self.cache.bind_vao(gl, &geometry);
 
         // 回收 vbs
         self.last_vbs = Some(geometry.vbs);
+
+        is_bound
     }
 
     fn remove_buffer(&mut self, gl: &glow::Context, bind_target: u32, buffer: glow::Buffer) {
@@ -1752,6 +3711,7 @@ impl GLStateImpl {
 
     fn remove_texture(&mut self, gl: &glow::Context, tex: glow::Texture) {
         self.cache.remove_texture(gl, tex);
+        self.texture_params.remove(&tex);
 
         for (slot, _) in self.textures.iter_mut() {
             let update = if let Some((_, t)) = slot {
@@ -1780,15 +3740,27 @@ impl GLStateImpl {
         }
     }
 
-    // 根据 render_pipeline.program + bind_group 更新 uniform
-    fn update_uniforms(&mut self, gl: &glow::Context) {
-        let program = &self.render_pipeline.as_ref().unwrap().0.program;
+    // 根据 当前 管线 的 program + bind_group 更新 uniform
+    // is_compute 时 用 compute 管线，否则 用 渲染管线；调用者 保证 对应的 管线 已经 设置
+    fn update_uniforms(&mut self, gl: &glow::Context, is_compute: bool) {
+        let (program, reorder) = if is_compute {
+            let cp = self.compute_pipeline.as_ref().unwrap();
+            (&cp.0.program, &cp.0.layout_reoder)
+        } else {
+            let rp = self.render_pipeline.as_ref().unwrap();
+            (&rp.0.program, &rp.0.layout_reoder)
+        };
 
         let program = program.0.as_ref();
 
-        let bg_set = &mut self.bind_group_set;
+        // 同一张 纹理 的 多个 视图 mip 范围 不同 时，有 拷贝 的 视图 改为 采样 拷贝
+        // 所有 组 都要 重新 绑定，否则 没变的 组 采样 的 是 被 改掉 参数 的 纹理
+        let mip_conflicts = Self::find_mip_conflicts(&self.bind_group_set, program, reorder);
+        if !mip_conflicts.is_empty() {
+            self.group_dirty = usize::MAX;
+        }
 
-        let reorder = &self.render_pipeline.as_ref().unwrap().0.layout_reoder;
+        let bg_set = &mut self.bind_group_set;
 
         for (i, bindings) in program.uniforms.iter().enumerate() {
             if self.group_dirty & (1 << i) == 0 {
@@ -1812,7 +3784,13 @@ impl GLStateImpl {
                         offset,
                         size,
                     } => unsafe {
-                        assert!(binding.ty == PiBindingType::Buffer);
+                        let (target, bound) = match binding.ty {
+                            PiBindingType::Buffer => (glow::UNIFORM_BUFFER, &mut self.ubos),
+                            PiBindingType::StorageBuffer => {
+                                (glow::SHADER_STORAGE_BUFFER, &mut self.ssbos)
+                            }
+                            _ => panic!("mis match Buffer type"),
+                        };
                         let inner = raw.upgrade().unwrap();
                         let imp = inner.as_ref();
 
@@ -1828,23 +3806,23 @@ impl GLStateImpl {
                             size: *size,
                         };
 
-                        let need_update = match self.ubos[binding.glow_binding as usize].as_ref() {
+                        let need_update = match bound[binding.glow_binding as usize].as_ref() {
                             None => true,
                             Some(s) => s.buffer != imp.raw || s.offset != offset || s.size != *size,
                         };
 
                         if need_update {
-                            self.ubos[binding.glow_binding as usize] = Some(state);
+                            bound[binding.glow_binding as usize] = Some(state);
 
                             if offset == 0 && *size == imp.size {
                                 gl.bind_buffer_base(
-                                    glow::UNIFORM_BUFFER,
+                                    target,
                                     binding.glow_binding,
                                     Some(imp.raw),
                                 );
                             } else {
                                 gl.bind_buffer_range(
-                                    glow::UNIFORM_BUFFER,
+                                    target,
                                     binding.glow_binding,
                                     Some(imp.raw),
                                     offset,
@@ -1853,31 +3831,84 @@ impl GLStateImpl {
                             }
                         }
                     },
-                    RawBindingState::Texture { raw: raw_ref } => unsafe {
+                    RawBindingState::Texture {
+                        raw: raw_ref,
+                        mip_levels,
+                        layers,
+                        layered,
+                        srgb_decode,
+                        layer_copy,
+                        mip_copy,
+                    } => unsafe {
                         assert!(binding.ty == PiBindingType::Texture);
                         let inner = raw_ref.upgrade().unwrap();
                         let imp = inner.as_ref();
-                        match &imp.inner {
-                            hal::TextureInner::Texture { raw, target, .. } => {
-                                let need_update = match self.textures[binding.glow_binding as usize]
-                                {
-                                    (None, _) => true,
-                                    (Some((old_target, old_texture)), _) => {
-                                        old_target != *target || old_texture != *raw
-                                    }
-                                };
-                                if need_update {
-                                    self.textures[binding.glow_binding as usize].0 =
-                                        Some((*target, *raw));
-
-                                    if self.active_texture_unit != binding.glow_binding {
-                                        self.active_texture_unit = binding.glow_binding;
-                                        gl.active_texture(glow::TEXTURE0 + binding.glow_binding);
-                                    }
-                                    gl.bind_texture(*target, Some(*raw));
+
+                        let copy = match (layer_copy, &imp.inner) {
+                            (Some(c), _) => Some(c),
+                            (None, hal::TextureInner::Texture { raw, .. })
+                                if mip_conflicts.contains(raw) =>
+                            {
+                                mip_copy.as_ref()
+                            }
+                            _ => None,
+                        };
+
+                        let (raw, target, levels) = match copy.map(|c| c.upgrade().unwrap()) {
+                            // 单层 视图 / 部分 层 的 数组 视图 / mip 范围 冲突 的 视图：先 把 选中的 层 拷贝 出来，采样 拷贝
+                            Some(copy) => {
+                                if self.active_texture_unit != binding.glow_binding {
+                                    self.active_texture_unit = binding.glow_binding;
+                                    gl.active_texture(glow::TEXTURE0 + binding.glow_binding);
+                                }
+                                let (raw, target) = Self::update_layer_copy(
+                                    gl,
+                                    imp,
+                                    &copy,
+                                    mip_levels.clone(),
+                                    layers.clone(),
+                                    *layered,
+                                );
+                                self.textures[binding.glow_binding as usize].0 = Some((target, raw));
+
+                                (raw, target, 0..mip_levels.len() as u32)
+                            }
+                            None => match &imp.inner {
+                                hal::TextureInner::Texture { raw, target, .. } => {
+                                    (*raw, *target, mip_levels.clone())
                                 }
+                                _ => panic!("mis match texture size"),
+                            },
+                        };
+
+                        let need_update = match self.textures[binding.glow_binding as usize] {
+                            (None, _) => true,
+                            (Some((old_target, old_texture)), _) => {
+                                old_target != target || old_texture != raw
+                            }
+                        };
+                        if need_update {
+                            self.textures[binding.glow_binding as usize].0 = Some((target, raw));
+
+                            if self.active_texture_unit != binding.glow_binding {
+                                self.active_texture_unit = binding.glow_binding;
+                                gl.active_texture(glow::TEXTURE0 + binding.glow_binding);
                             }
-                            _ => panic!("mis match texture size"),
+                            gl.bind_texture(target, Some(raw));
+                        }
+
+                        let params = TextureParams {
+                            base_level: levels.start,
+                            max_level: levels.end - 1,
+                            srgb_decode: *srgb_decode,
+                        };
+                        if self.texture_params.get(&raw) != Some(&params) {
+                            if self.active_texture_unit != binding.glow_binding {
+                                self.active_texture_unit = binding.glow_binding;
+                                gl.active_texture(glow::TEXTURE0 + binding.glow_binding);
+                            }
+                            Self::apply_texture_params(gl, target, &params, self.is_ios18);
+                            self.texture_params.insert(raw, params);
                         }
                     },
                     RawBindingState::Sampler { raw } => unsafe {
@@ -1939,16 +3970,68 @@ impl GLStateImpl {
                             }
                         }
                     },
+                    RawBindingState::StorageTexture {
+                        raw,
+                        level,
+                        layered,
+                        layer,
+                        access,
+                        format,
+                    } => unsafe {
+                        assert!(binding.ty == PiBindingType::StorageTexture);
+                        let inner = raw.upgrade().unwrap();
+
+                        let texture = match &inner.as_ref().inner {
+                            hal::TextureInner::Texture { raw, .. } => *raw,
+                            _ => panic!("storage texture must be a gl texture"),
+                        };
+
+                        let state = ImageState {
+                            texture,
+                            level: *level,
+                            layered: *layered,
+                            layer: *layer,
+                            access: *access,
+                            format: *format,
+                        };
+
+                        let unit = binding.glow_binding as usize;
+                        self.writable_images[unit] = if *access == glow::READ_ONLY {
+                            None
+                        } else {
+                            Some(raw.clone())
+                        };
+
+                        if self.images[unit].as_ref() != Some(&state) {
+                            gl.bind_image_texture(
+                                binding.glow_binding,
+                                texture,
+                                *level as i32,
+                                *layered,
+                                *layer as i32,
+                                *access,
+                                *format,
+                            );
+                            self.images[unit] = Some(state);
+                        }
+                    },
                 }
             }
         }
         self.group_dirty = 0;
+
+        for image in self.writable_images.iter().flatten() {
+            if let Some(texture) = image.upgrade() {
+                texture.mark_written();
+            }
+        }
     }
 
+    // colors: (颜色附件 序号, 清屏颜色, 格式)
     fn clear_render_target(
         &mut self,
         gl: &glow::Context,
-        color: &super::super::LoadOp<super::super::Color>,
+        colors: &[(u32, super::super::Color, wgt::TextureFormat)],
         depth: Option<&super::super::LoadOp<f32>>,
         stencil: Option<&super::super::LoadOp<u32>>,
     ) {
@@ -1960,13 +4043,11 @@ impl GLStateImpl {
             .render_pipeline
             .as_ref()
             .map(|rp| rp.0.as_ref())
-            .map(|imp| (imp.ds.as_ref().imp.is_write_enable, imp.color_writes));
+            .map(|imp| (imp.ds.as_ref().imp.is_write_enable, imp.bs.clone()));
 
-        if let super::super::LoadOp::Clear(color) = color {
-            clear_mask |= glow::COLOR_BUFFER_BIT;
-
-            if let Some((_, color_writes)) = state {
-                if color_writes != ColorWrites::ALL {
+        if !colors.is_empty() {
+            if let Some((_, bs)) = state.as_ref() {
+                if !bs.is_color_writes_all() {
                     // clear 受到 color_mask 的 影响
                     unsafe {
                         gl.color_mask(true, true, true, true);
@@ -1974,17 +4055,10 @@ impl GLStateImpl {
                 }
             }
 
-            // if self.clear_color != *color {
-                unsafe {
-                    gl.clear_color(
-                        color.r as f32,
-                        color.g as f32,
-                        color.b as f32,
-                        color.a as f32,
-                    );
-                }
-                self.clear_color = *color;
-            // }
+            // 每个 附件 单独 清，整数格式 不能 用 glClearColor
+            for (index, color, format) in colors {
+                Self::clear_color_attachment(gl, *index, color, *format);
+            }
         }
 
         match depth {
@@ -2053,10 +4127,10 @@ impl GLStateImpl {
             // }
         }
 
-        if clear_mask & glow::COLOR_BUFFER_BIT != 0 {
-            if let Some((_, color_writes)) = state {
-                if color_writes != ColorWrites::ALL {
-                    Self::apply_color_mask(gl, &color_writes);
+        if !colors.is_empty() {
+            if let Some((_, bs)) = state.as_ref() {
+                if !bs.is_color_writes_all() {
+                    Self::apply_color_masks(gl, bs);
                 }
             }
         }
@@ -2075,6 +4149,33 @@ impl GLStateImpl {
         }
     }
 
+    fn clear_color_attachment(
+        gl: &glow::Context,
+        index: u32,
+        color: &super::super::Color,
+        format: wgt::TextureFormat,
+    ) {
+        unsafe {
+            match format.sample_type(None, None) {
+                Some(wgt::TextureSampleType::Sint) => gl.clear_buffer_i32_slice(
+                    glow::COLOR,
+                    index,
+                    &[color.r as i32, color.g as i32, color.b as i32, color.a as i32],
+                ),
+                Some(wgt::TextureSampleType::Uint) => gl.clear_buffer_u32_slice(
+                    glow::COLOR,
+                    index,
+                    &[color.r as u32, color.g as u32, color.b as u32, color.a as u32],
+                ),
+                _ => gl.clear_buffer_f32_slice(
+                    glow::COLOR,
+                    index,
+                    &[color.r as f32, color.g as f32, color.b as f32, color.a as f32],
+                ),
+            }
+        }
+    }
+
     #[inline]
     fn apply_alpha_to_coverage(gl: &glow::Context, alpha_to_coverage_enabled: bool) {
         if alpha_to_coverage_enabled {
@@ -2084,6 +4185,30 @@ impl GLStateImpl {
         }
     }
 
+    fn apply_color_masks(gl: &glow::Context, bs: &super::BlendState) {
+        use wgt::ColorWrites as Cw;
+
+        match bs.uniform.as_ref() {
+            Some(b) => Self::apply_color_mask(gl, &b.color_writes),
+            None => {
+                for (i, b) in bs.imp.iter().enumerate() {
+                    if let Some(b) = b {
+                        let mask = &b.color_writes;
+                        unsafe {
+                            gl.color_mask_draw_buffer(
+                                i as u32,
+                                mask.contains(Cw::RED),
+                                mask.contains(Cw::GREEN),
+                                mask.contains(Cw::BLUE),
+                                mask.contains(Cw::ALPHA),
+                            )
+                        };
+                    }
+                }
+            }
+        }
+    }
+
     #[inline]
     fn apply_color_mask(gl: &glow::Context, mask: &wgt::ColorWrites) {
         use wgt::ColorWrites as Cw;
@@ -2130,11 +4255,53 @@ impl GLStateImpl {
         Self::apply_stencil_face(&gl, glow::BACK, stencil_ref, &new, &new.back);
     }
 
-    #[inline]
-    fn apply_blend(gl: &glow::Context, new: &super::BlendStateImpl) {
-        Self::apply_blend_enable(gl, new);
-        Self::apply_blend_equation(gl, new);
-        Self::apply_blend_factor(gl, new);
+    fn apply_blend(gl: &glow::Context, new: &super::BlendState) {
+        match new.uniform.as_ref() {
+            Some(b) => {
+                Self::apply_blend_enable(gl, b);
+                Self::apply_blend_equation(gl, b);
+                Self::apply_blend_factor(gl, b);
+                Self::apply_color_mask(gl, &b.color_writes);
+            }
+            None => {
+                for (i, b) in new.imp.iter().enumerate() {
+                    if let Some(b) = b {
+                        Self::apply_blend_indexed(gl, i as u32, b);
+                    }
+                }
+            }
+        }
+    }
+
+    // 每个 颜色附件 单独 设置，要 OES_draw_buffers_indexed 或 GL 4.0
+    fn apply_blend_indexed(gl: &glow::Context, index: u32, new: &super::BlendStateImpl) {
+        use wgt::ColorWrites as Cw;
+
+        unsafe {
+            if new.is_enable {
+                gl.enable_draw_buffer(glow::BLEND, index);
+            } else {
+                gl.disable_draw_buffer(glow::BLEND, index);
+            }
+
+            gl.blend_equation_separate_draw_buffer(index, new.color.equation, new.alpha.equation);
+            gl.blend_func_separate_draw_buffer(
+                index,
+                new.color.src_factor,
+                new.color.dst_factor,
+                new.alpha.src_factor,
+                new.alpha.dst_factor,
+            );
+
+            let mask = &new.color_writes;
+            gl.color_mask_draw_buffer(
+                index,
+                mask.contains(Cw::RED),
+                mask.contains(Cw::GREEN),
+                mask.contains(Cw::BLUE),
+                mask.contains(Cw::ALPHA),
+            );
+        }
     }
 
     fn set_raster(gl: &glow::Context, new: &super::RasterStateImpl, old: &super::RasterStateImpl) {
@@ -2201,9 +4368,19 @@ impl GLStateImpl {
         );
     }
 
-    fn set_blend(gl: &glow::Context, new: &super::BlendStateImpl, old: &super::BlendStateImpl) {
+    fn set_blend(gl: &glow::Context, new: &super::BlendState, old: &super::BlendState) {
         profiling::scope!("hal::GLState::set_blend");
 
+        // 有 独立设置 的，全部 重新设置
+        let (new, old) = match (new.uniform.as_ref(), old.uniform.as_ref()) {
+            (Some(new), Some(old)) => (new, old),
+            _ => return Self::apply_blend(gl, new),
+        };
+
+        if new.color_writes != old.color_writes {
+            Self::apply_color_mask(gl, &new.color_writes);
+        }
+
         if new.is_enable != old.is_enable {
             Self::apply_blend_enable(gl, new);
         }
@@ -2503,8 +4680,57 @@ pub(crate) struct AttributeInfo {
 
 #[derive(Debug, Hash, PartialEq, Eq, Clone)]
 pub(crate) struct RenderTarget {
-    pub(crate) depth_stencil: Option<GLTextureInfo>,
-    pub(crate) colors: GLTextureInfo,
+    pub(crate) depth_stencil: Option<RenderTargetAttachment>,
+    // 下标 就是 COLOR_ATTACHMENT 的 序号
+    pub(crate) colors: Box<[Option<RenderTargetAttachment>]>,
+}
+
+impl RenderTarget {
+    // 窗口表面 只能 单独 作为 渲染目标，用 默认帧缓冲
+    #[inline]
+    pub(crate) fn is_native(&self) -> bool {
+        let is_native =
+            |a: &RenderTargetAttachment| matches!(a.raw, GLTextureInfo::NativeRenderBuffer);
+
+        match self.colors.iter().flatten().next() {
+            Some(color) => is_native(color),
+            None => self.depth_stencil.as_ref().map_or(false, is_native),
+        }
+    }
+
+    // 纹理 / 渲染缓冲 释放 时，要 删掉 用到它的 fbo
+    #[inline]
+    pub(crate) fn contains(&self, info: &GLTextureInfo) -> bool {
+        self.colors.iter().flatten().any(|c| c.raw == *info)
+            || self.depth_stencil.as_ref().map_or(false, |ds| ds.raw == *info)
+    }
+}
+
+// 渲染目标 的 一个 附件：纹理 的 哪一级 mip，哪一层 / 哪个面 / 哪个 3D 切片
+#[derive(Debug, Hash, PartialEq, Eq, Clone)]
+pub(crate) struct RenderTargetAttachment {
+    pub(crate) raw: GLTextureInfo,
+    // 纹理 的 绑定目标，比如 TEXTURE_2D / TEXTURE_CUBE_MAP / TEXTURE_2D_ARRAY / TEXTURE_3D
+    pub(crate) target: super::BindTarget,
+    pub(crate) mip_level: u32,
+    // 数组层，立方体 的 面 (CUBEMAP_FACES 的 下标)，或 3D 纹理 的 切片
+    pub(crate) layer: u32,
+}
+
+impl From<&super::TextureView> for RenderTargetAttachment {
+    fn from(value: &super::TextureView) -> Self {
+        let target = match &value.inner.inner {
+            super::TextureInner::Texture { target, .. } => *target,
+            _ => glow::RENDERBUFFER,
+        };
+
+        Self {
+            raw: GLTextureInfo::from(value),
+            target,
+            mip_level: value.mip_levels.start,
+            layer: value.array_layers.start,
+        }
+    }
 }
 
 #[derive(Debug, Hash, PartialEq, Eq, Clone)]
@@ -2516,9 +4742,9 @@ pub(crate) enum GLTextureInfo {
     Texture(glow::Texture),
 }
 
-impl From<&super::super::TextureView> for GLTextureInfo {
-    fn from(value: &super::super::TextureView) -> Self {
-        match &value.inner.inner.inner {
+impl From<&super::TextureView> for GLTextureInfo {
+    fn from(value: &super::TextureView) -> Self {
+        match &value.inner.inner {
             super::TextureInner::NativeRenderBuffer => Self::NativeRenderBuffer,
 
             super::TextureInner::Renderbuffer { raw, .. } => Self::Renderbuffer(*raw),
@@ -2538,10 +4764,24 @@ pub(crate) enum RawBindingState {
     },
     Texture {
         raw: ShareWeak<super::TextureImpl>,
+        mip_levels: std::ops::Range<u32>,
+        layers: std::ops::Range<u32>,
+        layered: bool, // 部分 层 的 D2Array 视图，拷贝 到 2D 数组
+        srgb_decode: Option<bool>,
+        layer_copy: Option<ShareWeak<ShareCell<Option<(super::Texture, u32)>>>>,
+        mip_copy: Option<ShareWeak<ShareCell<Option<(super::Texture, u32)>>>>,
     },
     Sampler {
         raw: ShareWeak<super::SamplerImpl>,
     },
+    StorageTexture {
+        raw: ShareWeak<super::TextureImpl>,
+        level: u32,
+        layered: bool, // 数组 / 立方体 / 3D 视图 绑定 全部 层
+        layer: u32,
+        access: u32,
+        format: u32,
+    },
 }
 
 impl From<&super::RawBinding> for RawBindingState {
@@ -2560,10 +4800,25 @@ impl From<&super::RawBinding> for RawBindingState {
             },
             super::RawBinding::Texture(view) => Self::Texture {
                 raw: Share::downgrade(&view.inner),
+                mip_levels: view.mip_levels.clone(),
+                layers: view.array_layers.clone(),
+                layered: view.dimension == wgt::TextureViewDimension::D2Array,
+                srgb_decode: view.srgb_decode,
+                layer_copy: view.layer_copy.as_ref().map(|c| Share::downgrade(&c.0)),
+                mip_copy: view.mip_copy.as_ref().map(|c| Share::downgrade(&c.0)),
             },
             super::RawBinding::Sampler(sampler) => Self::Sampler {
                 raw: Share::downgrade(&sampler.0),
             },
+            super::RawBinding::StorageTexture { view, access } => Self::StorageTexture {
+                raw: Share::downgrade(&view.inner),
+                level: view.mip_levels.start,
+                layered: view.dimension != wgt::TextureViewDimension::D2
+                    && view.dimension != wgt::TextureViewDimension::D1,
+                layer: view.array_layers.start,
+                access: *access,
+                format: view.format_desc.internal,
+            },
         }
     }
 }
@@ -2673,9 +4928,40 @@ fn compile_naga_shader(
         super::ShaderError::Compilation(format!("glsl::Writer::write() error = {:?}", e))
     })?;
 
+    if shader_stage == naga::ShaderStage::Vertex {
+        output = patch_y_flip(output)?;
+    }
+
     Ok((output, reflection_info))
 }
 
+// ADJUST_COORDINATE_SPACE 写死了 -gl_Position.y，换成 uniform，渲染到 默认帧缓冲 时 不 翻转
+// 值 由 GLState 按 渲染目标 设置，见 Y_FLIP_UNIFORM
+// 依赖 naga 输出 的 原文，naga 升级 后 对不上 要 报错，否则 离屏 渲染 会 上下颠倒
+fn patch_y_flip(source: String) -> Result<String, super::ShaderError> {
+    const FLIP: &str = "gl_Position.yz = vec2(-gl_Position.y,";
+    const MAIN: &str = "void main()";
+
+    if source.matches(FLIP).count() != 1 || source.matches(MAIN).count() != 1 {
+        return Err(super::ShaderError::Compilation(format!(
+            "patch_y_flip: naga output has no single `{}` / `{}`, y flip can not be patched",
+            FLIP, MAIN
+        )));
+    }
+
+    Ok(source
+        .replacen(
+            FLIP,
+            &format!("gl_Position.yz = vec2({} * gl_Position.y,", super::Y_FLIP_UNIFORM),
+            1,
+        )
+        .replacen(
+            MAIN,
+            &format!("uniform float {};\n\n{}", super::Y_FLIP_UNIFORM, MAIN),
+            1,
+        ))
+}
+
 fn compile_gl_shader(
     gl: &glow::Context,
     source: &str,
@@ -108,6 +108,20 @@ bitflags::bitflags! {
         ///
         /// When this is true, instance offset emulation via vertex buffer rebinding and a shader uniform will be disabled.
         const FULLY_FEATURED_INSTANCING = 1 << 16;
+        /// Supports `EXT_texture_sRGB_decode`, sampling sRGB textures without conversion.
+        const TEXTURE_SRGB_DECODE = 1 << 17;
+        /// Supports `glDrawArraysIndirect` / `glDrawElementsIndirect` from `DRAW_INDIRECT_BUFFER`.
+        const DRAW_INDIRECT = 1 << 18;
+        /// Supports `glDrawElementsBaseVertex` and its instanced variant.
+        const DRAW_BASE_VERTEX = 1 << 19;
+        /// Supports shader storage buffers and image load / store (`glBindImageTexture`).
+        const SHADER_STORAGE = 1 << 20;
+        /// Accepts `GL_BGRA` pixel data for `RGBA8` / `SRGB8_ALPHA8` textures.
+        const TEXTURE_FORMAT_BGRA = 1 << 21;
+        /// Writes to sRGB attachments are only encoded while `GL_FRAMEBUFFER_SRGB` is enabled.
+        const FRAMEBUFFER_SRGB = 1 << 22;
+        /// Supports `glReadPixels` with `GL_BGRA`.
+        const READ_FORMAT_BGRA = 1 << 23;
     }
 }
 
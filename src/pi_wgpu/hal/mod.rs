@@ -3,6 +3,7 @@ use thiserror::Error;
 pub mod api;
 
 mod egl_impl;
+mod egl_native;
 mod gl_cache;
 mod gl_conv;
 mod gl_state;
@@ -15,6 +16,7 @@ mod command;
 mod device;
 mod instance;
 mod pipeline;
+mod query;
 mod queue;
 mod sampler;
 mod shader_module;
@@ -34,6 +36,7 @@ pub(crate) use gl_state::*;
 pub(crate) use gles::*;
 pub use instance::*;
 pub(crate) use pipeline::*;
+pub(crate) use query::*;
 pub(crate) use queue::*;
 pub(crate) use sampler::*;
 pub(crate) use shader_module::*;
@@ -66,6 +69,10 @@ pub(crate) const MAX_BIND_GROUPS: usize = 4;
 pub(crate) const MAX_VERTEX_BUFFERS: usize = 16;
 pub(crate) const MAX_COLOR_ATTACHMENTS: usize = 8;
 pub(crate) const MAX_MIP_LEVELS: u32 = 16;
+// push constant 的 最大 字数（u32），模拟 成 普通 uniform
+pub(crate) const MAX_PUSH_CONSTANTS: usize = 64;
+// 顶点 shader 里 乘到 gl_Position.y 上 的 uniform：离屏 -1，默认帧缓冲 1
+pub(crate) const Y_FLIP_UNIFORM: &str = "pi_y_flip";
 
 pub(crate) const CUBEMAP_FACES: [u32; 6] = [
     glow::TEXTURE_CUBE_MAP_POSITIVE_X,
@@ -92,6 +99,8 @@ pub(crate) enum PipelineError {
     Linkage(wgt::ShaderStages, String),
     #[error("entry point for stage {0:?} is invalid")]
     EntryPoint(naga::ShaderStage),
+    #[error("pipeline layout is required, implicit layouts are not supported")]
+    ImplicitLayout,
     #[error(transparent)]
     Device(#[from] super::super::DeviceError),
 }
@@ -12,6 +12,7 @@ use super::{super::wgt, gl_conv as conv, AdapterContext, AttributeState, GLState
 #[derive(Debug, Clone)]
 pub(crate) struct PipelineLayout {
     pub(crate) group_infos: Box<[BindGroupLayoutInfo]>,
+    pub(crate) push_constant_ranges: Share<[wgt::PushConstantRange]>,
     pub(crate) naga_options: naga::back::glsl::Options,
     pub(crate) id: u32,
 }
@@ -44,11 +45,13 @@ impl PipelineLayout {
         let mut num_samplers = 0u8;
         let mut num_textures = 0u8;
         let mut num_uniform_buffers = 0u8;
+        let mut has_storage = false;
 
         let mut binding_map = glsl::BindingMap::default();
 
         for (group_index, bg_layout) in desc.bind_group_layouts.iter().enumerate() {
-            let bg_layout = &bg_layout.inner;
+            // wgc 已经 检查过 layout 是否 有效
+            let bg_layout = bg_layout.inner.as_ref().unwrap();
 
             // create a vector with the size enough to hold all the bindings, filled with `!0`
             let mut binding_to_slot = vec![
@@ -68,7 +71,15 @@ impl PipelineLayout {
                         ty: wgt::BufferBindingType::Uniform,
                         ..
                     } => &mut num_uniform_buffers,
-                    _ => unreachable!(),
+                    // SSBO / Storage Texture 的 绑定点 在 编译 shader 时 全局 分配
+                    wgt::BindingType::Buffer {
+                        ty: wgt::BufferBindingType::Storage { .. },
+                        ..
+                    }
+                    | wgt::BindingType::StorageTexture { .. } => {
+                        has_storage = true;
+                        continue;
+                    }
                 };
 
                 binding_to_slot[entry.binding as usize] = *counter;
@@ -81,8 +92,14 @@ impl PipelineLayout {
             }
         }
 
+        // 300 es 没有 SSBO / image，用到 Storage 的 管线 换成 310 es / 430
+        let version = match adapter.compute_shading_language_version() {
+            Some(version) if has_storage => version,
+            _ => adapter.shading_language_version(),
+        };
+
         let naga_options = glsl::Options {
-            version: adapter.shading_language_version(),
+            version,
             writer_flags,
             binding_map,
             zero_initialize_workgroup_memory: true,
@@ -92,12 +109,13 @@ impl PipelineLayout {
             .bind_group_layouts
             .iter()
             .map(|layout| BindGroupLayoutInfo {
-                entries: layout.inner.entries.clone(),
+                entries: layout.inner.as_ref().unwrap().entries.clone(),
             })
             .collect();
 
         Ok(Self {
             group_infos,
+            push_constant_ranges: desc.push_constant_ranges.to_vec().into(),
             naga_options,
             id: PIPELINE_LAYOUT_AROM.fetch_add(1, std::sync::atomic::Ordering::Relaxed),
         })
@@ -116,7 +134,6 @@ pub(crate) struct RenderPipelineImpl {
 
     pub(crate) program: super::Program,
 
-    pub(crate) color_writes: wgt::ColorWrites,
     pub(crate) attributes: super::AttributeState,
 
     pub(crate) rs: Share<super::RasterState>,
@@ -144,20 +161,25 @@ impl RenderPipelineImpl {
         let vs = &desc.vertex;
         let fs = desc.fragment.as_ref().unwrap();
 
-        let layout = desc.layout.as_ref().unwrap().inner.clone();
+        let layout = desc
+            .layout
+            .ok_or(super::PipelineError::ImplicitLayout)?
+            .inner
+            .clone()
+            .unwrap();
 
         let naga_options = &layout.naga_options;
 
-        {
+        let (vs_id, fs_id) = {
             let lock = adapter.lock(None);
             let gl = lock.get_glow();
 
             let version = gl.version().clone();
 
-            state
+            let vs_id = state
                 .compile_shader(
                     &gl,
-                    &vs.module.inner,
+                    vs.module.inner.as_ref().unwrap(),
                     naga::ShaderStage::Vertex,
                     &version,
                     device_features,
@@ -170,10 +192,10 @@ impl RenderPipelineImpl {
                     super::PipelineError::Linkage(wgt::ShaderStages::VERTEX, e.to_string())
                 })?;
 
-            state
+            let fs_id = state
                 .compile_shader(
                     &gl,
-                    &fs.module.inner,
+                    fs.module.inner.as_ref().unwrap(),
                     naga::ShaderStage::Fragment,
                     &version,
                     device_features,
@@ -185,9 +207,11 @@ impl RenderPipelineImpl {
                 .map_err(|e| {
                     super::PipelineError::Linkage(wgt::ShaderStages::FRAGMENT, e.to_string())
                 })?;
-        }
 
-        let program = Self::create_program(&state, adapter, &vs.module.inner, &fs.module.inner)?;
+            (vs_id, fs_id)
+        };
+
+        let program = Self::create_program(&state, adapter, vs_id, fs_id)?;
 
         let layout_reoder = program.reorder(&layout);
 
@@ -199,7 +223,14 @@ impl RenderPipelineImpl {
         let ds = Self::create_ds(&state, desc.depth_stencil.as_ref());
         let ss = Self::create_ss(&state, desc.depth_stencil.as_ref());
 
-        let (bs, color_writes) = Self::create_bs(&state, fs);
+        let bs = Self::create_bs(
+            &state,
+            adapter
+                .downlevel()
+                .flags
+                .contains(wgt::DownlevelFlags::INDEPENDENT_BLEND),
+            fs,
+        );
 
         Ok(Self {
             topology,
@@ -209,7 +240,6 @@ impl RenderPipelineImpl {
             layout,
             layout_reoder,
 
-            color_writes,
             attributes,
 
             rs,
@@ -221,19 +251,111 @@ impl RenderPipelineImpl {
     }
 }
 
+#[derive(Debug, Clone)]
+pub(crate) struct ComputePipeline(pub(crate) Share<ComputePipelineImpl>);
+
+#[derive(Debug)]
+pub(crate) struct ComputePipelineImpl {
+    pub(crate) layout: PipelineLayout,
+    pub(crate) layout_reoder: Box<[Box<[usize]>]>,
+
+    pub(crate) program: super::Program,
+    pub(crate) id: u32,
+}
+
+impl ComputePipelineImpl {
+    pub fn new(
+        state: &GLState,
+        adapter: &AdapterContext,
+        device_features: &wgt::Features,
+        desc: &super::super::ComputePipelineDescriptor,
+    ) -> Result<Self, super::PipelineError> {
+        // 渲染 shader 用的 300 es 不支持 compute，换成 310 es / 430
+        let version = adapter.compute_shading_language_version().ok_or_else(|| {
+            super::PipelineError::Linkage(
+                wgt::ShaderStages::COMPUTE,
+                "compute shaders are not supported, need GLES 3.1 or GL 4.3".to_string(),
+            )
+        })?;
+
+        let layout = desc
+            .layout
+            .ok_or(super::PipelineError::ImplicitLayout)?
+            .inner
+            .clone()
+            .unwrap();
+
+        let mut naga_options = layout.naga_options.clone();
+        naga_options.version = version;
+
+        let cs = desc.module.inner.as_ref().unwrap();
+
+        let cs_id = {
+            let lock = adapter.lock(None);
+            let gl = lock.get_glow();
+
+            let gl_version = gl.version().clone();
+
+            state
+                .compile_shader(
+                    &gl,
+                    cs,
+                    naga::ShaderStage::Compute,
+                    &gl_version,
+                    device_features,
+                    &adapter.downlevel(),
+                    desc.entry_point.to_string(),
+                    None,
+                    &naga_options,
+                )
+                .map_err(|e| {
+                    super::PipelineError::Linkage(wgt::ShaderStages::COMPUTE, e.to_string())
+                })?
+        };
+
+        let program = match state.get_program(&(cs_id, cs_id)) {
+            Some(program) => program,
+            None => {
+                let program = ProgramImpl::new_compute(state, adapter, cs_id).map_err(|e| {
+                    super::PipelineError::Linkage(wgt::ShaderStages::COMPUTE, e.to_string())
+                })?;
+
+                let id = program.id;
+
+                let program = Program(Share::new(program));
+
+                state.insert_program(id, program.clone());
+                program
+            }
+        };
+
+        let layout_reoder = program.reorder(&layout);
+
+        Ok(Self {
+            layout,
+            layout_reoder,
+            program,
+            id: POPELINE_AROM.fetch_add(1, std::sync::atomic::Ordering::Relaxed),
+        })
+    }
+}
+
 impl RenderPipelineImpl {
     fn create_program(
         state: &GLState,
         adapter: &AdapterContext,
-        vs: &super::ShaderModule,
-        fs: &super::ShaderModule,
+        vs_id: super::ShaderID,
+        fs_id: super::ShaderID,
     ) -> Result<Program, super::PipelineError> {
-        let vs_id = vs.id;
-        let fs_id = fs.id;
         match state.get_program(&(vs_id, fs_id)) {
             Some(program) => Ok(program),
             None => {
-                let program = ProgramImpl::new(state, adapter, vs, fs).unwrap();
+                let program = ProgramImpl::new(state, adapter, vs_id, fs_id).map_err(|e| {
+                    super::PipelineError::Linkage(
+                        wgt::ShaderStages::VERTEX | wgt::ShaderStages::FRAGMENT,
+                        e.to_string(),
+                    )
+                })?;
 
                 let id = program.id;
 
@@ -346,43 +468,56 @@ impl RenderPipelineImpl {
         state.get_or_insert_ss(ss)
     }
 
+    // 每个 颜色附件 一个 混合状态，不支持 独立混合 时 都用 第一个 有效的 target
     fn create_bs(
         state: &GLState,
+        is_independent: bool,
         desc: &super::super::FragmentState<'_>,
-    ) -> (Share<super::BlendState>, wgt::ColorWrites) {
-        assert!(desc.targets.len() < 2);
+    ) -> Share<super::BlendState> {
+        assert!(desc.targets.len() <= super::MAX_COLOR_ATTACHMENTS);
 
-        let (bs, color_writes) = if desc.targets.len() == 0 || desc.targets[0].is_none() {
-            (super::BlendStateImpl::default(), wgt::ColorWrites::ALL)
-        } else {
-            let b = desc.targets[0].as_ref().unwrap();
+        let mut targets: Vec<Option<super::BlendStateImpl>> = desc
+            .targets
+            .iter()
+            .map(|t| t.as_ref().map(Self::create_blend_target))
+            .collect();
 
-            let color_writes = b.write_mask;
+        if !is_independent {
+            let first = targets.iter().flatten().next().cloned();
+            if let Some(first) = first {
+                if targets.iter().flatten().any(|t| *t != first) {
+                    log::warn!("create_render_pipeline: independent blend is not supported, all color targets use the blend state and write mask of the first one");
+                }
 
-            if b.blend.is_none() {
-                (super::BlendStateImpl::default(), color_writes)
-            } else {
-                let b = b.blend.as_ref().unwrap();
+                for t in targets.iter_mut().flatten() {
+                    *t = first.clone();
+                }
+            }
+        }
+
+        state.get_or_insert_bs(targets.into_boxed_slice())
+    }
 
+    fn create_blend_target(desc: &super::super::ColorTargetState) -> super::BlendStateImpl {
+        let color_writes = desc.write_mask;
+
+        match desc.blend.as_ref() {
+            None => super::BlendStateImpl {
+                color_writes,
+                ..Default::default()
+            },
+            Some(b) => {
                 let is_enable = b.color != wgt::BlendComponent::REPLACE
                     || b.alpha != wgt::BlendComponent::REPLACE;
 
-                let color = conv::map_blend_component(&b.color);
-                let alpha = conv::map_blend_component(&b.alpha);
-
-                (
-                    super::BlendStateImpl {
-                        is_enable,
-                        color,
-                        alpha,
-                    },
+                super::BlendStateImpl {
+                    is_enable,
+                    color: conv::map_blend_component(&b.color),
+                    alpha: conv::map_blend_component(&b.alpha),
                     color_writes,
-                )
+                }
             }
-        };
-
-        let bs = state.get_or_insert_bs(bs);
-        (bs, color_writes)
+        }
     }
 
     #[inline]
@@ -398,13 +533,31 @@ impl RenderPipelineImpl {
 
 #[derive(Debug)]
 pub(crate) struct BlendState {
-    pub(crate) imp: BlendStateImpl,
+    // 下标 是 颜色附件 的 序号，None 表示 该 槽位 没有 target
+    pub(crate) imp: Box<[Option<BlendStateImpl>]>,
+    // 所有 target 的 状态 都 一样 时，用 全局的 glBlendFunc / glColorMask 设置
+    pub(crate) uniform: Option<BlendStateImpl>,
 }
 
 impl BlendState {
     #[inline]
-    pub(crate) fn new(imp: BlendStateImpl) -> Self {
-        Self { imp }
+    pub(crate) fn new(imp: Box<[Option<BlendStateImpl>]>) -> Self {
+        let mut targets = imp.iter().flatten();
+
+        let uniform = match targets.next() {
+            None => Some(BlendStateImpl::default()),
+            Some(first) => targets.all(|t| t == first).then(|| first.clone()),
+        };
+
+        Self { imp, uniform }
+    }
+
+    #[inline]
+    pub(crate) fn is_color_writes_all(&self) -> bool {
+        self.imp
+            .iter()
+            .flatten()
+            .all(|t| t.color_writes == wgt::ColorWrites::ALL)
     }
 }
 
@@ -414,6 +567,8 @@ pub(crate) struct BlendStateImpl {
 
     pub(crate) color: super::BlendComponent,
     pub(crate) alpha: super::BlendComponent,
+
+    pub(crate) color_writes: wgt::ColorWrites,
 }
 
 impl Default for BlendStateImpl {
@@ -423,6 +578,7 @@ impl Default for BlendStateImpl {
             is_enable: true,
             color: Default::default(),
             alpha: Default::default(),
+            color_writes: wgt::ColorWrites::ALL,
         }
     }
 }
@@ -614,6 +770,12 @@ pub(crate) struct ProgramImpl {
 
     // Box 中的顺序 和 RenderPipelineLayout 的 一样
     pub(crate) uniforms: Box<[Box<[PiBindEntry]>]>,
+
+    // push constant 模拟 的 uniform，draw 时 上传
+    pub(crate) push_constants: Box<[super::PushConstantDesc]>,
+
+    // 翻转 y 的 uniform，compute 没有
+    pub(crate) y_flip: Option<glow::UniformLocation>,
 }
 
 impl Drop for ProgramImpl {
@@ -635,19 +797,44 @@ impl ProgramImpl {
     fn new(
         state: &GLState,
         adapter: &AdapterContext,
-        vs: &super::ShaderModule,
-        fs: &super::ShaderModule,
+        vs_id: super::ShaderID,
+        fs_id: super::ShaderID,
+    ) -> Result<Self, super::ShaderError> {
+        let lock = adapter.lock(None);
+        let gl = lock.get_glow();
+
+        let (raw, uniforms, push_constants) = state.create_program(&gl, vs_id, fs_id)?;
+
+        let y_flip = unsafe { gl.get_uniform_location(raw, super::Y_FLIP_UNIFORM) };
+
+        Ok(Self {
+            raw,
+            adapter: adapter.clone(),
+            id: (vs_id, fs_id),
+            uniforms,
+            push_constants,
+            y_flip,
+        })
+    }
+
+    // compute program 只有 一个 shader，id 用 (cs, cs)
+    fn new_compute(
+        state: &GLState,
+        adapter: &AdapterContext,
+        cs_id: super::ShaderID,
     ) -> Result<Self, super::ShaderError> {
         let lock = adapter.lock(None);
         let gl = lock.get_glow();
 
-        let (raw, uniforms) = state.create_program(&gl, vs.id, fs.id)?;
+        let (raw, uniforms, push_constants) = state.create_compute_program(&gl, cs_id)?;
 
         Ok(Self {
             raw,
             adapter: adapter.clone(),
-            id: (vs.id, fs.id),
+            id: (cs_id, cs_id),
             uniforms,
+            push_constants,
+            y_flip: None,
         })
     }
 }
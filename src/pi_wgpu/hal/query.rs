@@ -0,0 +1,70 @@
+use glow::HasContext;
+use pi_share::Share;
+
+use super::{super::wgt, AdapterContext};
+
+#[derive(Debug, Clone)]
+pub(crate) struct QuerySet(pub(crate) Share<QuerySetImpl>);
+
+impl QuerySet {
+    pub fn new(
+        adapter: &AdapterContext,
+        desc: &super::super::QuerySetDescriptor,
+    ) -> Result<Self, super::super::DeviceError> {
+        let lock = adapter.lock(None);
+        let gl = lock.get_glow();
+
+        let target = match desc.ty {
+            // GLES / WebGL2 用 保守 版本，允许 误报，但 不会 漏报，剔除 够用
+            // 桌面 GL 4.3 才有 保守 版本，用 3.3 核心 的 ANY_SAMPLES_PASSED
+            wgt::QueryType::Occlusion if gl.version().is_embedded => {
+                glow::ANY_SAMPLES_PASSED_CONSERVATIVE
+            }
+            wgt::QueryType::Occlusion => glow::ANY_SAMPLES_PASSED,
+            wgt::QueryType::Timestamp => glow::TIMESTAMP,
+            wgt::QueryType::PipelineStatistics(_) => {
+                return Err(super::super::DeviceError::UnsupportedFeature(
+                    wgt::Features::PIPELINE_STATISTICS_QUERY,
+                ));
+            }
+        };
+
+        let mut queries = Vec::with_capacity(desc.count as usize);
+        for _ in 0..desc.count {
+            let query =
+                unsafe { gl.create_query() }.map_err(|_| super::super::DeviceError::OutOfMemory)?;
+            queries.push(query);
+        }
+
+        let imp = QuerySetImpl {
+            raw: queries.into_boxed_slice(),
+            target,
+            adapter: adapter.clone(),
+        };
+
+        Ok(Self(Share::new(imp)))
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct QuerySetImpl {
+    pub(crate) raw: Box<[glow::Query]>,
+    // ANY_SAMPLES_PASSED(_CONSERVATIVE) 或 TIMESTAMP
+    pub(crate) target: u32,
+
+    adapter: AdapterContext,
+}
+
+impl Drop for QuerySetImpl {
+    #[inline]
+    fn drop(&mut self) {
+        let lock = self.adapter.lock(None);
+        let gl = lock.get_glow();
+
+        for &query in self.raw.iter() {
+            unsafe {
+                gl.delete_query(query);
+            }
+        }
+    }
+}
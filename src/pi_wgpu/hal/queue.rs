@@ -8,11 +8,16 @@ pub(crate) struct Queue {
 
 impl Queue {
     #[inline]
-    pub(crate) fn submit<I: IntoIterator<Item = super::CommandBuffer>>(&self, _command_buffers: I) {
+    pub(crate) fn submit<I: IntoIterator<Item = super::CommandBuffer>>(&self, command_buffers: I) {
         let lock = self.adapter.lock(None);
 
         let gl = lock.get_glow();
 
+        // 按 提交顺序 回放
+        for cb in command_buffers {
+            cb.execute(&gl);
+        }
+
         self.state.clear_cache(&gl);
     }
 }
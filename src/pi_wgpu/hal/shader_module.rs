@@ -37,7 +37,7 @@ impl ShaderModule {
             state,
             adapter: adapter.clone(),
             id,
-            input: ShaderInput::from(desc),
+            input: ShaderInput::try_from(desc)?,
         })
     }
 }
@@ -52,10 +52,12 @@ pub(crate) enum ShaderInput {
     },
 }
 
-impl From<&ShaderModuleDescriptor<'_>> for ShaderInput {
+impl TryFrom<&ShaderModuleDescriptor<'_>> for ShaderInput {
+    type Error = super::ShaderError;
+
     #[inline]
-    fn from(value: &ShaderModuleDescriptor) -> Self {
-        match &value.source {
+    fn try_from(value: &ShaderModuleDescriptor) -> Result<Self, Self::Error> {
+        let r = match &value.source {
             crate::ShaderSource::Naga(module) => {
                 let module = match module {
                     std::borrow::Cow::Borrowed(m) => (**m).clone(),
@@ -63,6 +65,38 @@ impl From<&ShaderModuleDescriptor<'_>> for ShaderInput {
                 };
                 Self::Naga(module)
             }
+            // wgsl 在 创建时 就 解析，语法错误 在 create_shader_module 报告，多个 入口 只 解析 一次
+            #[cfg(feature = "wgsl")]
+            crate::ShaderSource::Wgsl(shader) => {
+                let module = naga::front::wgsl::parse_str(shader).map_err(|e| {
+                    super::ShaderError::Compilation(format!(
+                        "naga parse wgsl err = {}",
+                        e.emit_to_string(shader)
+                    ))
+                })?;
+
+                Self::Naga(module)
+            }
+            // spirv 在 创建时 就 转成 naga::Module，后面 和 Naga 走 同一条路
+            #[cfg(feature = "spirv")]
+            crate::ShaderSource::SpirV(words) => {
+                let options = naga::front::spv::Options {
+                    adjust_coordinate_space: false,
+                    strict_capabilities: true,
+                    block_ctx_dump_prefix: None,
+                };
+
+                let module = naga::front::spv::Frontend::new(words.iter().cloned(), &options)
+                    .parse()
+                    .map_err(|e| {
+                        super::ShaderError::Compilation(format!(
+                            "naga parse spirv err = {:?}",
+                            e
+                        ))
+                    })?;
+
+                Self::Naga(module)
+            }
             crate::ShaderSource::Glsl {
                 shader,
                 stage,
@@ -72,7 +106,9 @@ impl From<&ShaderModuleDescriptor<'_>> for ShaderInput {
                 stage: *stage,
                 defines: defines.clone(),
             },
-        }
+        };
+
+        Ok(r)
     }
 }
 
@@ -93,6 +129,12 @@ impl From<&ShaderModuleDescriptor<'_>> for ShaderInput {
 //          gl.bindTexture(gl.TEXTURE_2D, texture);
 //      对 Sampler:
 //          gl.bindSampler(glow_binding, sampler);
+//      对 SSBO:
+//          gl.bindBufferRange(gl.SHADER_STORAGE_BUFFER, glow_binding, buffer, offset, size);
+//      对 Storage Texture:
+//          gl.bindImageTexture(glow_binding, texture, level, layered, layer, access, format);
+//
+// SSBO 和 Storage Texture 的 绑定点 GLES 3.1 不能 用 API 修改，编译 时 写进 shader 的 layout(binding)
 //
 #[derive(Clone, Debug)]
 pub(crate) struct PiBindEntry {
@@ -104,12 +146,33 @@ pub(crate) struct PiBindEntry {
     pub(crate) glow_binding: u32, // 编译后 由 程序分配的绑定，Buffer 和 Sampler/Texture 分两组编码
 }
 
+// push constant 模拟 成 普通 uniform 结构体，naga 把 每个 成员 展开成 一个 uniform
+// access_path 如 _push_constant_binding_vs.model，offset / size 是 字节
+#[derive(Clone, Debug)]
+pub(crate) struct PushConstantItem {
+    pub(crate) access_path: String,
+    pub(crate) ty: naga::TypeInner,
+    pub(crate) offset: u32,
+    pub(crate) size: u32,
+}
+
+// 链接 之后 的 push constant 成员，draw 时 用 glUniform* 上传
+#[derive(Clone, Debug)]
+pub(crate) struct PushConstantDesc {
+    pub(crate) location: glow::UniformLocation,
+    pub(crate) ty: naga::TypeInner,
+    pub(crate) offset: u32,
+    pub(crate) size: u32,
+}
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 #[repr(C)]
 pub(crate) enum PiBindingType {
     Buffer,
     Texture,
     Sampler,
+    StorageBuffer,
+    StorageTexture,
 }
 
 #[derive(Debug)]
@@ -121,11 +184,24 @@ pub(crate) struct ShaderBindingMap {
     next_sampler_id: usize,
     max_textures_slots: usize,
     sampler_map: XHashMap<PiResourceBinding, usize>,
+
+    next_ssbo_id: usize,
+    max_storage_buffer_bindings: usize,
+    ssbo_map: XHashMap<PiResourceBinding, usize>,
+
+    next_image_id: usize,
+    max_image_units: usize,
+    image_map: XHashMap<PiResourceBinding, usize>,
 }
 
 impl ShaderBindingMap {
     #[inline]
-    pub(crate) fn new(max_uniform_buffer_bindings: usize, max_textures_slots: usize) -> Self {
+    pub(crate) fn new(
+        max_uniform_buffer_bindings: usize,
+        max_textures_slots: usize,
+        max_storage_buffer_bindings: usize,
+        max_image_units: usize,
+    ) -> Self {
         Self {
             next_ubo_id: 0,
             max_uniform_buffer_bindings,
@@ -134,6 +210,14 @@ impl ShaderBindingMap {
             next_sampler_id: 0,
             max_textures_slots,
             sampler_map: Default::default(),
+
+            next_ssbo_id: 0,
+            max_storage_buffer_bindings,
+            ssbo_map: Default::default(),
+
+            next_image_id: 0,
+            max_image_units,
+            image_map: Default::default(),
         }
     }
 
@@ -164,6 +248,36 @@ impl ShaderBindingMap {
 
         *r as u32
     }
+
+    // 调用前 保证 max_storage_buffer_bindings > 0
+    #[inline]
+    pub(crate) fn get_or_insert_ssbo(&mut self, binding: PiResourceBinding) -> u32 {
+        let r = self.ssbo_map.entry(binding).or_insert_with(|| {
+            let r = self.next_ssbo_id;
+
+            self.next_ssbo_id += 1;
+            self.next_ssbo_id %= self.max_storage_buffer_bindings;
+
+            r
+        });
+
+        *r as u32
+    }
+
+    // 调用前 保证 max_image_units > 0
+    #[inline]
+    pub(crate) fn get_or_insert_image(&mut self, binding: PiResourceBinding) -> u32 {
+        let r = self.image_map.entry(binding).or_insert_with(|| {
+            let r = self.next_image_id;
+
+            self.next_image_id += 1;
+            self.next_image_id %= self.max_image_units;
+
+            r
+        });
+
+        *r as u32
+    }
 }
 
 /// Pipeline binding information for global resources.
@@ -10,8 +10,29 @@ layout(location = 0) out vec4 o_Target;
 layout(set = 0, binding = 0) uniform sampler samp;
 layout(set = 0, binding = 1) uniform texture2D tex2d;
 
+#if defined(SRGB_ENCODE)
+// 线性 -> sRGB
+vec3 linear_to_srgb(vec3 c) {
+    vec3 lo = c * 12.92;
+    vec3 hi = 1.055 * pow(c, vec3(1.0 / 2.4)) - 0.055;
+    return mix(lo, hi, step(vec3(0.0031308), c));
+}
+#endif
+
 void main() {
     vec4 color = texture(sampler2D(tex2d, samp), vUv);
 
+#if defined(SRGB_ENCODE)
+    // 采样 sRGB 纹理 得到 线性 值，默认帧缓冲 不会 编码
+    color.rgb = linear_to_srgb(color.rgb);
+#endif
+
+#if defined(ALPHA_OPAQUE)
     o_Target = vec4(color.rgb, 1.0);
-}
\ No newline at end of file
+#elif defined(ALPHA_POST_MULTIPLIED)
+    // 窗口系统 按 预乘 合成，这里 先 预乘
+    o_Target = vec4(color.rgb * color.a, color.a);
+#else
+    o_Target = color;
+#endif
+}
\ No newline at end of file
@@ -86,6 +86,11 @@ impl Surface {
         r
     }
 
+    #[inline]
+    pub(crate) fn set_direct_present(&self, enable: bool) {
+        self.imp.as_ref().borrow_mut().direct_present = enable;
+    }
+
     #[inline]
     pub(crate) fn acquire_texture(&self) -> Option<super::Texture> {
         // log::trace!(
@@ -111,6 +116,12 @@ pub(crate) struct SurfaceImpl {
     adapter: AdapterContext,
 
     sc: Option<SwapChain>,
+
+    // 当前 的 交换间隔，创建 EGL 实例 时 打开了 垂直同步
+    swap_interval: i32,
+
+    // 直接 渲染到 默认帧缓冲，下一次 configure 时 生效
+    direct_present: bool,
 }
 
 impl SurfaceImpl {
@@ -124,6 +135,8 @@ impl SurfaceImpl {
             raw,
             adapter,
             sc: None,
+            swap_interval: 1,
+            direct_present: false,
         })
     }
 
@@ -141,11 +154,75 @@ impl SurfaceImpl {
 
         self.adapter.set_surface(clone);
 
+        let present_modes = self.adapter.present_modes();
+        let present_mode = match config.present_mode {
+            wgt::PresentMode::AutoVsync => wgt::PresentMode::Fifo,
+            wgt::PresentMode::AutoNoVsync => {
+                if present_modes.contains(&wgt::PresentMode::Immediate) {
+                    wgt::PresentMode::Immediate
+                } else {
+                    wgt::PresentMode::Fifo
+                }
+            }
+            mode => mode,
+        };
+        if !present_modes.contains(&present_mode) {
+            log::error!(
+                "requested present mode {:?} is not in the list of supported present modes: {:?}",
+                config.present_mode,
+                present_modes
+            );
+            return Err(super::SurfaceError::Other("unsupported present mode"));
+        }
+
+        let swap_interval = match present_mode {
+            wgt::PresentMode::Immediate => 0,
+            _ => 1,
+        };
+        if self.swap_interval != swap_interval {
+            self.adapter.set_swap_interval(&self.raw, swap_interval);
+            self.swap_interval = swap_interval;
+        }
+
+        let alpha_modes = self.adapter.alpha_modes();
+        let alpha_mode = match config.alpha_mode {
+            wgt::CompositeAlphaMode::Auto => wgt::CompositeAlphaMode::Opaque,
+            // 窗口系统 按 预乘 合成，blit 直接 输出 纹理 的 alpha
+            wgt::CompositeAlphaMode::Inherit => {
+                if alpha_modes.contains(&wgt::CompositeAlphaMode::PreMultiplied) {
+                    wgt::CompositeAlphaMode::PreMultiplied
+                } else {
+                    wgt::CompositeAlphaMode::Opaque
+                }
+            }
+            mode => mode,
+        };
+        if !alpha_modes.contains(&alpha_mode) {
+            log::error!(
+                "requested alpha mode {:?} is not in the list of supported alpha modes: {:?}",
+                config.alpha_mode,
+                alpha_modes
+            );
+            return Err(super::SurfaceError::Other("unsupported alpha mode"));
+        }
+
         if self.sc.is_none() {
-            self.sc = Some(SwapChain::new(device, config));
+            self.sc = Some(SwapChain::new(device, config, alpha_mode));
         }
 
-        self.sc.as_mut().unwrap().configure(device, config);
+        // 默认帧缓冲 是 线性 的，sRGB 格式 要 经过 blit 编码
+        let direct = self.direct_present && !config.format.is_srgb();
+        if self.direct_present && !direct {
+            log::warn!(
+                "direct present is not supported with {:?}, fall back to blit",
+                config.format
+            );
+        }
+
+        self.sc
+            .as_mut()
+            .unwrap()
+            .configure(device, config, alpha_mode, direct);
 
         Ok(())
     }
@@ -158,7 +235,9 @@ impl SurfaceImpl {
     #[inline]
     fn present(&mut self) {
         self.sc.as_mut().map(|sc| {
-            sc.draw_y_flip();
+            if !sc.direct {
+                sc.draw_y_flip();
+            }
         });
 
         self.adapter.present(&self.raw);
@@ -215,15 +294,22 @@ struct SwapChain {
     encoder: crate::CommandEncoder,
 
     rp: crate::RenderPipeline,
+    pipeline_layout: crate::PipelineLayout,
+    vs: crate::ShaderModule,
+    // rp 对应 的 合成模式 和 格式，变化时 重建 rp
+    alpha_mode: wgt::CompositeAlphaMode,
+    format: wgt::TextureFormat,
     vb: crate::Buffer,
     sampler: crate::Sampler,
     bg_layout: crate::BindGroupLayout,
 
-    texture_size: (u32, u32),
-    texture: crate::Texture,
-    bg: crate::BindGroup,
+    // 离屏纹理，direct 时 为 None
+    texture: Option<crate::Texture>,
+    bg: Option<crate::BindGroup>,
 
     native_texture: crate::Texture,
+    // 直接 渲染到 默认帧缓冲，present 时 不用 blit
+    direct: bool,
     // 初始化 有值
     // 每次 acquire_texture 就为 None
     // present 后 会重新 有值
@@ -231,35 +317,40 @@ struct SwapChain {
 }
 
 impl SwapChain {
-    fn new(device: &crate::Device, config: &crate::SurfaceConfiguration) -> Self {
+    fn new(
+        device: &crate::Device,
+        config: &crate::SurfaceConfiguration,
+        alpha_mode: wgt::CompositeAlphaMode,
+    ) -> Self {
         let encoder = device.create_command_encoder(&super::super::CommandEncoderDescriptor {
             label: Some("Flip-Y Command Encoder"),
         });
 
+        // 默认帧缓冲 不 翻转 y，离屏纹理 的 第 0 行 (v = 0) 是 画面 顶部
         let vertices = [
             BlitVertex {
                 pos: [-1.0, -1.0],
-                uv: [0.0, 0.0],
+                uv: [0.0, 1.0],
             },
             BlitVertex {
                 pos: [1.0, -1.0],
-                uv: [1.0, 0.0],
+                uv: [1.0, 1.0],
             },
             BlitVertex {
                 pos: [1.0, 1.0],
-                uv: [1.0, 1.0],
+                uv: [1.0, 0.0],
             },
             BlitVertex {
                 pos: [-1.0, -1.0],
-                uv: [0.0, 0.0],
+                uv: [0.0, 1.0],
             },
             BlitVertex {
                 pos: [1.0, 1.0],
-                uv: [1.0, 1.0],
+                uv: [1.0, 0.0],
             },
             BlitVertex {
                 pos: [-1.0, 1.0],
-                uv: [0.0, 1.0],
+                uv: [0.0, 0.0],
             },
         ];
 
@@ -317,29 +408,149 @@ impl SwapChain {
             },
         });
 
+        let rp = Self::create_pipeline(device, &pipeline_layout, &vs, config.format, alpha_mode);
+
+        let sampler = device.create_sampler(&super::super::SamplerDescriptor {
+            label: Some("Flip-Y Sampler"),
+            address_mode_u: wgt::AddressMode::ClampToEdge,
+            address_mode_v: wgt::AddressMode::ClampToEdge,
+            address_mode_w: wgt::AddressMode::ClampToEdge,
+            mag_filter: wgt::FilterMode::Nearest,
+            min_filter: wgt::FilterMode::Nearest,
+            mipmap_filter: wgt::FilterMode::Nearest,
+            ..Default::default()
+        });
+
+        let native_texture =
+            device.create_texture_from_surface(config.width, config.height, config.format);
+
+        // 离屏纹理 在 configure 时 按需 创建
+        Self {
+            encoder,
+
+            rp,
+            pipeline_layout,
+            vs,
+            alpha_mode,
+            format: config.format,
+            vb,
+            bg: None,
+            sampler,
+            bg_layout,
+
+            texture: None,
+            current_texture: None,
+
+            native_texture,
+            direct: false,
+        }
+    }
+
+    fn configure(
+        &mut self,
+        device: &crate::Device,
+        config: &crate::SurfaceConfiguration,
+        alpha_mode: wgt::CompositeAlphaMode,
+        direct: bool,
+    ) {
+        let format_changed = self.format != config.format;
+
+        if self.alpha_mode != alpha_mode || format_changed {
+            self.rp = Self::create_pipeline(
+                device,
+                &self.pipeline_layout,
+                &self.vs,
+                config.format,
+                alpha_mode,
+            );
+            self.alpha_mode = alpha_mode;
+            self.format = config.format;
+        }
+
+        let size = self.native_texture.size();
+        let size_changed =
+            size.width != config.width || size.height != config.height || format_changed;
+
+        if size_changed {
+            self.native_texture =
+                device.create_texture_from_surface(config.width, config.height, config.format);
+        }
+
+        let mut target_changed = size_changed || self.direct != direct;
+
+        if direct {
+            // 直接 渲染到 默认帧缓冲，不需要 离屏纹理
+            self.texture = None;
+            self.bg = None;
+        } else if size_changed || self.texture.is_none() {
+            let texture =
+                Self::create_surface_texture(device, config.width, config.height, config.format);
+            let texture_view = texture.create_view(&Default::default());
+
+            self.bg = Some(device.create_bind_group(&super::super::BindGroupDescriptor {
+                label: Some("Flip-Y BindGroup"),
+                layout: &self.bg_layout,
+                entries: &[
+                    super::super::BindGroupEntry {
+                        binding: 0,
+                        resource: super::super::BindingResource::Sampler(&self.sampler),
+                    },
+                    super::super::BindGroupEntry {
+                        binding: 1,
+                        resource: super::super::BindingResource::TextureView(&texture_view),
+                    },
+                ],
+            }));
+            self.texture = Some(texture);
+            target_changed = true;
+        }
+
+        if target_changed {
+            self.direct = direct;
+            self.current_texture = None;
+            self.update_current_texture();
+        }
+    }
+
+    fn create_pipeline(
+        device: &crate::Device,
+        pipeline_layout: &crate::PipelineLayout,
+        vs: &crate::ShaderModule,
+        format: wgt::TextureFormat,
+        alpha_mode: wgt::CompositeAlphaMode,
+    ) -> crate::RenderPipeline {
+        // Opaque 强制 alpha = 1，PostMultiplied 在 blit 里 预乘，PreMultiplied 原样 输出
+        let mut defines = naga::FastHashMap::default();
+        match alpha_mode {
+            wgt::CompositeAlphaMode::Opaque => {
+                defines.insert("ALPHA_OPAQUE".to_string(), "1".to_string());
+            }
+            wgt::CompositeAlphaMode::PostMultiplied => {
+                defines.insert("ALPHA_POST_MULTIPLIED".to_string(), "1".to_string());
+            }
+            _ => {}
+        }
+        // 默认帧缓冲 是 线性 的，sRGB 格式 在 blit 里 编码
+        if format.is_srgb() {
+            defines.insert("SRGB_ENCODE".to_string(), "1".to_string());
+        }
+
         let fs = device.create_shader_module(super::super::ShaderModuleDescriptor {
             label: Some("Flip-Y FS"),
             source: super::super::ShaderSource::Glsl {
                 shader: include_str!("shaders/blit.frag").into(),
                 stage: naga::ShaderStage::Fragment,
-                defines: Default::default(),
+                defines,
             },
         });
 
-        // TODO 处理 wgt::TextureFormat::Bgra8UnormSrgb
-        let format = match config.format {
-            wgt::TextureFormat::Rgba8Unorm => wgt::TextureFormat::Rgba8Unorm,
-            wgt::TextureFormat::Bgra8Unorm => wgt::TextureFormat::Bgra8Unorm,
-            wgt::TextureFormat::Rgba8UnormSrgb => wgt::TextureFormat::Rgba8Unorm,
-            wgt::TextureFormat::Bgra8UnormSrgb => wgt::TextureFormat::Bgra8Unorm,
-            _ => unreachable!(),
-        };
+        let format = format.remove_srgb_suffix();
 
-        let rp = device.create_render_pipeline(&super::super::RenderPipelineDescriptor {
+        device.create_render_pipeline(&super::super::RenderPipelineDescriptor {
             label: Some("Flip-Y Render Pipeline"),
-            layout: Some(&pipeline_layout),
+            layout: Some(pipeline_layout),
             vertex: super::super::VertexState {
-                module: &vs,
+                module: vs,
                 entry_point: "main",
                 buffers: &[super::super::VertexBufferLayout {
                     array_stride: std::mem::size_of::<BlitVertex>() as super::super::BufferAddress,
@@ -379,96 +590,7 @@ impl SwapChain {
                 })],
             }),
             multiview: None,
-        });
-
-        let sampler = device.create_sampler(&super::super::SamplerDescriptor {
-            label: Some("Flip-Y Sampler"),
-            address_mode_u: wgt::AddressMode::ClampToEdge,
-            address_mode_v: wgt::AddressMode::ClampToEdge,
-            address_mode_w: wgt::AddressMode::ClampToEdge,
-            mag_filter: wgt::FilterMode::Nearest,
-            min_filter: wgt::FilterMode::Nearest,
-            mipmap_filter: wgt::FilterMode::Nearest,
-            ..Default::default()
-        });
-
-        let texture =
-            Self::create_surface_texture(device, config.width, config.height, config.format);
-
-        let native_texture =
-            device.create_texture_from_surface(config.width, config.height, config.format);
-
-        let texture_view = texture.create_view(&Default::default());
-        // let texture1 = device.create_texture(&TextureDescriptor { label: None, size: Extent3d { width: 1, height: 1, depth_or_array_layers: 1 }, mip_level_count: 1, sample_count: 1, dimension: crate::TextureDimension::D2, format: crate::TextureFormat::Rgba8Unorm, usage: crate::TextureUsages::from_bits(20).unwrap()/*TextureUsages(TEXTURE_BINDING | RENDER_ATTACHMENT)*/, view_formats: &[] }); 
-        // let texture_view = texture1.create_view(&Default::default());
-        // log::warn!("bbbb==========");
-
-        let bg = device.create_bind_group(&super::super::BindGroupDescriptor {
-            label: Some("Flip-Y BindGroup"),
-            layout: &bg_layout,
-            entries: &[
-                super::super::BindGroupEntry {
-                    binding: 0,
-                    resource: super::super::BindingResource::Sampler(&sampler),
-                },
-                super::super::BindGroupEntry {
-                    binding: 1,
-                    resource: super::super::BindingResource::TextureView(&texture_view),
-                },
-            ],
-        });
-
-        let current_texture = Some(texture.inner.clone());
-
-        Self {
-            encoder,
-
-            rp,
-            vb,
-            bg,
-            sampler,
-            bg_layout,
-
-            texture_size: (config.width, config.height),
-            texture,
-            current_texture,
-
-            native_texture,
-        }
-    }
-
-    fn configure(&mut self, device: &crate::Device, config: &crate::SurfaceConfiguration) {
-        let size = self.texture.inner.0.as_ref().copy_size;
-        let need_update_texture = size.width != config.width || size.height != config.height;
-
-        if need_update_texture {
-            self.texture =
-                Self::create_surface_texture(device, config.width, config.height, config.format);
-            self.native_texture =
-                device.create_texture_from_surface(config.width, config.height, config.format);
-            let texture_view = self.texture.create_view(&Default::default());
-
-
-            // let texture1 = device.create_texture(&TextureDescriptor { label: None, size: Extent3d { width: 1, height: 1, depth_or_array_layers: 1 }, mip_level_count: 1, sample_count: 1, dimension: crate::TextureDimension::D2, format: crate::TextureFormat::Rgba8Unorm, usage: crate::TextureUsages::from_bits(20).unwrap()/*TextureUsages(TEXTURE_BINDING | RENDER_ATTACHMENT)*/, view_formats: &[] }); 
-            // let texture_view = self.texture.create_view(&Default::default());
-            // log::warn!("bbbb==========");
-            self.bg = device.create_bind_group(&super::super::BindGroupDescriptor {
-                label: Some("Flip-Y BindGroup"),
-                layout: &self.bg_layout,
-                entries: &[
-                    super::super::BindGroupEntry {
-                        binding: 0,
-                        resource: super::super::BindingResource::Sampler(&self.sampler),
-                    },
-                    super::super::BindGroupEntry {
-                        binding: 1,
-                        resource: super::super::BindingResource::TextureView(&texture_view),
-                    },
-                ],
-            });
-            self.current_texture = None;
-            self.update_current_texture();
-        }
+        })
     }
 
     fn draw_y_flip(&mut self) {
@@ -509,18 +631,27 @@ impl SwapChain {
                 occlusion_query_set: None,
             });
         rp.set_pipeline(&self.rp);
-        rp.set_bind_group(0, &self.bg, &[]);
+        rp.set_bind_group(0, self.bg.as_ref().unwrap(), &[]);
         rp.set_vertex_buffer(0, self.vb.slice(..));
         rp.draw(0..6, 0..1);
         
         rp.flush();
+        drop(rp);
+
+        // SwapChain 不经过 Queue，录制完 立即 回放
+        self.encoder.inner.flush_commands();
     }
 
     #[inline]
     fn update_current_texture(&mut self) {
         assert!(self.current_texture.is_none());
 
-        self.current_texture = Some(self.texture.inner.clone());
+        let texture = match self.texture.as_ref() {
+            // 中间纹理 创建失败 时 直接 画到 表面
+            Some(texture) if !self.direct && texture.inner.is_some() => texture,
+            _ => &self.native_texture,
+        };
+        self.current_texture = texture.inner.clone();
     }
 
     fn create_surface_texture(
@@ -529,15 +660,7 @@ impl SwapChain {
         height: u32,
         format: wgt::TextureFormat,
     ) -> crate::Texture {
-        // TODO 处理 wgt::TextureFormat::Bgra8UnormSrgb
-        let format = match format {
-            wgt::TextureFormat::Rgba8Unorm => wgt::TextureFormat::Rgba8Unorm,
-            wgt::TextureFormat::Bgra8Unorm => wgt::TextureFormat::Bgra8Unorm,
-            wgt::TextureFormat::Rgba8UnormSrgb => wgt::TextureFormat::Rgba8Unorm,
-            wgt::TextureFormat::Bgra8UnormSrgb => wgt::TextureFormat::Bgra8Unorm,
-            _ => unreachable!(),
-        };
-
+        // 按 配置 的 格式 创建，sRGB 格式 写入 时 由 GL 编码
         let desc = super::super::TextureDescriptor {
             label: None,
             size: super::super::Extent3d {
@@ -1,7 +1,10 @@
-use std::{ops::Range, sync::atomic::AtomicU32};
+use std::{
+    ops::Range,
+    sync::atomic::{AtomicU32, Ordering},
+};
 
 use glow::HasContext;
-use pi_share::Share;
+use pi_share::{Share, ShareCell};
 
 use crate::TextureFormat;
 
@@ -25,7 +28,38 @@ impl Texture {
             | super::TextureUses::DEPTH_STENCIL_WRITE
             | super::TextureUses::DEPTH_STENCIL_READ;
 
-        let format_desc = conv::map_texture_format(desc.format);
+        // 视图格式 只能 在 sRGB 和 非 sRGB 之间 重新解释，靠 EXT_texture_sRGB_decode 实现
+        for format in desc.view_formats {
+            if format.remove_srgb_suffix() != desc.format.remove_srgb_suffix() {
+                log::error!(
+                    "view format {:?} is not compatible with texture format {:?}",
+                    format,
+                    desc.format
+                );
+                return Err(super::super::DeviceError::Invalid);
+            }
+        }
+        let reinterpret = desc.view_formats.iter().any(|f| *f != desc.format);
+        if reinterpret
+            && !adapter
+                .private_caps()
+                .contains(super::PrivateCapabilities::TEXTURE_SRGB_DECODE)
+        {
+            log::error!("view formats {:?} need EXT_texture_sRGB_decode", desc.view_formats);
+            return Err(super::super::DeviceError::Invalid);
+        }
+
+        // 纹理 仅作为 渲染目标（或 多重采样），不作为 Sampler 或 Storage 或 Copy，则直接创建 RenderBuffer
+        let is_renderbuffer = (render_usage.contains(usage) || desc.sample_count > 1)
+            && desc.dimension == wgt::TextureDimension::D2
+            && desc.size.depth_or_array_layers == 1;
+
+        // 线性纹理 声明了 sRGB 视图 时，存储 用 sRGB 格式，线性视图 采样 时 跳过 解码
+        let format_desc = if reinterpret && !is_renderbuffer {
+            conv::map_texture_format(desc.format.add_srgb_suffix())
+        } else {
+            conv::map_texture_format(desc.format)
+        };
 
         let mut copy_size = super::CopyExtent {
             width: desc.size.width,
@@ -36,11 +70,8 @@ impl Texture {
         let lock = adapter.lock(None);
         let gl = lock.get_glow();
 
-        let (inner, is_cubemap) = if render_usage.contains(usage)
-            && desc.dimension == wgt::TextureDimension::D2
-            && desc.size.depth_or_array_layers == 1
-        {
-            // 纹理 仅作为 渲染目标，不作为 Sampler 或 Storage 或 Copy，则直接创建 RenderBuffer
+        // GLES 3.0 没有 多重采样纹理，多重采样 一律 用 RenderBuffer，内容 只能 通过 解析 / blit 取出
+        let (inner, is_cubemap) = if is_renderbuffer {
             let raw = unsafe { gl.create_renderbuffer().unwrap() };
             #[cfg(all(target_arch = "wasm32", feature = "geterror"))]
             unsafe {
@@ -315,7 +346,9 @@ impl Texture {
             format: desc.format,
             copy_size,
             format_desc,
+            view_formats: desc.view_formats.into(),
             is_cubemap,
+            version: Default::default(),
         };
 
         Ok(Self(Share::new(imp)))
@@ -336,7 +369,88 @@ impl Texture {
                 depth: 1,
             },
             format_desc,
+            view_formats: Box::new([]),
+            is_cubemap: false,
+            version: Default::default(),
+        };
+
+        Self(Share::new(imp))
+    }
+
+    // GL 没有 纹理视图，2D 视图 选中 数组 的 一层 / 立方体 的 一面 时，用 一张 2D 纹理 存 这一层 的 拷贝
+    // D2Array 视图 只选 部分 层 时 (layered)，用 一张 layer_count 层 的 2D 数组 存 这些 层 的 拷贝
+    // 命令 回放 时 才 创建，此时 gl 已经 锁住，创建后 纹理 绑定 在 当前 纹理单元 的 对应 目标 上
+    pub(crate) fn new_layer_copy(
+        gl: &glow::Context,
+        src: &TextureImpl,
+        mip_levels: Range<u32>,
+        layer_count: u32,
+        layered: bool,
+    ) -> Self {
+        let (state, adapter) = match &src.inner {
+            TextureInner::Texture { state, adapter, .. } => (state.clone(), adapter.clone()),
+            _ => unreachable!(),
+        };
+
+        let levels = mip_levels.len() as u32;
+        let width = (src.copy_size.width >> mip_levels.start).max(1);
+        let height = (src.copy_size.height >> mip_levels.start).max(1);
+
+        let target = if layered {
+            glow::TEXTURE_2D_ARRAY
+        } else {
+            glow::TEXTURE_2D
+        };
+
+        let raw = unsafe { gl.create_texture().unwrap() };
+        unsafe {
+            gl.bind_texture(target, Some(raw));
+            if layered {
+                gl.tex_storage_3d(
+                    target,
+                    levels as i32,
+                    src.format_desc.internal as u32,
+                    width as i32,
+                    height as i32,
+                    layer_count as i32,
+                );
+            } else {
+                gl.tex_storage_2d(
+                    target,
+                    levels as i32,
+                    src.format_desc.internal as u32,
+                    width as i32,
+                    height as i32,
+                );
+            }
+        }
+        #[cfg(all(target_arch = "wasm32", feature = "geterror"))]
+        unsafe {
+            match gl.get_error() {
+                glow::NO_ERROR => {},
+                err => log::error!("OpenGL error: {:?}", err),
+            }
+        }
+
+        let imp = TextureImpl {
+            inner: TextureInner::Texture {
+                raw,
+                target,
+                state,
+                adapter,
+            },
+            mip_level_count: levels,
+            array_layer_count: layer_count,
+            format: src.format,
+            copy_size: super::CopyExtent {
+                width,
+                height,
+                depth: 1,
+            },
+            format_desc: src.format_desc.clone(),
+            view_formats: src.view_formats.clone(),
             is_cubemap: false,
+            version: Default::default(),
         };
 
         Self(Share::new(imp))
@@ -348,12 +462,13 @@ impl Texture {
         state: &GLState,
         copy: super::super::ImageCopyTexture,
         data1: &[u8],
-        _data_layout: super::super::ImageDataLayout,
+        data_layout: super::super::ImageDataLayout,
         size: super::super::Extent3d,
     ) {
         profiling::scope!("hal::Texture::write_data");
 
-        let inner = copy.texture.inner.0.as_ref();
+        let inner = copy.texture.inner.as_ref().unwrap().0.as_ref();
+        inner.mark_written();
 
         let (raw, dst_target, adapter) = match &inner.inner {
             TextureInner::Texture {
@@ -367,6 +482,59 @@ impl Texture {
 
         let format_desc = &inner.format_desc;
 
+        let (block_width, block_height) = copy.texture.format().block_dimensions();
+        let block_size = copy.texture.format().block_copy_size(None).unwrap();
+
+        // 紧密排列 时 一行 的 字节数 和 一张图 的 块行数
+        let row_bytes = ((size.width + block_width - 1) / block_width) * block_size;
+        let block_rows = (size.height + block_height - 1) / block_height;
+
+        let bytes_per_row = data_layout.bytes_per_row.unwrap_or(row_bytes);
+        let rows_per_image = data_layout.rows_per_image.unwrap_or(block_rows);
+        let data1 = &data1[data_layout.offset as usize..];
+
+        // GLES 不接受 BGRA 的 像素数据，交换 R / B 后 按 RGBA 上传
+        let swizzled;
+        let (data1, external) = if format_desc.external == glow::BGRA
+            && !adapter
+                .private_caps()
+                .contains(super::PrivateCapabilities::TEXTURE_FORMAT_BGRA)
+        {
+            let mut data = data1.to_vec();
+            swap_red_blue(&mut data);
+            swizzled = data;
+            (&swizzled[..], glow::RGBA)
+        } else {
+            (data1, format_desc.external)
+        };
+
+        // 有 行填充 或 图像填充：
+        //     非压缩纹理 行长 是 像素 整数倍，交给 UNPACK_ROW_LENGTH / UNPACK_IMAGE_HEIGHT
+        //     其他（GLES 的 压缩纹理 不认 UNPACK 参数）在 CPU 上 重新 紧密排列
+        let is_padded = bytes_per_row != row_bytes
+            || (size.depth_or_array_layers > 1 && rows_per_image != block_rows);
+        let is_unpack = is_padded && !inner.format.is_compressed() && bytes_per_row % block_size == 0;
+
+        let packed;
+        let (data1, bytes_per_row, rows_per_image) = if is_padded && !is_unpack {
+            packed = pack_rows(
+                data1,
+                bytes_per_row as usize,
+                rows_per_image as usize,
+                row_bytes as usize,
+                block_rows as usize,
+                size.depth_or_array_layers as usize,
+            );
+            (&packed[..], row_bytes, block_rows)
+        } else {
+            (data1, bytes_per_row, rows_per_image)
+        };
+
+        // 一张图（数组层 / 立方体面 / 3D 切片）在 数据 中 的 跨度
+        let image_stride = (bytes_per_row * rows_per_image) as usize;
+        // 压缩纹理 上传 要 给出 准确 的 字节数
+        let image_bytes = (row_bytes * block_rows) as usize;
+
         let lock = adapter.lock(None);
         let  gl = lock.get_glow();
 
@@ -380,50 +548,23 @@ impl Texture {
                     err => log::error!("OpenGL error: {:?}", err),
                 }
             }
-
-        
-            // if _data_layout.bytes_per_row.is_some() {
-            //     let row_texels = _data_layout.bytes_per_row.map_or(0, |bpr| block_width * bpr / block_size);
-            //     log::error!("UNPACK_ROW_LENGTH {:?}", row_texels);
-            //     gl.pixel_store_i32(glow::UNPACK_ROW_LENGTH, (row_texels) as i32);
-            // }
-            // if _data_layout.rows_per_image.is_some() {
-            //     let column_texels = _data_layout.rows_per_image.map_or(0, |rpi| block_height * rpi);
-            //     log::error!("UNPACK_IMAGE_HEIGHT {:?}", column_texels);
-            //     gl.pixel_store_i32(glow::UNPACK_IMAGE_HEIGHT, column_texels as i32);
-            // }
-                // #[cfg(all(target_arch = "wasm32", feature = "geterror"))]
-                // unsafe {
-                //     match gl.get_error() {
-                //         glow::NO_ERROR => {},
-                //         err => log::error!("OpenGL error: {:?}", err),
-                //     }
-                // }
         }
 
         if !inner.format.is_compressed() {
-            let data = glow::PixelUnpackData::Slice(data1);
+            unsafe {
+                if is_unpack {
+                    // 行跨度 由 ROW_LENGTH 精确 给出，不再 按 对齐 补齐
+                    gl.pixel_store_i32(glow::UNPACK_ALIGNMENT, 1);
+                    gl.pixel_store_i32(glow::UNPACK_ROW_LENGTH, (bytes_per_row / block_size) as i32);
+                    gl.pixel_store_i32(glow::UNPACK_IMAGE_HEIGHT, rows_per_image as i32);
+                } else {
+                    let align: i32 = if row_bytes % 4 == 0 { 4 } else if row_bytes % 2 == 0 { 2 } else { 1 };
+                    gl.pixel_store_i32(glow::UNPACK_ALIGNMENT, align);
+                }
+            }
 
-            let (block_width, block_height) = copy.texture.format().block_dimensions();
-            let block_size = copy.texture.format().block_copy_size(None).unwrap(); 
-            let rowbytes = ((size.width + block_width - 1) / block_width) * block_size;
-            let align: i32 = if rowbytes % 4 == 0 { 4 } else if (rowbytes % 2 == 0) { 2 } else { 1 };
-            unsafe { gl.pixel_store_i32(glow::UNPACK_ALIGNMENT, align) };   
-        
-            // unsafe {
-            // if _data_layout.bytes_per_row.is_some() {
-            //     let row_texels = _data_layout.bytes_per_row.map_or(0, |bpr| block_width * bpr / block_size);
-            //     // log::error!("UNPACK_ROW_LENGTH {:?}", row_texels);
-            //     gl.pixel_store_i32(glow::UNPACK_ROW_LENGTH, (row_texels) as i32);
-            // }
-            // if _data_layout.rows_per_image.is_some() {
-            //     let column_texels = _data_layout.rows_per_image.map_or(0, |rpi| block_height * rpi);
-            //     // log::error!("UNPACK_IMAGE_HEIGHT {:?}", column_texels);
-            //     gl.pixel_store_i32(glow::UNPACK_IMAGE_HEIGHT, column_texels as i32);
-            // }
-            // }
             match dst_target {
-                glow::TEXTURE_3D => {
+                glow::TEXTURE_3D | glow::TEXTURE_2D_ARRAY => {
                     unsafe {
                         gl.tex_sub_image_3d(
                             dst_target,
@@ -434,33 +575,9 @@ impl Texture {
                             size.width as i32,
                             size.height as i32,
                             size.depth_or_array_layers as i32,
-                            format_desc.external,
+                            external,
                             format_desc.data_type,
-                            data,
-                        )
-                    };
-                    #[cfg(all(target_arch = "wasm32"))]
-                    unsafe {
-                        match gl.get_error() {
-                            glow::NO_ERROR => {},
-                            err => log::error!("OpenGL error: {:?}", err),
-                        }
-                    }
-                }
-                glow::TEXTURE_2D_ARRAY => {
-                    unsafe {
-                        gl.tex_sub_image_3d(
-                            dst_target,
-                            copy.mip_level as i32,
-                            copy.origin.x as i32,
-                            copy.origin.y as i32,
-                            copy.origin.z as i32,
-                            size.width as i32,
-                            size.height as i32,
-                            size.depth_or_array_layers as i32,
-                            format_desc.external,
-                            format_desc.data_type,
-                            data,
+                            glow::PixelUnpackData::Slice(data1),
                         )
                     };
                     #[cfg(all(target_arch = "wasm32", feature = "geterror"))]
@@ -472,16 +589,6 @@ impl Texture {
                     }
                 }
                 glow::TEXTURE_2D => {
-                    // if size.width % 4 != 0 {
-                    //     unsafe { gl.pixel_store_i32(glow::UNPACK_ALIGNMENT, 1) };
-                    //     #[cfg(all(target_arch = "wasm32"))]
-                    //     unsafe {
-                    //         match gl.get_error() {
-                    //             glow::NO_ERROR => {},
-                    //             err => log::error!("OpenGL error: {:?}", err),
-                    //         }
-                    //     }
-                    // }
                     unsafe {
                         gl.tex_sub_image_2d(
                             dst_target,
@@ -490,9 +597,9 @@ impl Texture {
                             copy.origin.y as i32,
                             size.width as i32,
                             size.height as i32,
-                            format_desc.external,
+                            external,
                             format_desc.data_type,
-                            data,
+                            glow::PixelUnpackData::Slice(data1),
                         )
                     };
                     #[cfg(all(target_arch = "wasm32", feature = "geterror"))]
@@ -505,26 +612,29 @@ impl Texture {
                                 copy.origin.y as i32,
                                 size.width as i32,
                                 size.height as i32,
-                                format_desc.external,
+                                external,
                                 format_desc.data_type
                             )),
                         }
                     }
                 }
                 glow::TEXTURE_CUBE_MAP => {
-                    unsafe {
-                        gl.tex_sub_image_2d(
-                            super::CUBEMAP_FACES[size.depth_or_array_layers as usize],
-                            copy.mip_level as i32,
-                            copy.origin.x as i32,
-                            copy.origin.y as i32,
-                            size.width as i32,
-                            size.height as i32,
-                            format_desc.external,
-                            format_desc.data_type,
-                            data,
-                        )
-                    };
+                    // 每个 面 单独 上传，面 从 origin.z 开始
+                    for i in 0..size.depth_or_array_layers {
+                        unsafe {
+                            gl.tex_sub_image_2d(
+                                super::CUBEMAP_FACES[(copy.origin.z + i) as usize],
+                                copy.mip_level as i32,
+                                copy.origin.x as i32,
+                                copy.origin.y as i32,
+                                size.width as i32,
+                                size.height as i32,
+                                external,
+                                format_desc.data_type,
+                                glow::PixelUnpackData::Slice(&data1[i as usize * image_stride..]),
+                            )
+                        };
+                    }
                     #[cfg(all(target_arch = "wasm32", feature = "geterror"))]
                     unsafe {
                         match gl.get_error() {
@@ -535,41 +645,18 @@ impl Texture {
                 }
                 _ => unreachable!(),
             }
-            
-            unsafe { gl.pixel_store_i32(glow::UNPACK_ALIGNMENT, 4) };   
+
+            unsafe {
+                if is_unpack {
+                    gl.pixel_store_i32(glow::UNPACK_ROW_LENGTH, 0);
+                    gl.pixel_store_i32(glow::UNPACK_IMAGE_HEIGHT, 0);
+                }
+                gl.pixel_store_i32(glow::UNPACK_ALIGNMENT, 4);
+            }
         } else {
-            let data = glow::CompressedPixelUnpackData::Slice(data1);
             match dst_target {
                 glow::TEXTURE_3D | glow::TEXTURE_CUBE_MAP_ARRAY | glow::TEXTURE_2D_ARRAY => {
-                    
-                    let (block_width, block_height) = copy.texture.format().block_dimensions();
-                    let block_size = copy.texture.format().block_copy_size(None).unwrap();
-                    let blockx = (size.width + block_width - 1) / block_width;
-                    let blocky = (size.height + block_height - 1) / block_height;
-                    let expected_size = blockx * blocky * block_size;
-                    // if data1.len() as u32 != expected_size {
-                    //     log::error!("expected_size error: {:?}", (data1.len(), expected_size));
-                    // }
-                    // if copy.origin.x + size.width > copy.texture.width() ||
-                    // copy.origin.y + size.height > copy.texture.height() ||
-                    // copy.origin.z >= copy.texture.depth_or_array_layers() {
-                    //     log::error!("size check error: {:?}", (
-                    //         (copy.origin.x, size.width, copy.texture.width())
-                    //         ,(copy.origin.y, size.height, copy.texture.height())
-                    //         ,(copy.origin.z, copy.texture.depth_or_array_layers())
-                    //     )
-                    //     );
-                    // }
-                    // // if _data_layout.bytes_per_row.is_some() {
-                    // //     let row_texels = _data_layout.bytes_per_row.map_or(0, |bpr| block_width * bpr / block_size);
-                    // //     log::error!("UNPACK_ROW_LENGTH {:?}", row_texels);
-                    // //     gl.pixel_store_i32(glow::UNPACK_ROW_LENGTH, (row_texels) as i32);
-                    // // }
-                    // // if _data_layout.rows_per_image.is_some() {
-                    // //     let column_texels = _data_layout.rows_per_image.map_or(0, |rpi| block_height * rpi);
-                    // //     log::error!("UNPACK_IMAGE_HEIGHT {:?}", column_texels);
-                    // //     gl.pixel_store_i32(glow::UNPACK_IMAGE_HEIGHT, column_texels as i32);
-                    // // }
+                    let len = image_bytes * size.depth_or_array_layers as usize;
                     unsafe {
                         gl.compressed_tex_sub_image_3d(
                             dst_target,
@@ -581,7 +668,7 @@ impl Texture {
                             size.height as i32,
                             size.depth_or_array_layers as i32,
                             format_desc.internal,
-                            data,
+                            glow::CompressedPixelUnpackData::Slice(&data1[..len]),
                         );
                         #[cfg(all(target_arch = "wasm32", feature = "geterror"))]
                         match gl.get_error() {
@@ -602,7 +689,7 @@ impl Texture {
                             size.width as i32,
                             size.height as i32,
                             format_desc.internal,
-                            data,
+                            glow::CompressedPixelUnpackData::Slice(&data1[..image_bytes]),
                         )
                     };
                     #[cfg(all(target_arch = "wasm32", feature = "geterror"))]
@@ -621,18 +708,23 @@ impl Texture {
                     }
                 }
                 glow::TEXTURE_CUBE_MAP => {
+                    for i in 0..size.depth_or_array_layers {
+                        let start = i as usize * image_stride;
+                        unsafe {
+                            gl.compressed_tex_sub_image_2d(
+                                super::CUBEMAP_FACES[(copy.origin.z + i) as usize],
+                                copy.mip_level as i32,
+                                copy.origin.x as i32,
+                                copy.origin.y as i32,
+                                size.width as i32,
+                                size.height as i32,
+                                format_desc.internal,
+                                glow::CompressedPixelUnpackData::Slice(&data1[start..start + image_bytes]),
+                            );
+                        }
+                    }
+                    #[cfg(all(target_arch = "wasm32", feature = "geterror"))]
                     unsafe {
-                        gl.compressed_tex_sub_image_2d(
-                            super::CUBEMAP_FACES[size.depth_or_array_layers as usize],
-                            copy.mip_level as i32,
-                            copy.origin.x as i32,
-                            copy.origin.y as i32,
-                            size.width as i32,
-                            size.height as i32,
-                            format_desc.internal,
-                            data,
-                        );
-                        #[cfg(all(target_arch = "wasm32", feature = "geterror"))]
                         match gl.get_error() {
                             glow::NO_ERROR => {},
                             err => {
@@ -677,7 +769,8 @@ impl Texture {
     ) {
         profiling::scope!("hal::Texture::write_data");
 
-        let inner = copy.texture.inner.0.as_ref();
+        let inner = copy.texture.inner.as_ref().unwrap().0.as_ref();
+        inner.mark_written();
 
         let (raw, dst_target, adapter) = match &inner.inner {
             TextureInner::Texture {
@@ -816,7 +909,8 @@ impl Texture {
     ) {
         profiling::scope!("hal::Texture::write_external_image");
 
-        let inner = copy.texture.inner.0.as_ref();
+        let inner = copy.texture.inner.as_ref().unwrap().0.as_ref();
+        inner.mark_written();
 
         let (raw, dst_target, adapter) = match &inner.inner {
             TextureInner::Texture {
@@ -1062,9 +1156,27 @@ pub(crate) struct TextureView {
     pub(crate) mip_levels: Range<u32>,
     pub(crate) array_layers: Range<u32>,
     pub(crate) format: wgt::TextureFormat,
+    pub(crate) dimension: wgt::TextureViewDimension,
+    // 采样 sRGB 存储 的 纹理 时 是否 解码；None 表示 纹理 不能 重新解释，不用 设置
+    pub(crate) srgb_decode: Option<bool>,
+    // 选中 数组 一层 / 立方体 一面 的 2D 视图、只选 部分 层 的 D2Array 视图，采样 的 是 这份 拷贝
+    // 第一次 绑定 时 创建，源纹理 被 写过 之后 才 重新 拷贝
+    pub(crate) layer_copy: Option<LayerCopy>,
+    // 只选 部分 mip 的 2D 视图：同一次 绘制 中 同一张 纹理 的 另一个 视图 mip 范围 不同 时，采样 这份 拷贝
+    pub(crate) mip_copy: Option<LayerCopy>,
     pub(crate) id: u32,
 }
 
+// (拷贝, 拷贝时 源纹理 的 version)
+#[derive(Clone)]
+pub(crate) struct LayerCopy(pub(crate) Share<ShareCell<Option<(Texture, u32)>>>);
+
+impl std::fmt::Debug for LayerCopy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LayerCopy").finish()
+    }
+}
+
 impl TextureView {
     pub fn new(
         texture: &Texture,
@@ -1075,16 +1187,126 @@ impl TextureView {
         let imp = texture.0.as_ref();
 
         let mip_count = match desc.mip_level_count {
-            Some(count) => count.into(),
-            None => imp.mip_level_count,
+            Some(count) => count,
+            None => imp.mip_level_count.saturating_sub(desc.base_mip_level).max(1),
+        };
+        let mip_levels = desc.base_mip_level..(desc.base_mip_level + mip_count);
+
+        // 纹理 的 GL 目标 创建时 就 定了
+        let target = match &imp.inner {
+            TextureInner::Texture { target, .. } => *target,
+            _ => glow::TEXTURE_2D,
         };
-        let mip_levels = desc.base_mip_level..(mip_count - desc.base_mip_level);
 
+        // 3D 纹理 的 层 就是 深度切片
+        let layer_limit = if target == glow::TEXTURE_3D {
+            imp.copy_size.depth
+        } else {
+            imp.array_layer_count
+        };
         let layer_count = match desc.array_layer_count {
-            Some(count) => count.into(),
-            None => imp.array_layer_count,
+            Some(count) => count,
+            None => layer_limit.saturating_sub(desc.base_array_layer).max(1),
+        };
+        let array_layers = desc.base_array_layer..(desc.base_array_layer + layer_count);
+
+        if mip_levels.end > imp.mip_level_count || array_layers.end > layer_limit {
+            log::error!(
+                "texture view out of range: mip_levels = {:?}, array_layers = {:?}, texture has {} mips and {} layers",
+                mip_levels,
+                array_layers,
+                imp.mip_level_count,
+                layer_limit
+            );
+            return Err(super::super::DeviceError::Invalid);
+        }
+
+        let format = desc.format.unwrap_or(imp.format);
+        if format != imp.format && !imp.view_formats.contains(&format) {
+            log::error!(
+                "texture view format {:?} is neither {:?} nor in view_formats {:?}",
+                format,
+                imp.format,
+                imp.view_formats
+            );
+            return Err(super::super::DeviceError::Invalid);
+        }
+        let srgb_decode = if imp.view_formats.iter().any(|f| *f != imp.format) {
+            Some(format.is_srgb())
+        } else {
+            None
+        };
+
+        // 不指定 维度 时 取 纹理 本身 的 维度
+        let dimension = match desc.dimension {
+            Some(dimension) => dimension,
+            None => match target {
+                glow::TEXTURE_2D_ARRAY => wgt::TextureViewDimension::D2Array,
+                glow::TEXTURE_CUBE_MAP => wgt::TextureViewDimension::Cube,
+                glow::TEXTURE_3D => wgt::TextureViewDimension::D3,
+                _ => wgt::TextureViewDimension::D2,
+            },
+        };
+        let view_target = match dimension {
+            wgt::TextureViewDimension::D1 | wgt::TextureViewDimension::D2 => glow::TEXTURE_2D,
+            wgt::TextureViewDimension::D2Array => glow::TEXTURE_2D_ARRAY,
+            wgt::TextureViewDimension::Cube => glow::TEXTURE_CUBE_MAP,
+            wgt::TextureViewDimension::D3 => glow::TEXTURE_3D,
+            wgt::TextureViewDimension::CubeArray => {
+                log::error!("cube array texture views are not supported");
+                return Err(super::super::DeviceError::Invalid);
+            }
+        };
+
+        let layer_copy = if view_target == target {
+            if dimension == wgt::TextureViewDimension::D2Array
+                && array_layers.len() as u32 != imp.array_layer_count
+            {
+                // GL 不能 偏移 数组层，把 选中的 层 拷贝 到 另一个 数组 里 采样
+                if imp.format.is_compressed() {
+                    log::error!(
+                        "D2Array view of layers {:?} is not supported for compressed format {:?}",
+                        array_layers,
+                        imp.format
+                    );
+                    return Err(super::super::DeviceError::Invalid);
+                }
+                Some(LayerCopy(Share::new(ShareCell::new(None))))
+            } else {
+                None
+            }
+        } else if view_target == glow::TEXTURE_2D
+            && target != glow::TEXTURE_2D
+            && array_layers.len() == 1
+        {
+            // 压缩纹理 不能 挂到 fbo 上 拷贝
+            if imp.format.is_compressed() {
+                log::error!(
+                    "2D view of one layer is not supported for compressed format {:?}",
+                    imp.format
+                );
+                return Err(super::super::DeviceError::Invalid);
+            }
+            Some(LayerCopy(Share::new(ShareCell::new(None))))
+        } else {
+            log::error!(
+                "texture view dimension {:?} does not match texture target {:#x}",
+                dimension,
+                target
+            );
+            return Err(super::super::DeviceError::Invalid);
+        };
+
+        // TEXTURE_BASE_LEVEL / TEXTURE_MAX_LEVEL 是 纹理 的 状态，两个 视图 不能 同时 生效
+        let mip_copy = if target == glow::TEXTURE_2D
+            && layer_copy.is_none()
+            && mip_levels.len() as u32 != imp.mip_level_count
+            && !imp.format.is_compressed()
+        {
+            Some(LayerCopy(Share::new(ShareCell::new(None))))
+        } else {
+            None
         };
-        let array_layers = desc.base_array_layer..(layer_count - desc.base_array_layer);
 
         Ok(TextureView {
             inner: texture.0.clone(),
@@ -1092,8 +1314,12 @@ impl TextureView {
             mip_levels,
             array_layers,
 
-            format: imp.format,
+            format,
             // sample_type: imp.format.sample_type(None, None).unwrap(),
+            dimension,
+            srgb_decode,
+            layer_copy,
+            mip_copy,
 
             format_desc: imp.format_desc.clone(),
 
@@ -1117,8 +1343,25 @@ pub(crate) struct TextureImpl {
     pub copy_size: super::CopyExtent,
 
     pub format_desc: TextureFormatDesc,
+    // 创建时 声明的 视图格式，不为空 且 和 format 不同 时，存储 用 sRGB 格式
+    pub view_formats: Box<[wgt::TextureFormat]>,
 
     pub is_cubemap: bool,
+
+    // 每次 写入 加一，单层视图 的 拷贝 据此 判断 要不要 重新 拷贝
+    pub version: Share<AtomicU32>,
+}
+
+impl TextureImpl {
+    #[inline]
+    pub(crate) fn mark_written(&self) {
+        self.version.fetch_add(1, Ordering::Relaxed);
+    }
+
+    #[inline]
+    pub(crate) fn version(&self) -> u32 {
+        self.version.load(Ordering::Relaxed)
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -1240,3 +1483,29 @@ fn is_layered_target(target: u32) -> bool {
         _ => unreachable!(),
     }
 }
+
+// BGRA 的 数据 在 CPU 上 交换 R / B，变成 RGBA
+pub(crate) fn swap_red_blue(data: &mut [u8]) {
+    for pixel in data.chunks_exact_mut(4) {
+        pixel.swap(0, 2);
+    }
+}
+
+// 去掉 行填充 / 图像填充，得到 紧密排列 的 数据
+fn pack_rows(
+    data: &[u8],
+    bytes_per_row: usize,
+    rows_per_image: usize,
+    row_bytes: usize,
+    rows: usize,
+    images: usize,
+) -> Vec<u8> {
+    let mut packed = Vec::with_capacity(row_bytes * rows * images);
+    for image in 0..images {
+        for row in 0..rows {
+            let start = (image * rows_per_image + row) * bytes_per_row;
+            packed.extend_from_slice(&data[start..start + row_bytes]);
+        }
+    }
+    packed
+}
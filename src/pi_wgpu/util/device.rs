@@ -88,15 +88,19 @@ impl DeviceExt for super::super::Device {
                 label: descriptor.label,
                 size,
                 usage: descriptor.usage,
-                mapped_at_creation: true,
+                // 直接 写入，不经过 映射，省掉 一次 拷贝
+                mapped_at_creation: false,
             };
 
             let buffer = self.create_buffer_inner(&wgt_descriptor);
 
-            let lock = self.inner.adapter.lock(None);
-            let gl = lock.get_glow();
+            // 创建失败 的 错误 已经 报告了
+            if let Some(inner) = buffer.inner.as_ref() {
+                let lock = self.inner.adapter.lock(None);
+                let gl = lock.get_glow();
 
-            buffer.inner.write_buffer(&gl, 0, &descriptor.contents[..]);
+                inner.write_buffer(&gl, 0, &descriptor.contents[..]);
+            }
 			#[cfg(not(target_arch = "wasm32"))] {
             //     f.debug_struct("BufferInitDescriptor")
             // .field("label", &self.label)
@@ -106,7 +110,7 @@ impl DeviceExt for super::super::Device {
                 log::trace!(
                     "
                     let buffer{:?} = device.create_buffer_init(&{:?});",
-                    buffer.inner.0.raw.0,
+                    buffer.debug_id(),
                     descriptor
                 );
             }
@@ -198,7 +202,7 @@ impl DeviceExt for super::super::Device {
         {
             log::trace!(
                 "let texture{:?} = device.create_texture_with_data(&{:?});",
-                texture.inner.0.inner.debug_str(),
+                texture.debug_str(),
                 desc,
             );
         }
@@ -284,7 +288,7 @@ impl DeviceExt for super::super::Device {
         {
             log::trace!(
                 "let texture{:?} = device.create_texture_with_data(&{:?});",
-                texture.inner.0.inner.debug_str(),
+                texture.debug_str(),
                 desc,
             );
         }
@@ -1,7 +1,9 @@
 mod device;
+mod indirect;
 mod init;
 mod mutex;
 
 pub use device::*;
+pub use indirect::*;
 pub use init::*;
 pub use mutex::*;
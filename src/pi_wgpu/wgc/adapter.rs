@@ -1,5 +1,6 @@
 use std::future::{ready, Future};
 use pi_assets::allocator::Allocator;
+use pi_share::Share;
 use thiserror::Error;
 
 use crate::PiWgpuAdapter;
@@ -77,7 +78,10 @@ impl Adapter {
 
         let r = match open {
             Ok(open) => {
-                let device = Device { inner: open.device };
+                let device = Device {
+                    inner: open.device,
+                    error_sink: Share::new(Default::default()),
+                };
 
                 let queue = Queue { inner: open.queue };
 
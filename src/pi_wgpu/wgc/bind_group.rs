@@ -9,14 +9,20 @@ use super::super::{BindGroupLayout, BindingResource, Label};
 /// Corresponds to [WebGPU `GPUBindGroup`](https://gpuweb.github.io/gpuweb/#gpubindgroup).
 #[derive(Debug)]
 pub struct BindGroup {
-    pub(crate) inner: super::super::hal::BindGroup,
+    // 创建失败 时 为 None，设置 它 之后 的 绘制 不录制
+    pub(crate) inner: Option<super::super::hal::BindGroup>,
 }
 
 impl BindGroup {
     #[inline]
-    pub(crate) fn from_hal(inner: super::super::hal::BindGroup) -> Self {
+    pub(crate) fn from_hal(inner: Option<super::super::hal::BindGroup>) -> Self {
         Self { inner }
     }
+
+    #[inline]
+    pub(crate) fn debug_id(&self) -> u64 {
+        self.inner.as_ref().map_or(0, |g| g.id as u64)
+    }
 }
 
 /// Describes a group of bindings and the resources to be bound.
@@ -26,7 +26,7 @@ pub enum BindingResource<'a> {
     /// Binding is a sampler.
     ///
     /// Corresponds to [`super::super::wgt::BindingType::Sampler`] with [`BindGroupLayoutEntry::count`] set to None.
-    #[debug("BindingResource::Sampler(&sampler{:?})", _0.inner.0.raw)]
+    #[debug("BindingResource::Sampler(&sampler{:?})", _0.inner.as_ref().map(|s| s.0.raw))]
     Sampler(&'a Sampler),
     /// Binding is backed by an array of samplers.
     ///
@@ -39,7 +39,7 @@ pub enum BindingResource<'a> {
     ///
     /// Corresponds to [`super::super::wgt::BindingType::Texture`] and [`super::super::wgt::BindingType::StorageTexture`] with
     /// [`BindGroupLayoutEntry::count`] set to None.
-    #[debug("BindingResource::TextureView(&texture_view{:?})", _0.inner.id)]
+    #[debug("BindingResource::TextureView(&texture_view{:?})", _0.debug_id())]
     TextureView(&'a TextureView),
     /// Binding is backed by an array of textures.
     ///
@@ -63,14 +63,20 @@ pub enum BindingResource<'a> {
 /// https://gpuweb.github.io/gpuweb/#gpubindgrouplayout).
 #[derive(Debug)]
 pub struct BindGroupLayout {
-    pub(crate) inner: super::super::hal::BindGroupLayout,
+    // 创建失败 时 为 None，用它 创建的 BindGroup / PipelineLayout 也是 无效的
+    pub(crate) inner: Option<super::super::hal::BindGroupLayout>,
 }
 
 impl BindGroupLayout {
     #[inline]
-    pub(crate) fn from_hal(inner: super::super::hal::BindGroupLayout) -> Self {
+    pub(crate) fn from_hal(inner: Option<super::super::hal::BindGroupLayout>) -> Self {
         Self { inner }
     }
+
+    #[inline]
+    pub(crate) fn debug_id(&self) -> u64 {
+        self.inner.as_ref().map_or(0, |l| l.id as u64)
+    }
 }
 
 /// Describes a [`BindGroupLayout`].
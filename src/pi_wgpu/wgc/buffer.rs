@@ -1,11 +1,14 @@
 //!
 //! + Buffer, BufferSlice<'a>
 //! + BufferBinding<'a>, BindingResource<'a>
+//! + BufferView<'a>, BufferViewMut<'a>
 //!
 
-use std::ops::RangeBounds;
+use std::ops::{Deref, DerefMut, RangeBounds};
 
-use super::super::{hal, BindingResource, BufferAddress, BufferSize, BufferUsages, Label};
+use super::super::{
+    hal, BindingResource, BufferAddress, BufferSize, BufferUsages, Label, MAP_ALIGNMENT,
+};
 use derive_more::Debug;
 
 /// Describes a [`Buffer`].
@@ -27,18 +30,25 @@ pub struct Buffer {
     pub(crate) usage: BufferUsages,
     pub(crate) size: BufferAddress,
 
-    pub(crate) inner: hal::Buffer,
+    // 创建失败 时 为 None，不能 映射，用到 它 的 指令 不录制
+    pub(crate) inner: Option<hal::Buffer>,
 }
 
 impl Buffer {
     #[inline]
     pub(crate) fn from_hal(
-        inner: super::super::hal::Buffer,
+        inner: Option<super::super::hal::Buffer>,
         usage: BufferUsages,
         size: BufferAddress,
     ) -> Self {
         Self { inner, usage, size }
     }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    #[inline]
+    pub(crate) fn debug_id(&self) -> u32 {
+        self.inner.as_ref().map_or(0, |b| b.0.raw.0.get())
+    }
 }
 
 impl Buffer {
@@ -101,6 +111,256 @@ impl Buffer {
             size,
         }
     }
+
+    /// Flushes any pending write operations and unmaps the buffer from host memory.
+    ///
+    /// # Panics
+    ///
+    /// - Any [`BufferView`] or [`BufferViewMut`] of this buffer is still alive.
+    pub fn unmap(&self) {
+        #[cfg(not(target_arch = "wasm32"))]
+        log::trace!("buffer{}.unmap();", self.debug_id());
+
+        let inner = match self.inner.as_ref() {
+            Some(inner) => inner,
+            None => return,
+        };
+
+        let lock = inner.0.adapter.lock(None);
+        let gl = lock.get_glow();
+
+        inner.unmap(&gl);
+    }
+}
+
+impl<'a> BufferSlice<'a> {
+    /// Map the buffer. Buffer is ready to map once the callback is called.
+    ///
+    /// On GL the read back happens immediately, so `callback` is called before this function returns.
+    /// [`MapMode::Write`] never reads back from the GPU, buffers with [`BufferUsages::MAP_WRITE`]
+    /// keep a CPU copy instead.
+    ///
+    /// The callback will be called with `Err` if the buffer is already mapped,
+    /// was not created with the [`BufferUsages::MAP_READ`] / [`BufferUsages::MAP_WRITE`]
+    /// usage matching `mode`, the offset is not a multiple of [`MAP_ALIGNMENT`],
+    /// the size is not a multiple of 4, the range overruns the buffer, the buffer is invalid,
+    /// or the read back failed.
+    pub fn map_async(
+        &self,
+        mode: MapMode,
+        callback: impl FnOnce(Result<(), BufferAsyncError>) + 'static,
+    ) {
+        #[cfg(not(target_arch = "wasm32"))]
+        log::trace!(
+            "buffer{}.slice({}..{}).map_async(MapMode::{:?}, |_| {{}});",
+            self.buffer.debug_id(),
+            self.offset,
+            self.offset + self.size(),
+            mode
+        );
+
+        let inner = match self.buffer.inner.as_ref() {
+            Some(inner) => inner,
+            None => {
+                log::error!("BufferSlice::map_async: buffer is invalid");
+                callback(Err(BufferAsyncError));
+                return;
+            }
+        };
+
+        let required = match mode {
+            MapMode::Read => BufferUsages::MAP_READ,
+            MapMode::Write => BufferUsages::MAP_WRITE,
+        };
+        if !self.buffer.usage.contains(required) {
+            callback(Err(BufferAsyncError));
+            return;
+        }
+
+        let size = self.size();
+        if self.offset % MAP_ALIGNMENT != 0
+            || size % 4 != 0
+            || self.offset + size > self.buffer.size
+        {
+            log::error!(
+                "BufferSlice::map_async: range {}..{} is misaligned or overruns buffer of size {}",
+                self.offset,
+                self.offset + size,
+                self.buffer.size
+            );
+            callback(Err(BufferAsyncError));
+            return;
+        }
+
+        let is_mapped = {
+            let lock = inner.0.adapter.lock(None);
+            let gl = lock.get_glow();
+
+            inner.map(&gl, self.offset as i32, size as i32, mode == MapMode::Write)
+        };
+
+        if is_mapped {
+            callback(Ok(()));
+        } else {
+            callback(Err(BufferAsyncError));
+        }
+    }
+
+    /// Synchronously and immediately map a buffer for reading. If the buffer is not immediately mappable
+    /// through [`BufferDescriptor::mapped_at_creation`] or [`BufferSlice::map_async`], will panic.
+    ///
+    /// An invalid buffer is never mappable.
+    pub fn get_mapped_range(&self) -> BufferView<'a> {
+        let size = self.size();
+        let mapping = self
+            .buffer
+            .inner
+            .as_ref()
+            .expect("get_mapped_range: buffer is invalid")
+            .get_mapped_range(self.offset as i32, size as i32, false);
+
+        BufferView {
+            slice: *self,
+            mapping,
+            size: size as usize,
+        }
+    }
+
+    /// Synchronously and immediately map a buffer for writing. If the buffer is not immediately mappable
+    /// through [`BufferDescriptor::mapped_at_creation`] or [`BufferSlice::map_async`], will panic.
+    ///
+    /// An invalid buffer is never mappable.
+    pub fn get_mapped_range_mut(&self) -> BufferViewMut<'a> {
+        let size = self.size();
+        let mapping = self
+            .buffer
+            .inner
+            .as_ref()
+            .expect("get_mapped_range_mut: buffer is invalid")
+            .get_mapped_range(self.offset as i32, size as i32, true);
+
+        BufferViewMut {
+            slice: *self,
+            mapping,
+            size: size as usize,
+        }
+    }
+
+    // 没有 指定 size 时，到 buffer 末尾；offset 超出 buffer 时 为 0，由 调用者 检查
+    #[inline]
+    fn size(&self) -> BufferAddress {
+        match self.size {
+            Some(size) => size.get(),
+            None => self.buffer.size.saturating_sub(self.offset),
+        }
+    }
+}
+
+/// Type of buffer mapping.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum MapMode {
+    /// Map only for reading
+    Read,
+    /// Map only for writing
+    Write,
+}
+
+/// Error occurred when trying to async map a buffer.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct BufferAsyncError;
+
+impl std::fmt::Display for BufferAsyncError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Error occurred when trying to async map a buffer")
+    }
+}
+
+impl std::error::Error for BufferAsyncError {}
+
+/// Read only view into a mapped buffer.
+///
+/// To get a `BufferView`, first [map] the buffer, and then
+/// call `buffer.slice(range).get_mapped_range()`.
+///
+/// [map]: BufferSlice::map_async
+#[derive(Debug)]
+pub struct BufferView<'a> {
+    slice: BufferSlice<'a>,
+    #[debug(skip)]
+    mapping: hal::BufferMapping,
+    size: usize,
+}
+
+impl Deref for BufferView<'_> {
+    type Target = [u8];
+
+    #[inline]
+    fn deref(&self) -> &[u8] {
+        unsafe { std::slice::from_raw_parts(self.mapping.ptr.as_ptr(), self.size) }
+    }
+}
+
+impl AsRef<[u8]> for BufferView<'_> {
+    #[inline]
+    fn as_ref(&self) -> &[u8] {
+        self
+    }
+}
+
+impl Drop for BufferView<'_> {
+    fn drop(&mut self) {
+        if let Some(inner) = self.slice.buffer.inner.as_ref() {
+            inner.release_mapped_range();
+        }
+    }
+}
+
+/// Write only view into mapped buffer.
+///
+/// To get a `BufferViewMut`, first [map] the buffer, and then
+/// call `buffer.slice(range).get_mapped_range_mut()`.
+///
+/// It is possible to read the buffer using this view, but doing so is not
+/// recommended, as it is likely to be slow.
+///
+/// [map]: BufferSlice::map_async
+#[derive(Debug)]
+pub struct BufferViewMut<'a> {
+    slice: BufferSlice<'a>,
+    #[debug(skip)]
+    mapping: hal::BufferMapping,
+    size: usize,
+}
+
+impl Deref for BufferViewMut<'_> {
+    type Target = [u8];
+
+    #[inline]
+    fn deref(&self) -> &[u8] {
+        unsafe { std::slice::from_raw_parts(self.mapping.ptr.as_ptr(), self.size) }
+    }
+}
+
+impl DerefMut for BufferViewMut<'_> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut [u8] {
+        unsafe { std::slice::from_raw_parts_mut(self.mapping.ptr.as_ptr(), self.size) }
+    }
+}
+
+impl AsMut<[u8]> for BufferViewMut<'_> {
+    #[inline]
+    fn as_mut(&mut self) -> &mut [u8] {
+        self
+    }
+}
+
+impl Drop for BufferViewMut<'_> {
+    fn drop(&mut self) {
+        if let Some(inner) = self.slice.buffer.inner.as_ref() {
+            inner.release_mapped_range();
+        }
+    }
 }
 
 /// Slice into a [`Buffer`].
@@ -125,7 +385,7 @@ pub struct BufferSlice<'a> {
 #[derive(Clone, Debug)]
 pub struct BufferBinding<'a> {
     /// The buffer to bind.
-    #[debug("&buffer{:?}", buffer.inner.0.raw)]
+    #[debug("&buffer{:?}", buffer.inner.as_ref().map(|b| b.0.raw))]
     pub buffer: &'a Buffer,
     /// Base offset of the buffer. For bindings with `dynamic == true`, this offset
     /// will be added to the dynamic offset provided in [`RenderPass::set_bind_group`].
@@ -1,13 +1,12 @@
 use std::ops::Range;
 
-use crate::pi_wgpu::hal::AdapterContextLock;
-
 use super::super::{
-    hal, BindGroup, Buffer, BufferSlice, Color, DynamicOffset, IndexFormat, Label, Operations,
-    RenderPipeline, TextureView,
+    hal, BindGroup, Buffer, BufferAddress, BufferSlice, BufferUsages, Color, ComputePipeline,
+    DynamicOffset, Extent3d, ImageCopyTexture, IndexFormat, Label, Operations, QueryType, RenderBundle, RenderPipeline, TextureDimension, TextureViewDimension,
+    TextureUsages, TextureView, COPY_BUFFER_ALIGNMENT, COPY_BYTES_PER_ROW_ALIGNMENT,
+    QUERY_RESOLVE_BUFFER_ALIGNMENT, QUERY_SIZE, PUSH_CONSTANT_ALIGNMENT, ShaderStages,
 };
 use derive_more::Debug;
-use glow::HasContext;
 
 /// Handle to a command buffer on the GPU.
 ///
@@ -44,12 +43,12 @@ impl CommandEncoder {
 
 impl CommandEncoder {
     /// Finishes recording and returns a [`CommandBuffer`] that can be submitted for execution.
-    pub fn finish(self) -> CommandBuffer {
+    pub fn finish(mut self) -> CommandBuffer {
         log::trace!("command_encoder.finish();
         }}");
 
         CommandBuffer {
-            inner: hal::CommandBuffer,
+            inner: self.inner.finish(),
         }
     }
     /// Begins recording of a render pass.
@@ -64,13 +63,532 @@ impl CommandEncoder {
             desc,
         );
 
-        let gl = self.inner.begin_render_pass(desc);
+        // GL 一次 只能 挂 3D 纹理 的 一个 切片：视图 用 base_array_layer 选 切片，
+        // 可以是 3D 纹理 的 D2 视图，或者 只含 一个 切片 的 D3 视图
+        for c in desc.color_attachments.iter().flatten() {
+            let Some(view) = c.view.inner.as_ref() else {
+                continue;
+            };
+            assert!(
+                view.dimension != TextureViewDimension::D3 || view.array_layers.len() == 1,
+                "begin_render_pass: a 3D color attachment must select one depth slice with base_array_layer and array_layer_count = Some(1), view covers slices {:?}",
+                view.array_layers
+            );
+        }
+
+        if let Some(query_set) = desc.occlusion_query_set {
+            assert!(
+                query_set.ty == QueryType::Occlusion,
+                "begin_render_pass: occlusion_query_set must be an occlusion query set"
+            );
+        }
+        if let Some(writes) = desc.timestamp_writes.as_ref() {
+            assert!(
+                writes.query_set.ty == QueryType::Timestamp,
+                "begin_render_pass: timestamp_writes must use a timestamp query set"
+            );
+            assert!(
+                [writes.beginning_of_pass_write_index, writes.end_of_pass_write_index]
+                    .iter()
+                    .flatten()
+                    .all(|&i| i < writes.query_set.count),
+                "begin_render_pass: timestamp write index is out of range"
+            );
+        }
+
+        // WebGPU 里 编码器 变成 无效；这里 整个 通道 不录制
+        let is_view_invalid = desc
+            .color_attachments
+            .iter()
+            .flatten()
+            .any(|c| c.view.inner.is_none() || c.resolve_target.map_or(false, |r| r.inner.is_none()))
+            || desc
+                .depth_stencil_attachment
+                .as_ref()
+                .map_or(false, |ds| ds.view.inner.is_none());
+        if is_view_invalid {
+            log::error!(
+                "begin_render_pass: label = {:?}, attachment view is invalid, the pass is skipped",
+                desc.label
+            );
+            self.inner.begin_invalid_render_pass();
+        } else {
+            self.inner.begin_render_pass(desc);
+        }
 
         RenderPass {
-            lock: gl,
-            encoder: &self.inner,
+            encoder: &mut self.inner,
+        }
+    }
+
+    /// Begins recording of a compute pass.
+    ///
+    /// This function returns a [`ComputePass`] object which records a single compute pass.
+    pub fn begin_compute_pass(&mut self, desc: &ComputePassDescriptor) -> ComputePass {
+        log::trace!(
+            "let mut compute_pass = command_encoder.begin_compute_pass(&{:?});",
+            desc,
+        );
+
+        if let Some(writes) = desc.timestamp_writes.as_ref() {
+            assert!(
+                writes.query_set.ty == QueryType::Timestamp,
+                "begin_compute_pass: timestamp_writes must use a timestamp query set"
+            );
+            assert!(
+                [writes.beginning_of_pass_write_index, writes.end_of_pass_write_index]
+                    .iter()
+                    .flatten()
+                    .all(|&i| i < writes.query_set.count),
+                "begin_compute_pass: timestamp write index is out of range"
+            );
+        }
+
+        self.inner.begin_compute_pass(desc);
+
+        ComputePass {
+            encoder: &mut self.inner,
+        }
+    }
+
+    /// Copy data from one buffer to another.
+    ///
+    /// # Panics
+    ///
+    /// - Buffer offsets or copy size not a multiple of [`COPY_BUFFER_ALIGNMENT`].
+    /// - Copy would overrun buffer.
+    /// - Copy within the same buffer.
+    pub fn copy_buffer_to_buffer(
+        &mut self,
+        source: &Buffer,
+        source_offset: BufferAddress,
+        destination: &Buffer,
+        destination_offset: BufferAddress,
+        copy_size: BufferAddress,
+    ) {
+        #[cfg(not(target_arch = "wasm32"))]
+        log::trace!(
+            "command_encoder.copy_buffer_to_buffer(&buffer{}, {}, &buffer{}, {}, {});",
+            source.debug_id(),
+            source_offset,
+            destination.debug_id(),
+            destination_offset,
+            copy_size
+        );
+
+        assert!(
+            source.usage.contains(BufferUsages::COPY_SRC),
+            "copy_buffer_to_buffer: source buffer must have COPY_SRC usage"
+        );
+        assert!(
+            destination.usage.contains(BufferUsages::COPY_DST),
+            "copy_buffer_to_buffer: destination buffer must have COPY_DST usage"
+        );
+        let (Some(src), Some(dst)) = (source.inner.as_ref(), destination.inner.as_ref()) else {
+            log::error!("copy_buffer_to_buffer: buffer is invalid, skipped");
+            return;
+        };
+        assert!(
+            src.0.raw != dst.0.raw,
+            "copy_buffer_to_buffer: copy within the same buffer"
+        );
+        assert!(
+            source_offset % COPY_BUFFER_ALIGNMENT == 0
+                && destination_offset % COPY_BUFFER_ALIGNMENT == 0
+                && copy_size % COPY_BUFFER_ALIGNMENT == 0,
+            "copy_buffer_to_buffer: offsets and size must be multiples of COPY_BUFFER_ALIGNMENT"
+        );
+        assert!(
+            source_offset + copy_size <= source.size
+                && destination_offset + copy_size <= destination.size,
+            "copy_buffer_to_buffer: copy would overrun buffer"
+        );
+
+        if copy_size == 0 {
+            return;
         }
+
+        self.inner.copy_buffer_to_buffer(
+            src,
+            source_offset as i32,
+            dst,
+            destination_offset as i32,
+            copy_size as i32,
+        );
     }
+
+    /// Resolves a query set, writing the results into the supplied destination buffer.
+    ///
+    /// Queries may be between 8 and 40 bytes each. See [`PipelineStatisticsTypes`] for more information.
+    ///
+    /// The results are read back when the command buffer is submitted, which waits for the GPU.
+    pub fn resolve_query_set(
+        &mut self,
+        query_set: &QuerySet,
+        query_range: Range<u32>,
+        destination: &Buffer,
+        destination_offset: BufferAddress,
+    ) {
+        #[cfg(not(target_arch = "wasm32"))]
+        log::trace!(
+            "command_encoder.resolve_query_set(&query_set, {:?}, &buffer{}, {});",
+            query_range,
+            destination.debug_id(),
+            destination_offset
+        );
+
+        assert!(
+            destination.usage.contains(BufferUsages::QUERY_RESOLVE),
+            "resolve_query_set: destination buffer must have QUERY_RESOLVE usage"
+        );
+        assert!(
+            destination_offset % QUERY_RESOLVE_BUFFER_ALIGNMENT == 0,
+            "resolve_query_set: destination offset must be a multiple of QUERY_RESOLVE_BUFFER_ALIGNMENT"
+        );
+        assert!(
+            query_range.start <= query_range.end && query_range.end <= query_set.count,
+            "resolve_query_set: query range {:?} is out of the query set of {} queries",
+            query_range,
+            query_set.count
+        );
+        assert!(
+            destination_offset + query_range.len() as BufferAddress * QUERY_SIZE as BufferAddress
+                <= destination.size,
+            "resolve_query_set: resolve would overrun buffer"
+        );
+
+        // 无效 的 查询集 没有 结果 可写
+        let inner = match query_set.inner.as_ref() {
+            Some(inner) if !query_range.is_empty() => inner,
+            _ => return,
+        };
+        let Some(dst) = destination.inner.as_ref() else {
+            log::error!("resolve_query_set: destination buffer is invalid, skipped");
+            return;
+        };
+
+        self.inner.resolve_query_set(
+            inner,
+            query_range,
+            dst,
+            destination_offset as i32,
+        );
+    }
+
+    /// Copy data from a buffer to a texture.
+    pub fn copy_buffer_to_texture(
+        &mut self,
+        source: ImageCopyBuffer,
+        destination: ImageCopyTexture,
+        copy_size: Extent3d,
+    ) {
+        #[cfg(not(target_arch = "wasm32"))]
+        log::trace!(
+            "command_encoder.copy_buffer_to_texture(pi_wgpu::ImageCopyBuffer {{
+            buffer: &buffer{},
+            layout: {:?},
+        }}, pi_wgpu::ImageCopyTexture {{
+            texture: &texture{},
+            mip_level: {:?},
+            origin: {:?},
+            aspect: {:?},
+        }}, {:?});",
+            source.buffer.debug_id(),
+            source.layout,
+            destination.texture.debug_str(),
+            destination.mip_level,
+            destination.origin,
+            destination.aspect,
+            copy_size
+        );
+
+        assert!(
+            source.buffer.usage.contains(BufferUsages::COPY_SRC),
+            "copy_buffer_to_texture: source buffer must have COPY_SRC usage"
+        );
+        assert!(
+            destination.texture.usage().contains(TextureUsages::COPY_DST),
+            "copy_buffer_to_texture: destination texture must have COPY_DST usage"
+        );
+        validate_texture_copy_range(&destination, &copy_size);
+        validate_linear_texture_data(&source, &destination, &copy_size);
+
+        if copy_size.width == 0 || copy_size.height == 0 || copy_size.depth_or_array_layers == 0 {
+            return;
+        }
+
+        if source.buffer.inner.is_none() || destination.texture.inner.is_none() {
+            log::error!("copy_buffer_to_texture: buffer or texture is invalid, skipped");
+            return;
+        }
+
+        self.inner
+            .copy_buffer_to_texture(&source, &destination, copy_size);
+    }
+
+    /// Copy data from a texture to a buffer.
+    pub fn copy_texture_to_buffer(
+        &mut self,
+        source: ImageCopyTexture,
+        destination: ImageCopyBuffer,
+        copy_size: Extent3d,
+    ) {
+        #[cfg(not(target_arch = "wasm32"))]
+        log::trace!(
+            "command_encoder.copy_texture_to_buffer(pi_wgpu::ImageCopyTexture {{
+            texture: &texture{},
+            mip_level: {:?},
+            origin: {:?},
+            aspect: {:?},
+        }}, pi_wgpu::ImageCopyBuffer {{
+            buffer: &buffer{},
+            layout: {:?},
+        }}, {:?});",
+            source.texture.debug_str(),
+            source.mip_level,
+            source.origin,
+            source.aspect,
+            destination.buffer.debug_id(),
+            destination.layout,
+            copy_size
+        );
+
+        assert!(
+            source.texture.usage().contains(TextureUsages::COPY_SRC),
+            "copy_texture_to_buffer: source texture must have COPY_SRC usage"
+        );
+        assert!(
+            destination.buffer.usage.contains(BufferUsages::COPY_DST),
+            "copy_texture_to_buffer: destination buffer must have COPY_DST usage"
+        );
+        validate_texture_copy_range(&source, &copy_size);
+        validate_linear_texture_data(&destination, &source, &copy_size);
+
+        if copy_size.width == 0 || copy_size.height == 0 || copy_size.depth_or_array_layers == 0 {
+            return;
+        }
+
+        if source.texture.inner.is_none() || destination.buffer.inner.is_none() {
+            log::error!("copy_texture_to_buffer: texture or buffer is invalid, skipped");
+            return;
+        }
+
+        self.inner
+            .copy_texture_to_buffer(&source, &destination, copy_size);
+    }
+
+    /// Copy data from one texture to another.
+    ///
+    /// # Panics
+    ///
+    /// - Textures are not the same type
+    /// - If a depth texture, or a multisampled texture, the entire texture must be copied
+    /// - Copy would overrun either texture
+    pub fn copy_texture_to_texture(
+        &mut self,
+        source: ImageCopyTexture,
+        destination: ImageCopyTexture,
+        copy_size: Extent3d,
+    ) {
+        log::trace!(
+            "command_encoder.copy_texture_to_texture(pi_wgpu::ImageCopyTexture {{
+            texture: &texture{},
+            mip_level: {:?},
+            origin: {:?},
+            aspect: {:?},
+        }}, pi_wgpu::ImageCopyTexture {{
+            texture: &texture{},
+            mip_level: {:?},
+            origin: {:?},
+            aspect: {:?},
+        }}, {:?});",
+            source.texture.debug_str(),
+            source.mip_level,
+            source.origin,
+            source.aspect,
+            destination.texture.debug_str(),
+            destination.mip_level,
+            destination.origin,
+            destination.aspect,
+            copy_size
+        );
+
+        let (src, dst) = (source.texture, destination.texture);
+
+        assert!(
+            src.usage().contains(TextureUsages::COPY_SRC),
+            "copy_texture_to_texture: source texture must have COPY_SRC usage"
+        );
+        assert!(
+            dst.usage().contains(TextureUsages::COPY_DST),
+            "copy_texture_to_texture: destination texture must have COPY_DST usage"
+        );
+        assert!(
+            src.format().remove_srgb_suffix() == dst.format().remove_srgb_suffix(),
+            "copy_texture_to_texture: formats are not copy compatible, {:?} and {:?}",
+            src.format(),
+            dst.format()
+        );
+        assert!(
+            src.sample_count() == dst.sample_count(),
+            "copy_texture_to_texture: sample counts are not the same"
+        );
+        assert!(
+            !src.format().is_compressed(),
+            "copy_texture_to_texture: compressed formats can not be copied on gl"
+        );
+        if src.format().is_depth_stencil_format() || src.sample_count() > 1 {
+            assert!(
+                copy_size == src.size() && copy_size == dst.size(),
+                "copy_texture_to_texture: depth / multisampled textures must be copied entirely"
+            );
+        }
+        validate_texture_copy_range(&source, &copy_size);
+        validate_texture_copy_range(&destination, &copy_size);
+
+        if copy_size.width == 0 || copy_size.height == 0 || copy_size.depth_or_array_layers == 0 {
+            return;
+        }
+
+        if src.inner.is_none() || dst.inner.is_none() {
+            log::error!("copy_texture_to_texture: texture is invalid, skipped");
+            return;
+        }
+
+        self.inner
+            .copy_texture_to_texture(&source, &destination, copy_size);
+    }
+}
+
+// push constant 的 偏移 和 长度 要 4 字节 对齐，且 不能 超出 MAX_PUSH_CONSTANTS
+pub(super) fn check_push_constants(offset: u32, data: &[u8]) {
+    assert!(
+        offset % PUSH_CONSTANT_ALIGNMENT == 0,
+        "set_push_constants: offset {} must be a multiple of {}",
+        offset,
+        PUSH_CONSTANT_ALIGNMENT
+    );
+    assert!(
+        data.len() as u32 % PUSH_CONSTANT_ALIGNMENT == 0,
+        "set_push_constants: data size {} must be a multiple of {}",
+        data.len(),
+        PUSH_CONSTANT_ALIGNMENT
+    );
+    assert!(
+        offset as usize + data.len() <= hal::MAX_PUSH_CONSTANTS * 4,
+        "set_push_constants: {}..{} exceeds max_push_constant_size {}",
+        offset,
+        offset as usize + data.len(),
+        hal::MAX_PUSH_CONSTANTS * 4
+    );
+}
+
+// 拷贝区域 不能 超出 纹理 对应 mip 的 大小，且 要 按 块 对齐
+fn validate_texture_copy_range(copy: &ImageCopyTexture, size: &Extent3d) {
+    let texture = copy.texture;
+
+    assert!(
+        copy.mip_level < texture.mip_level_count(),
+        "texture copy: mip level {} is out of range",
+        copy.mip_level
+    );
+
+    let mip_size = texture.size().mip_level_size(copy.mip_level, texture.dimension());
+    let layers = if texture.dimension() == TextureDimension::D3 {
+        mip_size.depth_or_array_layers
+    } else {
+        texture.depth_or_array_layers()
+    };
+    assert!(
+        copy.origin.x + size.width <= mip_size.width
+            && copy.origin.y + size.height <= mip_size.height
+            && copy.origin.z + size.depth_or_array_layers <= layers,
+        "texture copy: copy would overrun texture, origin = {:?}, size = {:?}, mip size = {:?}",
+        copy.origin,
+        size,
+        mip_size
+    );
+
+    let (block_width, block_height) = texture.format().block_dimensions();
+    assert!(
+        copy.origin.x % block_width == 0
+            && copy.origin.y % block_height == 0
+            && size.width % block_width == 0
+            && size.height % block_height == 0,
+        "texture copy: origin and size must be multiples of the block size"
+    );
+}
+
+// buffer 中的 纹理数据 要 满足 wgt 的 对齐规则，且 不能 超出 buffer
+fn validate_linear_texture_data(
+    buffer: &ImageCopyBuffer,
+    texture: &ImageCopyTexture,
+    size: &Extent3d,
+) {
+    let format = texture.texture.format();
+    let layout = &buffer.layout;
+
+    assert!(
+        texture.texture.sample_count() == 1,
+        "buffer texture copy: multisampled textures can not be copied to / from buffers"
+    );
+
+    let (block_width, block_height) = format.block_dimensions();
+    let block_size = format
+        .block_copy_size(Some(texture.aspect))
+        .expect("buffer texture copy: aspect is not copyable") as u64;
+
+    let width_blocks = (size.width / block_width) as u64;
+    let height_blocks = (size.height / block_height) as u64;
+    let row_bytes = width_blocks * block_size;
+
+    assert!(
+        layout.offset % block_size == 0,
+        "buffer texture copy: buffer offset must be a multiple of the texel block size"
+    );
+
+    if let Some(bytes_per_row) = layout.bytes_per_row {
+        assert!(
+            bytes_per_row % COPY_BYTES_PER_ROW_ALIGNMENT == 0,
+            "buffer texture copy: bytes_per_row must be a multiple of COPY_BYTES_PER_ROW_ALIGNMENT"
+        );
+        assert!(
+            bytes_per_row as u64 >= row_bytes,
+            "buffer texture copy: bytes_per_row is less than one row of the copy"
+        );
+    } else {
+        assert!(
+            height_blocks <= 1 && size.depth_or_array_layers <= 1,
+            "buffer texture copy: bytes_per_row must be specified when copying more than one row"
+        );
+    }
+
+    if let Some(rows_per_image) = layout.rows_per_image {
+        assert!(
+            rows_per_image as u64 >= height_blocks,
+            "buffer texture copy: rows_per_image is less than the copy height"
+        );
+    } else {
+        assert!(
+            size.depth_or_array_layers <= 1,
+            "buffer texture copy: rows_per_image must be specified when copying more than one layer"
+        );
+    }
+
+    // 最后一层 的 最后一行 不需要 填充
+    let bytes_per_row = layout.bytes_per_row.map_or(row_bytes, |b| b as u64);
+    let rows_per_image = layout.rows_per_image.map_or(height_blocks, |r| r as u64);
+    let required = if height_blocks == 0 || size.depth_or_array_layers == 0 {
+        0
+    } else {
+        bytes_per_row * rows_per_image * (size.depth_or_array_layers as u64 - 1)
+            + bytes_per_row * (height_blocks - 1)
+            + row_bytes
+    };
+    assert!(
+        layout.offset + required <= buffer.buffer.size,
+        "buffer texture copy: copy would overrun buffer"
+    );
 }
 
 /// Handle to a query set.
@@ -80,9 +598,30 @@ impl CommandEncoder {
 /// Corresponds to [WebGPU `GPUQuerySet`](https://gpuweb.github.io/gpuweb/#queryset).
 #[derive(Debug)]
 pub struct QuerySet {
+    // None 表示 创建失败
+    pub(crate) inner: Option<hal::QuerySet>,
+    pub(crate) ty: QueryType,
+    pub(crate) count: u32,
+}
 
+impl QuerySet {
+    #[inline]
+    pub(crate) fn from_hal(inner: Option<hal::QuerySet>, desc: &QuerySetDescriptor) -> Self {
+        Self {
+            inner,
+            ty: desc.ty,
+            count: desc.count,
+        }
+    }
 }
 
+/// Describes a [`QuerySet`].
+///
+/// For use with [`Device::create_query_set`].
+///
+/// Corresponds to [WebGPU `GPUQuerySetDescriptor`](
+/// https://gpuweb.github.io/gpuweb/#dictdef-gpuquerysetdescriptor).
+pub type QuerySetDescriptor<'a> = super::super::wgt::QuerySetDescriptor<Label<'a>>;
 
 /// Describes the timestamp writes of a render pass.
 ///
@@ -137,8 +676,7 @@ pub struct RenderPassDescriptor<'tex, 'desc> {
 /// https://gpuweb.github.io/gpuweb/#render-pass-encoder).
 #[derive(Debug)]
 pub struct RenderPass<'a> {
-    lock: AdapterContextLock<'a>,
-    encoder: &'a hal::CommandEncoder,
+    encoder: &'a mut hal::CommandEncoder,
 }
 
 impl<'a> Drop for RenderPass<'a> {
@@ -149,8 +687,8 @@ impl<'a> Drop for RenderPass<'a> {
 }
 
 impl<'a> RenderPass<'a> {
-    pub fn flush(&self) {
-        unsafe { self.lock.get_glow().flush();}
+    pub fn flush(&mut self) {
+        self.encoder.flush();
     }
     /// Sets the active bind group for a given bind group index. The bind group layout
     /// in the active pipeline when any `draw()` function is called must match the layout of this bind group.
@@ -167,12 +705,17 @@ impl<'a> RenderPass<'a> {
         log::trace!(
             "render_pass.set_bind_group({:?}, &bind_group{:?}, &{:?});",
             index,
-            bind_group.inner.id,
+            bind_group.debug_id(),
             offsets
         );
 
-        self.encoder
-            .set_bind_group(index, &bind_group.inner, offsets)
+        match bind_group.inner.as_ref() {
+            Some(inner) => self.encoder.set_bind_group(index, inner, offsets),
+            None => {
+                log::error!("render_pass.set_bind_group: bind group {} is invalid, draws are skipped until a valid one is set", index);
+                self.encoder.set_invalid_bind_group(index);
+            }
+        }
     }
 
     /// Sets the active render pipeline.
@@ -181,10 +724,16 @@ impl<'a> RenderPass<'a> {
     pub fn set_pipeline(&mut self, pipeline: &'a RenderPipeline) {
         log::trace!(
             "render_pass.set_pipeline(&render_pipeline{:?});",
-            pipeline.inner.0.id
+            pipeline.debug_id()
         );
-        self.encoder
-            .set_render_pipeline(&self.lock.get_glow(), &pipeline.inner)
+
+        match pipeline.inner.as_ref() {
+            Some(inner) => self.encoder.set_render_pipeline(inner),
+            None => {
+                log::error!("render_pass.set_pipeline: pipeline is invalid, draws are skipped until a valid pipeline is set");
+                self.encoder.set_invalid_render_pipeline();
+            }
+        }
     }
 
     /// Sets the blend color as used by some of the blending modes.
@@ -198,8 +747,7 @@ impl<'a> RenderPass<'a> {
             color.b as f32,
             color.a as f32,
         ];
-        self.encoder
-            .set_blend_constants(&self.lock.get_glow(), &arr)
+        self.encoder.set_blend_constants(&arr)
     }
 
     /// Sets the active index buffer.
@@ -211,14 +759,14 @@ impl<'a> RenderPass<'a> {
         match buffer_slice.size {
             Some(r) => log::trace!(
                 "render_pass.set_index_buffer(buffer{}.slice({}..{}), IndexFormat::{:?});",
-                buffer_slice.buffer.inner.0.raw.0.get(),
+                buffer_slice.buffer.debug_id(),
                 buffer_slice.offset,
                 buffer_slice.offset + r.get(),
                 index_format
             ),
             None => log::trace!(
                 "render_pass.set_index_buffer(buffer{}.slice({}..), IndexFormat::{:?});",
-                buffer_slice.buffer.inner.0.raw.0.get(),
+                buffer_slice.buffer.debug_id(),
                 buffer_slice.offset,
                 index_format
             ),
@@ -230,8 +778,7 @@ impl<'a> RenderPass<'a> {
             size: buffer_slice.size,
         };
 
-        self.encoder
-            .set_index_buffer(&self.lock.get_glow(), binding, index_format)
+        self.encoder.set_index_buffer(binding, index_format)
     }
 
     /// Assign a vertex buffer to a slot.
@@ -250,14 +797,14 @@ impl<'a> RenderPass<'a> {
             Some(r) => log::trace!(
                 "render_pass.set_vertex_buffer({}, buffer{}.slice({}..{}));",
                 slot,
-                buffer_slice.buffer.inner.0.raw.0.get(),
+                buffer_slice.buffer.debug_id(),
                 buffer_slice.offset,
                 buffer_slice.offset + r.get()
             ),
             None => log::trace!(
                 "render_pass.set_vertex_buffer({}, buffer{}.slice({}..));",
                 slot,
-                buffer_slice.buffer.inner.0.raw.0.get(),
+                buffer_slice.buffer.debug_id(),
                 buffer_slice.offset
             ),
         };
@@ -276,7 +823,6 @@ impl<'a> RenderPass<'a> {
     pub fn set_scissor_rect(&mut self, x: u32, y: u32, width: u32, height: u32) {
         log::trace!("render_pass.set_scissor_rect({x}, {y}, {width}, {height});");
         self.encoder.set_scissor_rect(
-            &self.lock.get_glow(),
             x as i32,
             y as i32,
             width as i32,
@@ -290,7 +836,6 @@ impl<'a> RenderPass<'a> {
     pub fn set_viewport(&mut self, x: f32, y: f32, w: f32, h: f32, min_depth: f32, max_depth: f32) {
         log::trace!("render_pass.set_viewport({x:?}, {y:?}, {w:?}, {h:?}, {min_depth:?}, {max_depth:?});");
         self.encoder.set_viewport(
-            &self.lock.get_glow(),
             x as i32,
             y as i32,
             w as i32,
@@ -305,8 +850,31 @@ impl<'a> RenderPass<'a> {
     /// Subsequent stencil tests will test against this value.
     pub fn set_stencil_reference(&mut self, reference: u32) {
         log::trace!("render_pass.set_stencil_reference({reference});");
-        self.encoder
-            .set_stencil_reference(&self.lock.get_glow(), reference)
+        self.encoder.set_stencil_reference(reference)
+    }
+
+    /// Set push constant data for subsequent draw calls.
+    ///
+    /// Write the bytes in `data` at offset `offset` within push constant
+    /// storage, all of which are accessible by all the pipeline stages in
+    /// `stages`, and no others.  Both `offset` and the length of `data` must be
+    /// multiples of [`PUSH_CONSTANT_ALIGNMENT`], which is always 4.
+    ///
+    /// For example, if `offset` is `4` and `data` is eight bytes long, this
+    /// call will write `data` to bytes `4..12` of push constant storage.
+    ///
+    /// Requires [`Features::PUSH_CONSTANTS`] to be enabled.
+    pub fn set_push_constants(&mut self, stages: ShaderStages, offset: u32, data: &[u8]) {
+        log::trace!(
+            "render_pass.set_push_constants(ShaderStages::{:?}, {}, &{:?});",
+            stages,
+            offset,
+            data
+        );
+
+        check_push_constants(offset, data);
+
+        self.encoder.set_push_constants(stages, offset, data)
     }
 
     /// Draws primitives from the active vertex buffer(s).
@@ -315,7 +883,6 @@ impl<'a> RenderPass<'a> {
     pub fn draw(&mut self, vertices: Range<u32>, instances: Range<u32>) {
         log::trace!("render_pass.draw({:?}, {:?});", vertices, instances);
         self.encoder.draw(
-            &self.lock.get_glow(),
             vertices.start,
             vertices.len() as u32,
             instances.start,
@@ -327,10 +894,13 @@ impl<'a> RenderPass<'a> {
     ///
     /// The active index buffer can be set with [`RenderPass::set_index_buffer`], while the active
     /// vertex buffers can be set with [`RenderPass::set_vertex_buffer`].
+    ///
+    /// Without [`DownlevelFlags::BASE_VERTEX`](crate::DownlevelFlags::BASE_VERTEX) a non-zero `base_vertex` is emulated by offsetting
+    /// the vertex attributes; a negative `base_vertex` that would move an attribute before the start
+    /// of its buffer skips the draw and logs an error.
     pub fn draw_indexed(&mut self, indices: Range<u32>, base_vertex: i32, instances: Range<u32>) {
         log::trace!("render_pass.draw_indexed({indices:?}, {base_vertex:?}, {instances:?});");
         self.encoder.draw_indexed(
-            &self.lock.get_glow(),
             indices.start,
             indices.len() as u32,
             base_vertex,
@@ -338,6 +908,240 @@ impl<'a> RenderPass<'a> {
             instances.len() as u32,
         )
     }
+
+    /// Draws primitives from the active vertex buffer(s) based on the contents of the `indirect_buffer`.
+    ///
+    /// The active vertex buffers can be set with [`RenderPass::set_vertex_buffer`].
+    ///
+    /// The structure expected in `indirect_buffer` must conform to [`DrawIndirectArgs`](crate::util::DrawIndirectArgs).
+    pub fn draw_indirect(&mut self, indirect_buffer: &'a Buffer, indirect_offset: BufferAddress) {
+        #[cfg(not(target_arch = "wasm32"))]
+        log::trace!(
+            "render_pass.draw_indirect(&buffer{}, {});",
+            indirect_buffer.debug_id(),
+            indirect_offset
+        );
+
+        assert!(indirect_buffer.usage.contains(BufferUsages::INDIRECT));
+        assert!(indirect_offset % 4 == 0);
+        assert!(indirect_offset + 16 <= indirect_buffer.size);
+
+        let Some(inner) = indirect_buffer.inner.as_ref() else {
+            log::error!("render_pass.draw_indirect: indirect buffer is invalid, skipped");
+            return;
+        };
+
+        self.encoder.draw_indirect(inner, indirect_offset as i32)
+    }
+
+    /// Draws indexed primitives using the active index buffer and the active vertex buffers,
+    /// based on the contents of the `indirect_buffer`.
+    ///
+    /// The active index buffer can be set with [`RenderPass::set_index_buffer`], while the active
+    /// vertex buffers can be set with [`RenderPass::set_vertex_buffer`].
+    ///
+    /// The structure expected in `indirect_buffer` must conform to [`DrawIndexedIndirectArgs`](crate::util::DrawIndexedIndirectArgs).
+    pub fn draw_indexed_indirect(
+        &mut self,
+        indirect_buffer: &'a Buffer,
+        indirect_offset: BufferAddress,
+    ) {
+        #[cfg(not(target_arch = "wasm32"))]
+        log::trace!(
+            "render_pass.draw_indexed_indirect(&buffer{}, {});",
+            indirect_buffer.debug_id(),
+            indirect_offset
+        );
+
+        assert!(indirect_buffer.usage.contains(BufferUsages::INDIRECT));
+        assert!(indirect_offset % 4 == 0);
+        assert!(indirect_offset + 20 <= indirect_buffer.size);
+
+        let Some(inner) = indirect_buffer.inner.as_ref() else {
+            log::error!("render_pass.draw_indexed_indirect: indirect buffer is invalid, skipped");
+            return;
+        };
+
+        self.encoder.draw_indexed_indirect(inner, indirect_offset as i32)
+    }
+
+    /// Start a occlusion query on this render pass. It can be ended with
+    /// `end_occlusion_query`. Occlusion queries may not be nested.
+    pub fn begin_occlusion_query(&mut self, query_index: u32) {
+        log::trace!("render_pass.begin_occlusion_query({query_index});");
+        self.encoder.begin_occlusion_query(query_index)
+    }
+
+    /// End the occlusion query on this render pass. It can be started with
+    /// `begin_occlusion_query`. Occlusion queries may not be nested.
+    pub fn end_occlusion_query(&mut self) {
+        log::trace!("render_pass.end_occlusion_query();");
+        self.encoder.end_occlusion_query()
+    }
+
+    /// Execute a [render bundle][RenderBundle], which is a set of pre-recorded commands
+    /// that can be run together.
+    ///
+    /// As in WebGPU, the pipeline, bind groups, vertex and index buffers are cleared
+    /// after each bundle, so set them again before drawing outside of the bundle.
+    pub fn execute_bundles<I: IntoIterator<Item = &'a RenderBundle>>(&mut self, render_bundles: I) {
+        log::trace!("render_pass.execute_bundles(..);");
+        for bundle in render_bundles {
+            self.encoder.execute_bundle(&bundle.inner)
+        }
+    }
+}
+
+/// Describes the timestamp writes of a compute pass.
+///
+/// For use with [`ComputePassDescriptor`].
+/// At least one of `beginning_of_pass_write_index` and `end_of_pass_write_index` must be `Some`.
+///
+/// Corresponds to [WebGPU `GPUComputePassTimestampWrites`](
+/// https://gpuweb.github.io/gpuweb/#dictdef-gpucomputepasstimestampwrites).
+#[derive(Clone, Debug)]
+pub struct ComputePassTimestampWrites<'a> {
+    /// The query set to write to.
+    pub query_set: &'a QuerySet,
+    /// The index of the query set at which a start timestamp of this pass is written, if any.
+    pub beginning_of_pass_write_index: Option<u32>,
+    /// The index of the query set at which an end timestamp of this pass is written, if any.
+    pub end_of_pass_write_index: Option<u32>,
+}
+
+/// Describes the attachments of a compute pass.
+///
+/// For use with [`CommandEncoder::begin_compute_pass`].
+///
+/// Corresponds to [WebGPU `GPUComputePassDescriptor`](
+/// https://gpuweb.github.io/gpuweb/#dictdef-gpucomputepassdescriptor).
+#[derive(Clone, Debug, Default)]
+pub struct ComputePassDescriptor<'a> {
+    /// Debug label of the compute pass. This will show up in graphics debuggers for easy identification.
+    pub label: Label<'a>,
+    /// Defines which timestamp values will be written for this pass, and where to write them to.
+    ///
+    /// Requires [`Features::TIMESTAMP_QUERY`] to be enabled.
+    pub timestamp_writes: Option<ComputePassTimestampWrites<'a>>,
+}
+
+/// In-progress recording of a compute pass.
+///
+/// It can be created with [`CommandEncoder::begin_compute_pass`].
+///
+/// Corresponds to [WebGPU `GPUComputePassEncoder`](
+/// https://gpuweb.github.io/gpuweb/#compute-pass-encoder).
+#[derive(Debug)]
+pub struct ComputePass<'a> {
+    encoder: &'a mut hal::CommandEncoder,
+}
+
+impl<'a> Drop for ComputePass<'a> {
+    fn drop(&mut self) {
+        self.encoder.end_compute_pass();
+    }
+}
+
+impl<'a> ComputePass<'a> {
+    /// Sets the active bind group for a given bind group index. The bind group layout
+    /// in the active pipeline when the `dispatch()` function is called must match the layout of this bind group.
+    ///
+    /// If the bind group have dynamic offsets, provide them in the binding order.
+    /// These offsets have to be aligned to [`Limits::min_uniform_buffer_offset_alignment`]
+    /// or [`Limits::min_storage_buffer_offset_alignment`] appropriately.
+    pub fn set_bind_group(
+        &mut self,
+        index: u32,
+        bind_group: &'a BindGroup,
+        offsets: &[DynamicOffset],
+    ) {
+        log::trace!(
+            "compute_pass.set_bind_group({:?}, &bind_group{:?}, &{:?});",
+            index,
+            bind_group.debug_id(),
+            offsets
+        );
+
+        match bind_group.inner.as_ref() {
+            Some(inner) => self.encoder.set_bind_group(index, inner, offsets),
+            None => {
+                log::error!("compute_pass.set_bind_group: bind group {} is invalid, dispatches are skipped until a valid one is set", index);
+                self.encoder.set_invalid_bind_group(index);
+            }
+        }
+    }
+
+    /// Sets the active compute pipeline.
+    pub fn set_pipeline(&mut self, pipeline: &'a ComputePipeline) {
+        log::trace!(
+            "compute_pass.set_pipeline(&compute_pipeline{:?});",
+            pipeline.debug_id()
+        );
+
+        match pipeline.inner.as_ref() {
+            Some(inner) => self.encoder.set_compute_pipeline(inner),
+            None => {
+                log::error!("compute_pass.set_pipeline: pipeline is invalid, dispatches are skipped until a valid pipeline is set");
+                self.encoder.set_invalid_compute_pipeline();
+            }
+        }
+    }
+
+    /// Set push constant data for subsequent dispatch calls.
+    ///
+    /// Write the bytes in `data` at offset `offset` within push constant
+    /// storage.  Both `offset` and the length of `data` must be
+    /// multiples of [`PUSH_CONSTANT_ALIGNMENT`], which is always 4.
+    ///
+    /// Requires [`Features::PUSH_CONSTANTS`] to be enabled.
+    pub fn set_push_constants(&mut self, offset: u32, data: &[u8]) {
+        log::trace!("compute_pass.set_push_constants({}, &{:?});", offset, data);
+
+        check_push_constants(offset, data);
+
+        self.encoder
+            .set_push_constants(ShaderStages::COMPUTE, offset, data)
+    }
+
+    /// Dispatches compute work operations.
+    ///
+    /// `x`, `y` and `z` denote the number of work groups to dispatch in each dimension.
+    pub fn dispatch_workgroups(&mut self, x: u32, y: u32, z: u32) {
+        log::trace!("compute_pass.dispatch_workgroups({x}, {y}, {z});");
+
+        if x == 0 || y == 0 || z == 0 {
+            return;
+        }
+
+        self.encoder.dispatch([x, y, z])
+    }
+
+    /// Dispatches compute work operations, based on the contents of the `indirect_buffer`.
+    ///
+    /// The structure expected in `indirect_buffer` must conform to [`DispatchIndirectArgs`](crate::util::DispatchIndirectArgs).
+    pub fn dispatch_workgroups_indirect(
+        &mut self,
+        indirect_buffer: &'a Buffer,
+        indirect_offset: BufferAddress,
+    ) {
+        #[cfg(not(target_arch = "wasm32"))]
+        log::trace!(
+            "compute_pass.dispatch_workgroups_indirect(&buffer{}, {});",
+            indirect_buffer.debug_id(),
+            indirect_offset
+        );
+
+        assert!(indirect_buffer.usage.contains(BufferUsages::INDIRECT));
+        assert!(indirect_offset % 4 == 0);
+        assert!(indirect_offset + 12 <= indirect_buffer.size);
+
+        let Some(inner) = indirect_buffer.inner.as_ref() else {
+            log::error!("compute_pass.dispatch_indirect: indirect buffer is invalid, skipped");
+            return;
+        };
+
+        self.encoder.dispatch_indirect(inner, indirect_offset as i32)
+    }
 }
 
 /// Describes a color attachment to a [`RenderPass`].
@@ -349,11 +1153,11 @@ impl<'a> RenderPass<'a> {
 #[derive(Clone, Debug)]
 pub struct RenderPassColorAttachment<'tex> {
     /// The view to use as an attachment.
-    #[debug("&texture_view{:?}", view.inner.id)]
+    #[debug("&texture_view{:?}", view.debug_id())]
     pub view: &'tex TextureView,
     /// The view that will receive the resolved output if multisampling is used.
     #[debug("{}", match resolve_target {
-        Some(r) => format!("Some(&texture_view{})", r.inner.id),
+        Some(r) => format!("Some(&texture_view{})", r.debug_id()),
         None => "None".to_string()
     })]
     pub resolve_target: Option<&'tex TextureView>,
@@ -370,7 +1174,7 @@ pub struct RenderPassColorAttachment<'tex> {
 #[derive(Clone, Debug)]
 pub struct RenderPassDepthStencilAttachment<'tex> {
     /// The view to use as an attachment.
-    #[debug("&texture_view{:?}", view.inner.id)]
+    #[debug("&texture_view{:?}", view.debug_id())]
     pub view: &'tex TextureView,
     /// What operations will be performed on the depth part of the attachment.
     pub depth_ops: Option<Operations<f32>>,
@@ -0,0 +1,48 @@
+use derive_more::derive::Debug;
+
+use super::super::{hal, Label, PipelineLayout, ShaderModule};
+
+/// Handle to a compute pipeline.
+///
+/// A `ComputePipeline` object represents a compute pipeline and its single shader stage.
+/// It can be created with [`Device::create_compute_pipeline`].
+///
+/// Corresponds to [WebGPU `GPUComputePipeline`](https://gpuweb.github.io/gpuweb/#compute-pipeline).
+#[derive(Debug)]
+pub struct ComputePipeline {
+    // 创建失败 时 为 None，设置到 compute 通道 后 dispatch 会被 跳过
+    pub(crate) inner: Option<hal::ComputePipeline>,
+}
+
+impl ComputePipeline {
+    #[inline]
+    pub(crate) fn from_hal(inner: Option<hal::ComputePipeline>) -> Self {
+        Self { inner }
+    }
+
+    #[inline]
+    pub(crate) fn debug_id(&self) -> u64 {
+        self.inner.as_ref().map_or(0, |p| p.0.id as u64)
+    }
+}
+
+/// Describes a compute pipeline.
+///
+/// For use with [`Device::create_compute_pipeline`].
+///
+/// Corresponds to [WebGPU `GPUComputePipelineDescriptor`](
+/// https://gpuweb.github.io/gpuweb/#dictdef-gpucomputepipelinedescriptor).
+#[derive(Clone, Debug)]
+pub struct ComputePipelineDescriptor<'a> {
+    /// Debug label of the pipeline. This will show up in graphics debuggers for easy identification.
+    pub label: Label<'a>,
+    /// The layout of bind groups for this pipeline.
+    #[debug("{}", match layout {Some(r) => format!("Some(&pipeline_layout{:?})", r.debug_id()), None => "None".to_string()})]
+    pub layout: Option<&'a PipelineLayout>,
+    /// The compiled shader module for this stage.
+    #[debug("&shader_module{:?}", module.debug_id())]
+    pub module: &'a ShaderModule,
+    /// The name of the entry point in the compiled shader. There must be a function with this name
+    /// and no return value in the shader.
+    pub entry_point: &'a str,
+}
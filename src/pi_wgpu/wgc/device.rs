@@ -1,11 +1,19 @@
+use std::future::{ready, Future};
+
+use parking_lot::Mutex;
+use pi_share::Share;
 use thiserror::Error;
 
 use super::super::{
-    wgt, BindGroup, BindGroupLayout, BindGroupLayoutDescriptor, Buffer, BufferUsages,
-    CommandEncoder, Label, PipelineLayout, PipelineLayoutDescriptor, RenderPipeline,
+    hal, wgt, BindGroup, BindGroupLayout, BindGroupLayoutDescriptor, BindingResource, Buffer, BufferUsages,
+    CommandEncoder, ComputePipeline, ComputePipelineDescriptor, Label, PipelineLayout, PipelineLayoutDescriptor, QuerySet, QuerySetDescriptor, RenderBundleEncoder,
+    RenderBundleEncoderDescriptor, RenderPipeline,
     RenderPipelineDescriptor, Sampler, SamplerDescriptor, ShaderModule, ShaderModuleDescriptor,
     SubmissionIndex, Texture, TextureUsages,
 };
+use super::{ErrorFilter, ErrorSink, InvalidObject, UncapturedErrorHandler};
+#[cfg(feature = "spirv")]
+use super::super::{ShaderModuleDescriptorSpirV, ShaderSource};
 
 /// Open connection to a graphics and/or compute device.
 ///
@@ -18,6 +26,9 @@ use super::super::{
 #[derive(Debug)]
 pub struct Device {
     pub(crate) inner: super::super::hal::Device,
+
+    // Texture 也要 持有，create_view 的 错误 交给 它；用 Mutex 保证 Device 还是 Send
+    pub(crate) error_sink: Share<Mutex<ErrorSink>>,
 }
 
 impl Device {
@@ -42,15 +53,72 @@ impl Device {
         self.inner.reset_state();
     }
 
+    /// Set a callback for errors that are not handled in error scopes.
+    pub fn on_uncaptured_error(&self, handler: Box<dyn UncapturedErrorHandler>) {
+        self.error_sink
+            .lock()
+            .set_uncaptured_handler(handler);
+    }
+
+    /// Push an error scope.
+    pub fn push_error_scope(&self, filter: ErrorFilter) {
+        log::trace!("device.push_error_scope(ErrorFilter::{:?});", filter);
+
+        self.error_sink.lock().push_scope(filter);
+    }
+
+    /// Pop an error scope.
+    pub fn pop_error_scope(&self) -> impl Future<Output = Option<super::Error>> + Send {
+        log::trace!("device.pop_error_scope();");
+
+        let r = self.error_sink.lock().pop_scope();
+        ready(r)
+    }
+
+    #[inline]
+    pub(crate) fn handle_error(&self, err: super::Error) {
+        super::handle_error(&self.error_sink, err);
+    }
+
     /// Creates a shader module from either SPIR-V or WGSL source code.
     #[inline]
     pub fn create_shader_module(&self, desc: ShaderModuleDescriptor) -> ShaderModule {
-        let r = self.inner.create_shader_module(&desc);
-        let r = r.unwrap();
-		log::trace!("let shader_module{:?} = device.create_shader_module({:?});", r.id, &desc);
+        let r = match self.inner.create_shader_module(&desc) {
+            Ok(r) => {
+                log::trace!("let shader_module{:?} = device.create_shader_module({:?});", r.id, &desc);
+                Some(r)
+            }
+            Err(e) => {
+                let description = format!("In Device::create_shader_module, label = {:?}: {}", desc.label, e);
+                self.handle_error(match e {
+                    hal::ShaderError::Device(e) => super::Error::from_device(description, e),
+                    e => super::Error::validation(description, e),
+                });
+                None
+            }
+        };
+
         ShaderModule::from_hal(r)
     }
 
+    /// Creates a shader module from SPIR-V binary directly.
+    ///
+    /// # Safety
+    ///
+    /// Kept `unsafe` for compatibility with wgpu. On this backend the binary is
+    /// still parsed and validated by naga before being translated to GLSL.
+    #[cfg(feature = "spirv")]
+    #[inline]
+    pub unsafe fn create_shader_module_spirv(
+        &self,
+        desc: &ShaderModuleDescriptorSpirV,
+    ) -> ShaderModule {
+        self.create_shader_module(ShaderModuleDescriptor {
+            label: desc.label,
+            source: ShaderSource::SpirV(desc.source.clone()),
+        })
+    }
+
     /// Creates an empty [`CommandEncoder`].
     #[inline]
     pub fn create_command_encoder(
@@ -61,56 +129,234 @@ impl Device {
         let mut command_encoder = device.create_command_encoder(&{:?});", desc);
 
         let r = self.inner.create_command_encoder(&desc);
-        let r = r.unwrap();
         CommandEncoder::from_hal(r)
     }
 
+    /// Creates a new [`QuerySet`].
+    pub fn create_query_set(&self, desc: &QuerySetDescriptor) -> QuerySet {
+        log::trace!("let query_set = device.create_query_set(&{:?});", desc);
+
+        if desc.ty == wgt::QueryType::Timestamp
+            && !self.inner.features.contains(Features::TIMESTAMP_QUERY)
+        {
+            self.handle_error(super::Error::validation(
+                format!("In Device::create_query_set, label = {:?}", desc.label),
+                MissingFeatures(Features::TIMESTAMP_QUERY),
+            ));
+            return QuerySet::from_hal(None, desc);
+        }
+
+        let r = match self.inner.create_query_set(desc) {
+            Ok(r) => Some(r),
+            Err(e) => {
+                let description = format!("In Device::create_query_set, label = {:?}: {}", desc.label, e);
+                self.handle_error(match e {
+                    DeviceError::UnsupportedFeature(features) => {
+                        super::Error::validation(description, MissingFeatures(features))
+                    }
+                    e => super::Error::from_device(description, e),
+                });
+                None
+            }
+        };
+
+        QuerySet::from_hal(r, desc)
+    }
+
+    /// Creates an empty [`RenderBundleEncoder`].
+    pub fn create_render_bundle_encoder(
+        &self,
+        desc: &RenderBundleEncoderDescriptor,
+    ) -> RenderBundleEncoder {
+        log::trace!("let mut render_bundle_encoder = device.create_render_bundle_encoder(&{:?});", desc);
+
+        let mut r = self
+            .inner
+            .create_command_encoder(&super::super::CommandEncoderDescriptor { label: desc.label });
+        // bundle 不 继承 通道 的 管线，set_pipeline 之前 的 绘制 不录制
+        r.set_invalid_render_pipeline();
+        RenderBundleEncoder {
+            inner: r,
+            _p: std::marker::PhantomData,
+        }
+    }
+
     /// Creates a [`BindGroupLayout`].
     #[inline]
     pub fn create_bind_group_layout(&self, desc: &BindGroupLayoutDescriptor) -> BindGroupLayout {
-        let r = self.inner.create_bind_group_layout(&desc);
-        let r = r.unwrap();
-		log::trace!("let bind_group_layout{} = device.create_bind_group_layout(&{:?});", r.id, &desc);
+        let r = match self.inner.create_bind_group_layout(&desc) {
+            Ok(r) => {
+                log::trace!("let bind_group_layout{} = device.create_bind_group_layout(&{:?});", r.id, &desc);
+                Some(r)
+            }
+            Err(e) => {
+                self.handle_error(super::Error::from_device(
+                    format!("In Device::create_bind_group_layout, label = {:?}: {}", desc.label, e),
+                    e,
+                ));
+                None
+            }
+        };
+
         BindGroupLayout::from_hal(r)
     }
 
     /// Creates a new [`BindGroup`].
     #[inline]
     pub fn create_bind_group(&self, desc: &super::super::BindGroupDescriptor) -> BindGroup {
-        let r = self.inner.create_bind_group(&desc);
-        let r = r.unwrap();
-		log::trace!("let bind_group{} = device.create_bind_group(&BindGroupDescriptor {{
+        let invalid = if desc.layout.inner.is_none() {
+            Some("BindGroupLayout")
+        } else {
+            desc.entries.iter().find_map(|e| invalid_binding_resource(&e.resource))
+        };
+        if let Some(name) = invalid {
+            self.handle_error(super::Error::validation(
+                format!("In Device::create_bind_group, label = {:?}: {} is invalid", desc.label, name),
+                InvalidObject(name),
+            ));
+            return BindGroup::from_hal(None);
+        }
+
+        let r = match self.inner.create_bind_group(&desc) {
+            Ok(r) => {
+                log::trace!("let bind_group{} = device.create_bind_group(&BindGroupDescriptor {{
 			label: {:?},
 			layout: &bind_group_layout{:?},
 			entries: &{:?},
-		}});", r.id, &desc.label, desc.layout.inner.id, &desc.entries);
+		}});", r.id, &desc.label, desc.layout.debug_id(), &desc.entries);
+                Some(r)
+            }
+            Err(e) => {
+                self.handle_error(super::Error::from_device(
+                    format!("In Device::create_bind_group, label = {:?}: {}", desc.label, e),
+                    e,
+                ));
+                None
+            }
+        };
+
         BindGroup::from_hal(r)
     }
 
     /// Creates a [`PipelineLayout`].
     #[inline]
     pub fn create_pipeline_layout(&self, desc: &PipelineLayoutDescriptor) -> PipelineLayout {
-        let r = self.inner.create_pipeline_layout(&desc);
-        let r = r.unwrap();
-		// log::trace!("let pipeline_layout{} = device.create_bind_group(&BindGroupDescriptor {{
-		// 	label: {:?},
-		// 	bind_group_layouts: &bind_group_layout{:?},
-		// 	push_constant_ranges: &{:?},
-		// }});", r.id, &desc.label, desc.bind_group_layouts.inner.id, &desc.entries);
-
-		log::trace!("let pipeline_layout{} = device.create_pipeline_layout(&{:?});", r.id, desc);
+        if desc.bind_group_layouts.iter().any(|l| l.inner.is_none()) {
+            self.handle_error(super::Error::validation(
+                format!("In Device::create_pipeline_layout, label = {:?}: bind group layout is invalid", desc.label),
+                InvalidObject("BindGroupLayout"),
+            ));
+            return PipelineLayout::from_hal(None);
+        }
+
+        let r = match self.inner.create_pipeline_layout(&desc) {
+            Ok(r) => {
+                log::trace!("let pipeline_layout{} = device.create_pipeline_layout(&{:?});", r.id, desc);
+                Some(r)
+            }
+            Err(e) => {
+                self.handle_error(super::Error::from_device(
+                    format!("In Device::create_pipeline_layout, label = {:?}: {}", desc.label, e),
+                    e,
+                ));
+                None
+            }
+        };
+
         PipelineLayout::from_hal(r)
     }
 
     /// Creates a [`RenderPipeline`].
     #[inline]
     pub fn create_render_pipeline(&self, desc: &RenderPipelineDescriptor) -> RenderPipeline {
-        let r = self.inner.create_render_pipeline(&desc);
-        let r = r.unwrap();
-		log::trace!("let render_pipeline{} = device.create_render_pipeline(&{:?});", r.0.id, desc);
+        let is_module_valid = desc.vertex.module.inner.is_some()
+            && desc
+                .fragment
+                .as_ref()
+                .map_or(true, |f| f.module.inner.is_some());
+
+        if !is_module_valid {
+            self.handle_error(super::Error::validation(
+                format!("In Device::create_render_pipeline, label = {:?}: shader module is invalid", desc.label),
+                InvalidObject("ShaderModule"),
+            ));
+            return RenderPipeline::from_hal(None);
+        }
+        if desc.layout.map_or(false, |l| l.inner.is_none()) {
+            self.handle_error(super::Error::validation(
+                format!("In Device::create_render_pipeline, label = {:?}: pipeline layout is invalid", desc.label),
+                InvalidObject("PipelineLayout"),
+            ));
+            return RenderPipeline::from_hal(None);
+        }
+
+        let r = match self.inner.create_render_pipeline(&desc) {
+            Ok(r) => {
+                log::trace!("let render_pipeline{} = device.create_render_pipeline(&{:?});", r.0.id, desc);
+                Some(r)
+            }
+            Err(e) => {
+                let description = format!("In Device::create_render_pipeline, label = {:?}: {}", desc.label, e);
+                self.handle_error(match e {
+                    hal::PipelineError::Device(e) => super::Error::from_device(description, e),
+                    e => super::Error::validation(description, e),
+                });
+                None
+            }
+        };
+
         RenderPipeline::from_hal(r)
     }
 
+    /// Creates a [`ComputePipeline`].
+    #[inline]
+    pub fn create_compute_pipeline(&self, desc: &ComputePipelineDescriptor) -> ComputePipeline {
+        if desc.module.inner.is_none() {
+            self.handle_error(super::Error::validation(
+                format!("In Device::create_compute_pipeline, label = {:?}: shader module is invalid", desc.label),
+                InvalidObject("ShaderModule"),
+            ));
+            return ComputePipeline::from_hal(None);
+        }
+        if desc.layout.map_or(false, |l| l.inner.is_none()) {
+            self.handle_error(super::Error::validation(
+                format!("In Device::create_compute_pipeline, label = {:?}: pipeline layout is invalid", desc.label),
+                InvalidObject("PipelineLayout"),
+            ));
+            return ComputePipeline::from_hal(None);
+        }
+
+        if !self
+            .inner
+            .downlevel
+            .flags
+            .contains(wgt::DownlevelFlags::COMPUTE_SHADERS)
+        {
+            self.handle_error(super::Error::validation(
+                format!("In Device::create_compute_pipeline, label = {:?}", desc.label),
+                MissingDownlevelFlags(wgt::DownlevelFlags::COMPUTE_SHADERS),
+            ));
+            return ComputePipeline::from_hal(None);
+        }
+
+        let r = match self.inner.create_compute_pipeline(&desc) {
+            Ok(r) => {
+                log::trace!("let compute_pipeline{} = device.create_compute_pipeline(&{:?});", r.0.id, desc);
+                Some(r)
+            }
+            Err(e) => {
+                let description = format!("In Device::create_compute_pipeline, label = {:?}: {}", desc.label, e);
+                self.handle_error(match e {
+                    hal::PipelineError::Device(e) => super::Error::from_device(description, e),
+                    e => super::Error::validation(description, e),
+                });
+                None
+            }
+        };
+
+        ComputePipeline::from_hal(r)
+    }
+
 	/// Creates a [`Buffer`].
     #[inline]
     pub fn create_buffer(&self, desc: &super::super::BufferDescriptor) -> Buffer {
@@ -118,7 +364,7 @@ impl Device {
 		#[cfg(not(target_arch = "wasm32"))]
 		log::trace!(
 			"let buffer{:?} = device.create_buffer(&{:?});",
-			buffer.inner.0.raw.0,
+			buffer.debug_id(),
 			desc,
 		);
 		buffer
@@ -150,16 +396,43 @@ impl Device {
 
                 let count = has_uniform + has_vertex + has_index;
 
-                count == 1
+                // 只用于 映射 / 拷贝 的 staging buffer，间接绘制 的 参数，或者 SSBO
+                let is_staging = usage.intersects(
+                    BufferUsages::MAP_READ
+                        | BufferUsages::MAP_WRITE
+                        | BufferUsages::COPY_SRC
+                        | BufferUsages::COPY_DST
+                        | BufferUsages::INDIRECT
+                        | BufferUsages::STORAGE,
+                );
+
+                count == 1 || (count == 0 && is_staging)
             }
 
             debug_assert!(is_usage_valid(&desc.usage));
-            debug_assert!(!desc.usage.contains(BufferUsages::STORAGE));
-            debug_assert!(!desc.usage.contains(BufferUsages::INDIRECT));
+            // SSBO 要 GLES 3.1 / GL 4.3，WebGL2 不支持
+            debug_assert!(
+                !desc.usage.contains(BufferUsages::STORAGE)
+                    || self.inner.limits.max_storage_buffer_binding_size > 0
+            );
         }
 
-        let r = self.inner.create_buffer(&desc);
-        let r = r.unwrap();
+        let r = match self.inner.create_buffer(&desc) {
+            Ok(r) => {
+                if desc.mapped_at_creation {
+                    r.map_at_creation();
+                }
+                Some(r)
+            }
+            Err(e) => {
+                self.handle_error(super::Error::from_device(
+                    format!("In Device::create_buffer, label = {:?}: {}", desc.label, e),
+                    e,
+                ));
+                None
+            }
+        };
+
         Buffer::from_hal(r, desc.usage, desc.size)
     }
 
@@ -169,7 +442,7 @@ impl Device {
     // #[inline]
     pub fn create_texture(&self, desc: &super::super::TextureDescriptor) -> Texture {
         let texture = self.create_texture_inner(desc);
-		log::trace!("let texture{} = device.create_texture(&{:?});", texture.inner.0.inner.debug_str(), desc);
+		log::trace!("let texture{} = device.create_texture(&{:?});", texture.debug_str(), desc);
 		texture
     }
 
@@ -177,12 +450,25 @@ impl Device {
     pub(crate) fn create_texture_inner(&self, desc: &super::super::TextureDescriptor) -> Texture {
         #[cfg(debug_assertions)]
         {
-            debug_assert!(!desc.usage.contains(TextureUsages::STORAGE_BINDING));
+            // image load store 要 GLES 3.1 / GL 4.3，WebGL2 不支持
+            debug_assert!(
+                !desc.usage.contains(TextureUsages::STORAGE_BINDING)
+                    || self.inner.limits.max_storage_textures_per_shader_stage > 0
+            );
         }
 
-        let r = self.inner.create_texture(&desc);
-        let r = r.unwrap();
-        Texture::from_hal(r, desc)
+        let r = match self.inner.create_texture(&desc) {
+            Ok(r) => Some(r),
+            Err(e) => {
+                self.handle_error(super::Error::from_device(
+                    format!("In Device::create_texture, label = {:?}: {}", desc.label, e),
+                    e,
+                ));
+                None
+            }
+        };
+
+        Texture::from_hal(r, desc, self.error_sink.clone())
     }
 
     // 从窗口表面创建
@@ -212,7 +498,7 @@ impl Device {
             .inner
             .create_texture_from_surface(width, height, format);
 
-        Texture::from_hal(r, &desc)
+        Texture::from_hal(Some(r), &desc, self.error_sink.clone())
     }
 
     /// Creates a new [`Sampler`].
@@ -220,10 +506,21 @@ impl Device {
     /// `desc` specifies the behavior of the sampler.
     #[inline]
     pub fn create_sampler(&self, desc: &SamplerDescriptor) -> Sampler {
-        let r = self.inner.create_sampler(&desc);
-        let r = r.unwrap();
-		#[cfg(not(target_arch = "wasm32"))]
-		log::trace!("let sampler{} = device.create_sampler(&{:?});", r.0.raw.0.get(), desc);
+        let r = match self.inner.create_sampler(&desc) {
+            Ok(r) => {
+                #[cfg(not(target_arch = "wasm32"))]
+                log::trace!("let sampler{} = device.create_sampler(&{:?});", r.0.raw.0.get(), desc);
+                Some(r)
+            }
+            Err(e) => {
+                self.handle_error(super::Error::from_device(
+                    format!("In Device::create_sampler, label = {:?}: {}", desc.label, e),
+                    e,
+                ));
+                None
+            }
+        };
+
         Sampler::from_hal(r)
     }
     pub fn unmake_current(&self){
@@ -236,6 +533,26 @@ impl Device {
     }
 }
 
+// 绑定 的 资源 里 有 创建失败 的，返回 它的 类型名
+fn invalid_binding_resource(resource: &BindingResource) -> Option<&'static str> {
+    match resource {
+        BindingResource::Buffer(b) => b.buffer.inner.is_none().then_some("Buffer"),
+        BindingResource::BufferArray(bs) => bs
+            .iter()
+            .any(|b| b.buffer.inner.is_none())
+            .then_some("Buffer"),
+        BindingResource::Sampler(s) => s.inner.is_none().then_some("Sampler"),
+        BindingResource::SamplerArray(ss) => {
+            ss.iter().any(|s| s.inner.is_none()).then_some("Sampler")
+        }
+        BindingResource::TextureView(v) => v.inner.is_none().then_some("TextureView"),
+        BindingResource::TextureViewArray(vs) => vs
+            .iter()
+            .any(|v| v.inner.is_none())
+            .then_some("TextureView"),
+    }
+}
+
 /// Describes a [`Device`].
 ///
 /// For use with [`Adapter::request_device`].
@@ -0,0 +1,212 @@
+use std::{error, fmt};
+
+use parking_lot::Mutex;
+
+use super::super::DeviceError;
+
+/// Filter for error scopes.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, PartialOrd)]
+pub enum ErrorFilter {
+    /// Catch only out-of-memory errors.
+    OutOfMemory,
+    /// Catch only validation errors.
+    Validation,
+    /// Catch only internal errors.
+    Internal,
+}
+
+/// Error type
+#[derive(Debug)]
+pub enum Error {
+    /// Out of memory error
+    OutOfMemory {
+        /// Lower level source of the error.
+        source: Box<dyn error::Error + Send + 'static>,
+    },
+    /// Validation error, signifying a bug in code or data
+    Validation {
+        /// Lower level source of the error.
+        source: Box<dyn error::Error + Send + 'static>,
+        /// Description of the validation error.
+        description: String,
+    },
+    /// Internal error. Used for signalling any failures not explicitly expected by WebGPU.
+    ///
+    /// These could be due to internal implementation or system limits being reached.
+    Internal {
+        /// Lower level source of the error.
+        source: Box<dyn error::Error + Send + 'static>,
+        /// Description of the internal GPU error.
+        description: String,
+    },
+}
+
+impl Error {
+    #[inline]
+    pub(crate) fn filter(&self) -> ErrorFilter {
+        match self {
+            Error::OutOfMemory { .. } => ErrorFilter::OutOfMemory,
+            Error::Validation { .. } => ErrorFilter::Validation,
+            Error::Internal { .. } => ErrorFilter::Internal,
+        }
+    }
+
+    // 校验失败：shader 解析 / 编译 / 链接 错误，用了 无效对象 等
+    pub(crate) fn validation<E: error::Error + Send + 'static>(
+        description: String,
+        source: E,
+    ) -> Self {
+        Error::Validation {
+            source: Box::new(source),
+            description,
+        }
+    }
+
+    // GL 不会报告 设备丢失，DeviceError 只区分 内存不足 和 其他
+    pub(crate) fn from_device(description: String, source: DeviceError) -> Self {
+        match source {
+            DeviceError::OutOfMemory => Error::OutOfMemory {
+                source: Box::new(source),
+            },
+            _ => Error::Internal {
+                source: Box::new(source),
+                description,
+            },
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::OutOfMemory { .. } => f.write_str("Out of Memory"),
+            Error::Validation { description, .. } => f.write_str(description),
+            Error::Internal { description, .. } => f.write_str(description),
+        }
+    }
+}
+
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Error::OutOfMemory { source } => Some(source.as_ref()),
+            Error::Validation { source, .. } => Some(source.as_ref()),
+            Error::Internal { source, .. } => Some(source.as_ref()),
+        }
+    }
+}
+
+// 用了 创建失败 的 对象
+#[derive(Clone, Debug, thiserror::Error)]
+#[error("{0} is invalid")]
+pub(crate) struct InvalidObject(pub(crate) &'static str);
+
+/// Type for the callback of uncaptured error handler
+pub trait UncapturedErrorHandler: Fn(Error) + Send + 'static {}
+impl<T> UncapturedErrorHandler for T where T: Fn(Error) + Send + 'static {}
+
+/// 错误 的 去处：最内层 匹配的 错误域，没有 就交给 on_uncaptured_error 的 回调
+pub(crate) struct ErrorSink {
+    scopes: Vec<ErrorScope>,
+    uncaptured_handler: Option<Box<dyn UncapturedErrorHandler>>,
+}
+
+struct ErrorScope {
+    error: Option<Error>,
+    filter: ErrorFilter,
+}
+
+impl fmt::Debug for ErrorSink {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ErrorSink")
+            .field("scopes", &self.scopes.len())
+            .finish()
+    }
+}
+
+impl Default for ErrorSink {
+    fn default() -> Self {
+        Self {
+            scopes: vec![],
+            uncaptured_handler: Some(Box::new(default_error_handler)),
+        }
+    }
+}
+
+impl ErrorSink {
+    #[inline]
+    pub(crate) fn push_scope(&mut self, filter: ErrorFilter) {
+        self.scopes.push(ErrorScope {
+            error: None,
+            filter,
+        });
+    }
+
+    #[inline]
+    pub(crate) fn pop_scope(&mut self) -> Option<Error> {
+        let scope = self
+            .scopes
+            .pop()
+            .expect("Mismatched pop_error_scope call: no error scope for this thread");
+
+        scope.error
+    }
+
+    #[inline]
+    pub(crate) fn set_uncaptured_handler(&mut self, handler: Box<dyn UncapturedErrorHandler>) {
+        self.uncaptured_handler = Some(handler);
+    }
+
+    // 返回 Err 表示 没有 错误域 接收，调用者 要 交给 回调
+    // 回调 可能 再 调用 Device 的 函数，所以 不能 在 借用 期间 调用
+    pub(crate) fn capture(&mut self, err: Error) -> Result<(), Error> {
+        let filter = err.filter();
+
+        match self.scopes.iter_mut().rev().find(|s| s.filter == filter) {
+            Some(scope) => {
+                // 只 保留 第一个 错误
+                if scope.error.is_none() {
+                    scope.error = Some(err);
+                }
+                Ok(())
+            }
+            None => Err(err),
+        }
+    }
+
+    #[inline]
+    pub(crate) fn take_uncaptured_handler(&mut self) -> Option<Box<dyn UncapturedErrorHandler>> {
+        self.uncaptured_handler.take()
+    }
+
+    // 回调 执行 期间 没有 设置 新回调，才 放回去
+    #[inline]
+    pub(crate) fn restore_uncaptured_handler(&mut self, handler: Box<dyn UncapturedErrorHandler>) {
+        if self.uncaptured_handler.is_none() {
+            self.uncaptured_handler = Some(handler);
+        }
+    }
+}
+
+// 交给 最内层 匹配的 错误域，没有 就 调用 on_uncaptured_error 的 回调
+pub(crate) fn handle_error(sink: &Mutex<ErrorSink>, err: Error) {
+    let r = sink.lock().capture(err);
+
+    if let Err(err) = r {
+        let handler = sink.lock().take_uncaptured_handler();
+        match handler {
+            Some(handler) => {
+                handler(err);
+
+                sink.lock().restore_uncaptured_handler(handler);
+            }
+            // 回调 里 又 产生了 错误
+            None => log::error!("uncaptured error in error handler: {}", err),
+        }
+    }
+}
+
+fn default_error_handler(err: Error) {
+    log::error!("Handling wgpu errors as fatal by default");
+    panic!("wgpu error: {err}\n");
+}
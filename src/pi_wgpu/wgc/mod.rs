@@ -7,6 +7,8 @@ mod adapter;
 
 mod device;
 
+mod error;
+
 mod queue;
 
 mod command;
@@ -24,9 +26,12 @@ mod bind_group_layout;
 mod pipeline_layout;
 
 mod render_pipeline;
+mod render_bundle;
+mod compute_pipeline;
 
 pub use adapter::*;
 pub use device::*;
+pub use error::*;
 pub use instance::*;
 pub use surface::*;
 
@@ -44,4 +49,6 @@ pub use bind_group_layout::*;
 pub use pipeline_layout::*;
 
 pub use render_pipeline::*;
+pub use render_bundle::*;
+pub use compute_pipeline::*;
 
@@ -9,14 +9,20 @@ use derive_more::Debug;
 /// Corresponds to [WebGPU `GPUPipelineLayout`](https://gpuweb.github.io/gpuweb/#gpupipelinelayout).
 #[derive(Debug)]
 pub struct PipelineLayout {
-    pub(crate) inner: super::super::hal::PipelineLayout,
+    // 创建失败 时 为 None，用它 创建的 管线 也是 无效的
+    pub(crate) inner: Option<super::super::hal::PipelineLayout>,
 }
 
 impl PipelineLayout {
     #[inline]
-    pub(crate) fn from_hal(inner: super::super::hal::PipelineLayout) -> Self {
+    pub(crate) fn from_hal(inner: Option<super::super::hal::PipelineLayout>) -> Self {
         Self { inner }
     }
+
+    #[inline]
+    pub(crate) fn debug_id(&self) -> u64 {
+        self.inner.as_ref().map_or(0, |l| l.id as u64)
+    }
 }
 
 /// Describes a [`PipelineLayout`].
@@ -31,7 +37,7 @@ pub struct PipelineLayoutDescriptor<'a> {
     pub label: Label<'a>,
     /// Bind groups that this pipeline uses. The first entry will provide all the bindings for
     /// "set = 0", second entry will provide all the bindings for "set = 1" etc.
-    #[debug("&[{}]", bind_group_layouts.iter().map(|r| {format!("&bind_group_layout{:?}, lable: {:?}", r.inner.id, r.inner.lable)}).collect::<Vec<String>>().join(", "))]  
+    #[debug("&[{}]", bind_group_layouts.iter().map(|r| {format!("&bind_group_layout{:?}, lable: {:?}", r.debug_id(), r.inner.as_ref().and_then(|l| l.lable.as_ref()))}).collect::<Vec<String>>().join(", "))]  
     pub bind_group_layouts: &'a [&'a BindGroupLayout],
     /// Set of push constant ranges this pipeline uses. Each shader stage that uses push constants
     /// must define the range in push constant memory that corresponds to its single `layout(push_constant)`
@@ -25,7 +25,7 @@ impl Queue {
         #[cfg(not(target_arch = "wasm32"))]
         log::trace!(
             "queue.write_buffer(&buffer{}, {}, &{:?});",
-            buffer.inner.0.raw.0.get(),
+            buffer.debug_id(),
             offset,
             data
         );
@@ -34,9 +34,14 @@ impl Queue {
 
     #[inline]
     pub(crate) fn write_buffer_inner(&self, buffer: &Buffer, offset: BufferAddress, data: &[u8]) {
+        let Some(inner) = buffer.inner.as_ref() else {
+            log::error!("write_buffer: buffer is invalid, skipped");
+            return;
+        };
+
         let lock = self.inner.adapter.lock(None);
         let gl = lock.get_glow();
-        buffer.inner.write_buffer(&gl, offset as i32, data);
+        inner.write_buffer(&gl, offset as i32, data);
     }
 
     /// Schedule a write of some data into a texture.
@@ -69,7 +74,7 @@ impl Queue {
             mip_level: {:?},
             origin: {:?},
             aspect: {:?},
-        }}, &{:?}, {:?}, {:?});", texture.texture.inner.0.inner.debug_str(), &texture.mip_level, texture.origin, texture.aspect, data, data_layout, size);
+        }}, &{:?}, {:?}, {:?});", texture.texture.debug_str(), &texture.mip_level, texture.origin, texture.aspect, data, data_layout, size);
 
         self.write_texture_inner(texture, data, data_layout, size);
     }
@@ -90,6 +95,11 @@ impl Queue {
             size
         );
 
+        if texture.texture.inner.is_none() {
+            log::error!("write_texture_jsbuffer: texture is invalid, skipped");
+            return;
+        }
+
         hal::Texture::write_compress_jsdata(&self.inner.state, texture, data, data_layout, size);
     }
 
@@ -108,6 +118,11 @@ impl Queue {
         //     size
         // );
 
+        if texture.texture.inner.is_none() {
+            log::error!("write_texture: texture is invalid, skipped");
+            return;
+        }
+
         hal::Texture::write_data(&self.inner.state, texture, data, data_layout, size);
     }
 
@@ -118,9 +133,21 @@ impl Queue {
         dest: super::super::ImageCopyTextureTagged,
         size: Extent3d,
     ) {
+        if dest.texture.inner.is_none() {
+            log::error!("copy_external_image_to_texture: texture is invalid, skipped");
+            return;
+        }
+
         hal::Texture::write_external_image(&self.inner.state, source, dest.to_untagged(), size, dest.premultiplied_alpha);
     }
 
+    /// Gets the amount of nanoseconds each tick of a timestamp query represents.
+    ///
+    /// GL timestamps are already in nanoseconds, so this is always `1.0`.
+    pub fn get_timestamp_period(&self) -> f32 {
+        1.0
+    }
+
     /// Submits a series of finished command buffers for execution.
     #[inline]
     pub fn submit<I: IntoIterator<Item = CommandBuffer>>(
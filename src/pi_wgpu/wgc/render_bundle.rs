@@ -0,0 +1,304 @@
+use std::{marker::PhantomData, num::NonZeroU32, ops::Range};
+
+use super::super::{
+    hal, wgt, BindGroup, Buffer, BufferAddress, BufferSlice, BufferUsages, DynamicOffset,
+    IndexFormat, Label, RenderBundleDepthStencil, RenderPipeline, ShaderStages, TextureFormat,
+};
+use derive_more::Debug;
+
+/// Pre-prepared reusable bundle of GPU operations.
+///
+/// It only supports a handful of render commands, but it makes them reusable. Executing a
+/// [`RenderBundle`] is often more efficient than issuing the underlying commands manually.
+///
+/// It can be created by use of a [`RenderBundleEncoder`], and executed onto a [`CommandEncoder`]
+/// using [`RenderPass::execute_bundles`].
+///
+/// Corresponds to [WebGPU `GPURenderBundle`](https://gpuweb.github.io/gpuweb/#render-bundle).
+#[derive(Debug)]
+pub struct RenderBundle {
+    pub(crate) inner: hal::RenderBundle,
+}
+
+/// Encodes a series of GPU operations into a reusable "render bundle".
+///
+/// It only supports a handful of render commands, but it makes them reusable.
+/// It can be created with [`Device::create_render_bundle_encoder`].
+/// It can be executed onto a [`CommandEncoder`] using [`RenderPass::execute_bundles`].
+///
+/// Executing a [`RenderBundle`] is often more efficient than issuing the underlying commands
+/// manually.
+///
+/// Corresponds to [WebGPU `GPURenderBundleEncoder`](
+/// https://gpuweb.github.io/gpuweb/#gpurenderbundleencoder).
+#[derive(Debug)]
+pub struct RenderBundleEncoder<'a> {
+    // 和 CommandEncoder 用 同一套 录制，finish 时 去掉 冗余状态
+    pub(crate) inner: hal::CommandEncoder,
+
+    pub(crate) _p: PhantomData<&'a ()>,
+}
+
+impl<'a> RenderBundleEncoder<'a> {
+    /// Finishes recording and returns a [`RenderBundle`] that can be executed in other render passes.
+    pub fn finish(mut self, desc: &RenderBundleDescriptor) -> RenderBundle {
+        log::trace!("let render_bundle = render_bundle_encoder.finish(&{:?});", desc);
+
+        RenderBundle {
+            inner: self.inner.finish_bundle(),
+        }
+    }
+
+    /// Sets the active bind group for a given bind group index. The bind group layout
+    /// in the active pipeline when any `draw()` function is called must match the layout of this bind group.
+    ///
+    /// If the bind group have dynamic offsets, provide them in the binding order.
+    pub fn set_bind_group(
+        &mut self,
+        index: u32,
+        bind_group: &'a BindGroup,
+        offsets: &[DynamicOffset],
+    ) {
+        log::trace!(
+            "render_bundle_encoder.set_bind_group({:?}, &bind_group{:?}, &{:?});",
+            index,
+            bind_group.debug_id(),
+            offsets
+        );
+
+        match bind_group.inner.as_ref() {
+            Some(inner) => self.inner.set_bind_group(index, inner, offsets),
+            None => {
+                log::error!("render_bundle_encoder.set_bind_group: bind group {} is invalid, draws are skipped until a valid one is set", index);
+                self.inner.set_invalid_bind_group(index);
+            }
+        }
+    }
+
+    /// Sets the active render pipeline.
+    ///
+    /// Subsequent draw calls will exhibit the behavior defined by `pipeline`.
+    pub fn set_pipeline(&mut self, pipeline: &'a RenderPipeline) {
+        log::trace!(
+            "render_bundle_encoder.set_pipeline(&render_pipeline{:?});",
+            pipeline.debug_id()
+        );
+
+        match pipeline.inner.as_ref() {
+            Some(inner) => self.inner.set_render_pipeline(inner),
+            None => {
+                log::error!("render_bundle_encoder.set_pipeline: pipeline is invalid, draws are skipped until a valid pipeline is set");
+                self.inner.set_invalid_render_pipeline();
+            }
+        }
+    }
+
+    /// Sets the active index buffer.
+    ///
+    /// Subsequent calls to [`draw_indexed`](RenderBundleEncoder::draw_indexed) on this [`RenderBundleEncoder`] will
+    /// use `buffer` as the source index buffer.
+    pub fn set_index_buffer(&mut self, buffer_slice: BufferSlice<'a>, index_format: IndexFormat) {
+        #[cfg(not(target_arch = "wasm32"))]
+        match buffer_slice.size {
+            Some(r) => log::trace!(
+                "render_bundle_encoder.set_index_buffer(buffer{}.slice({}..{}), IndexFormat::{:?});",
+                buffer_slice.buffer.debug_id(),
+                buffer_slice.offset,
+                buffer_slice.offset + r.get(),
+                index_format
+            ),
+            None => log::trace!(
+                "render_bundle_encoder.set_index_buffer(buffer{}.slice({}..), IndexFormat::{:?});",
+                buffer_slice.buffer.debug_id(),
+                buffer_slice.offset,
+                index_format
+            ),
+        };
+
+        let binding = super::super::BufferBinding {
+            buffer: buffer_slice.buffer,
+            offset: buffer_slice.offset,
+            size: buffer_slice.size,
+        };
+
+        self.inner.set_index_buffer(binding, index_format)
+    }
+
+    /// Assign a vertex buffer to a slot.
+    ///
+    /// Subsequent calls to [`draw`] and [`draw_indexed`] on this
+    /// [`RenderBundleEncoder`] will use `buffer` as one of the source vertex buffers.
+    ///
+    /// The `slot` refers to the index of the matching descriptor in
+    /// [`VertexState::buffers`].
+    ///
+    /// [`draw`]: RenderBundleEncoder::draw
+    /// [`draw_indexed`]: RenderBundleEncoder::draw_indexed
+    pub fn set_vertex_buffer(&mut self, slot: u32, buffer_slice: BufferSlice<'a>) {
+        #[cfg(not(target_arch = "wasm32"))]
+        match buffer_slice.size {
+            Some(r) => log::trace!(
+                "render_bundle_encoder.set_vertex_buffer({}, buffer{}.slice({}..{}));",
+                slot,
+                buffer_slice.buffer.debug_id(),
+                buffer_slice.offset,
+                buffer_slice.offset + r.get()
+            ),
+            None => log::trace!(
+                "render_bundle_encoder.set_vertex_buffer({}, buffer{}.slice({}..));",
+                slot,
+                buffer_slice.buffer.debug_id(),
+                buffer_slice.offset
+            ),
+        };
+
+        let binding = super::super::BufferBinding {
+            buffer: buffer_slice.buffer,
+            offset: buffer_slice.offset,
+            size: buffer_slice.size,
+        };
+
+        self.inner.set_vertex_buffer(slot, binding)
+    }
+
+    /// Set push constant data.
+    ///
+    /// Offset is measured in bytes, but must be a multiple of [`wgt::PUSH_CONSTANT_ALIGNMENT`].
+    ///
+    /// Data size must be a multiple of 4 and must have an alignment of 4.
+    /// For example, with an offset of 4 and an array of `[u8; 8]`, that will write to the range
+    /// of 4..12.
+    ///
+    /// Requires [`wgt::Features::PUSH_CONSTANTS`] to be enabled.
+    pub fn set_push_constants(&mut self, stages: ShaderStages, offset: u32, data: &[u8]) {
+        log::trace!(
+            "render_bundle_encoder.set_push_constants(ShaderStages::{:?}, {}, &{:?});",
+            stages,
+            offset,
+            data
+        );
+
+        super::command::check_push_constants(offset, data);
+
+        self.inner.set_push_constants(stages, offset, data)
+    }
+
+    /// Draws primitives from the active vertex buffer(s).
+    ///
+    /// The active vertex buffers can be set with [`RenderBundleEncoder::set_vertex_buffer`].
+    pub fn draw(&mut self, vertices: Range<u32>, instances: Range<u32>) {
+        log::trace!("render_bundle_encoder.draw({:?}, {:?});", vertices, instances);
+        self.inner.draw(
+            vertices.start,
+            vertices.len() as u32,
+            instances.start,
+            instances.len() as u32,
+        )
+    }
+
+    /// Draws indexed primitives using the active index buffer and the active vertex buffers.
+    ///
+    /// The active index buffer can be set with [`RenderBundleEncoder::set_index_buffer`], while the active
+    /// vertex buffers can be set with [`RenderBundleEncoder::set_vertex_buffer`].
+    ///
+    /// Without [`DownlevelFlags::BASE_VERTEX`](crate::DownlevelFlags::BASE_VERTEX) a non-zero `base_vertex` is emulated by offsetting
+    /// the vertex attributes; a negative `base_vertex` that would move an attribute before the start
+    /// of its buffer skips the draw and logs an error.
+    pub fn draw_indexed(&mut self, indices: Range<u32>, base_vertex: i32, instances: Range<u32>) {
+        log::trace!("render_bundle_encoder.draw_indexed({indices:?}, {base_vertex:?}, {instances:?});");
+        self.inner.draw_indexed(
+            indices.start,
+            indices.len() as u32,
+            base_vertex,
+            instances.start,
+            instances.len() as u32,
+        )
+    }
+
+    /// Draws primitives from the active vertex buffer(s) based on the contents of the `indirect_buffer`.
+    ///
+    /// The active vertex buffers can be set with [`RenderBundleEncoder::set_vertex_buffer`].
+    ///
+    /// The structure expected in `indirect_buffer` must conform to [`DrawIndirectArgs`](crate::util::DrawIndirectArgs).
+    pub fn draw_indirect(&mut self, indirect_buffer: &'a Buffer, indirect_offset: BufferAddress) {
+        #[cfg(not(target_arch = "wasm32"))]
+        log::trace!(
+            "render_bundle_encoder.draw_indirect(&buffer{}, {});",
+            indirect_buffer.debug_id(),
+            indirect_offset
+        );
+
+        assert!(indirect_buffer.usage.contains(BufferUsages::INDIRECT));
+        assert!(indirect_offset % 4 == 0);
+        assert!(indirect_offset + 16 <= indirect_buffer.size);
+
+        let Some(inner) = indirect_buffer.inner.as_ref() else {
+            log::error!("render_bundle_encoder.draw_indirect: indirect buffer is invalid, skipped");
+            return;
+        };
+
+        self.inner.draw_indirect(inner, indirect_offset as i32)
+    }
+
+    /// Draws indexed primitives using the active index buffer and the active vertex buffers,
+    /// based on the contents of the `indirect_buffer`.
+    ///
+    /// The active index buffer can be set with [`RenderBundleEncoder::set_index_buffer`], while the active
+    /// vertex buffers can be set with [`RenderBundleEncoder::set_vertex_buffer`].
+    ///
+    /// The structure expected in `indirect_buffer` must conform to [`DrawIndexedIndirectArgs`](crate::util::DrawIndexedIndirectArgs).
+    pub fn draw_indexed_indirect(
+        &mut self,
+        indirect_buffer: &'a Buffer,
+        indirect_offset: BufferAddress,
+    ) {
+        #[cfg(not(target_arch = "wasm32"))]
+        log::trace!(
+            "render_bundle_encoder.draw_indexed_indirect(&buffer{}, {});",
+            indirect_buffer.debug_id(),
+            indirect_offset
+        );
+
+        assert!(indirect_buffer.usage.contains(BufferUsages::INDIRECT));
+        assert!(indirect_offset % 4 == 0);
+        assert!(indirect_offset + 20 <= indirect_buffer.size);
+
+        let Some(inner) = indirect_buffer.inner.as_ref() else {
+            log::error!("render_bundle_encoder.draw_indexed_indirect: indirect buffer is invalid, skipped");
+            return;
+        };
+
+        self.inner.draw_indexed_indirect(inner, indirect_offset as i32)
+    }
+}
+
+/// Describes a [`RenderBundleEncoder`].
+///
+/// For use with [`Device::create_render_bundle_encoder`].
+///
+/// Corresponds to [WebGPU `GPURenderBundleEncoderDescriptor`](
+/// https://gpuweb.github.io/gpuweb/#dictdef-gpurenderbundleencoderdescriptor).
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct RenderBundleEncoderDescriptor<'a> {
+    /// Debug label of the render bundle encoder. This will show up in graphics debuggers for easy identification.
+    pub label: Label<'a>,
+    /// The formats of the color attachments that this render bundle is capable to rendering to. This
+    /// must match the formats of the color attachments in the render pass this render bundle is executed in.
+    pub color_formats: &'a [Option<TextureFormat>],
+    /// Information about the depth attachment that this render bundle is capable to rendering to. This
+    /// must match the format of the depth attachments in the render pass this render bundle is executed in.
+    pub depth_stencil: Option<RenderBundleDepthStencil>,
+    /// Sample count this render bundle is capable of rendering to. This must match the pipelines and
+    /// the render passes it is used in.
+    pub sample_count: u32,
+    /// If this render bundle will rendering to multiple array layers in the attachments at the same time.
+    pub multiview: Option<NonZeroU32>,
+}
+
+/// Describes a [`RenderBundle`].
+///
+/// For use with [`RenderBundleEncoder::finish`].
+///
+/// Corresponds to [WebGPU `GPURenderBundleDescriptor`](
+/// https://gpuweb.github.io/gpuweb/#dictdef-gpurenderbundledescriptor).
+pub type RenderBundleDescriptor<'a> = wgt::RenderBundleDescriptor<Label<'a>>;
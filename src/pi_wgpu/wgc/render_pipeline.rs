@@ -15,14 +15,20 @@ use std::num::NonZeroU32;
 /// Corresponds to [WebGPU `GPURenderPipeline`](https://gpuweb.github.io/gpuweb/#render-pipeline).
 #[derive(Debug)]
 pub struct RenderPipeline {
-    pub(crate) inner: hal::RenderPipeline,
+    // 创建失败 时 为 None，设置到 渲染通道 后 绘制 会被 跳过
+    pub(crate) inner: Option<hal::RenderPipeline>,
 }
 
 impl RenderPipeline {
     #[inline]
-    pub(crate) fn from_hal(inner: super::super::hal::RenderPipeline) -> Self {
+    pub(crate) fn from_hal(inner: Option<super::super::hal::RenderPipeline>) -> Self {
         Self { inner }
     }
+
+    #[inline]
+    pub(crate) fn debug_id(&self) -> u64 {
+        self.inner.as_ref().map_or(0, |p| p.0.id as u64)
+    }
 }
 
 /// Describes a render (graphics) pipeline.
@@ -36,7 +42,7 @@ pub struct RenderPipelineDescriptor<'a> {
     /// Debug label of the pipeline. This will show up in graphics debuggers for easy identification.
     pub label: Label<'a>,
     /// The layout of bind groups for this pipeline.
-    #[debug("{}", match layout {Some(r) => format!("Some(&pipeline_layout{:?})", r.inner.id), None => "None".to_string()})]
+    #[debug("{}", match layout {Some(r) => format!("Some(&pipeline_layout{:?})", r.debug_id()), None => "None".to_string()})]
     pub layout: Option<&'a PipelineLayout>,
     /// The compiled vertex stage, its entry point, and the input buffers layout.
     pub vertex: VertexState<'a>,
@@ -61,7 +67,7 @@ pub struct RenderPipelineDescriptor<'a> {
 /// https://gpuweb.github.io/gpuweb/#dictdef-gpuvertexstate).
 #[derive(Clone, Debug)]
 pub struct VertexState<'a> {
-    #[debug("&shader_module{:?}", module.inner.id)]
+    #[debug("&shader_module{:?}", module.debug_id())]
     /// The compiled shader module for this stage.
     pub module: &'a ShaderModule,
     /// The name of the entry point in the compiled shader. There must be a function with this name
@@ -99,7 +105,7 @@ pub struct VertexBufferLayout<'a> {
 #[derive(Clone, Debug)]
 pub struct FragmentState<'a> {
     /// The compiled shader module for this stage.
-    #[debug("&shader_module{:?}", module.inner.id)]
+    #[debug("&shader_module{:?}", module.debug_id())]
     pub module: &'a ShaderModule,
     /// The name of the entry point in the compiled shader. There must be a function with this name
     /// in the shader.
@@ -11,12 +11,13 @@ use super::super::{AddressMode, CompareFunction, FilterMode, Label};
 /// Corresponds to [WebGPU `GPUSampler`](https://gpuweb.github.io/gpuweb/#sampler-interface).
 #[derive(Debug)]
 pub struct Sampler {
-    pub(crate) inner: super::super::hal::Sampler,
+    // 创建失败 时 为 None，用它 创建的 BindGroup 也是 无效的
+    pub(crate) inner: Option<super::super::hal::Sampler>,
 }
 
 impl Sampler {
     #[inline]
-    pub(crate) fn from_hal(inner: super::super::hal::Sampler) -> Self {
+    pub(crate) fn from_hal(inner: Option<super::super::hal::Sampler>) -> Self {
         Self { inner }
     }
 }
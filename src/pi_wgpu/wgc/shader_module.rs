@@ -15,14 +15,20 @@ use derive_more::Debug;
 /// Corresponds to [WebGPU `GPUShaderModule`](https://gpuweb.github.io/gpuweb/#shader-module).
 #[derive(Debug)]
 pub struct ShaderModule {
-    pub(crate) inner: super::super::hal::ShaderModule,
+    // 创建失败 时 为 None，用它 创建的 管线 也是 无效的
+    pub(crate) inner: Option<super::super::hal::ShaderModule>,
 }
 
 impl ShaderModule {
     #[inline]
-    pub(crate) fn from_hal(inner: super::super::hal::ShaderModule) -> Self {
+    pub(crate) fn from_hal(inner: Option<super::super::hal::ShaderModule>) -> Self {
         Self { inner }
     }
+
+    #[inline]
+    pub(crate) fn debug_id(&self) -> u64 {
+        self.inner.as_ref().map_or(0, |m| m.id)
+    }
 }
 
 /// Source of a shader module.
@@ -41,6 +47,16 @@ pub enum ShaderSource<'a> {
     #[debug("ShaderSource::Naga(Cow::from({_0:?}))")]
     Naga(Cow<'static, naga::Module>),
 
+    /// WGSL module as a string slice.
+    #[cfg(feature = "wgsl")]
+    #[debug("ShaderSource::Wgsl(Cow::from({_0:?}))")]
+    Wgsl(Cow<'a, str>),
+
+    /// SPIR-V module represented as a slice of words.
+    #[cfg(feature = "spirv")]
+    #[debug("ShaderSource::SpirV(Cow::from(&{_0:?}))")]
+    SpirV(Cow<'a, [u32]>),
+
     #[debug("Glsl {{shader: Cow::from({shader:?}), stage: naga::ShaderStage::{stage:?},  defines: naga::FastHashMap::default()}}")]
     Glsl {
         /// The source code of the shader.
@@ -3,6 +3,8 @@ use std::marker::PhantomData;
 use super::super::{hal, wgt, Adapter, Device, SurfaceCapabilities, Texture, TextureFormat};
 use raw_window_handle::{HasDisplayHandle, HasWindowHandle};
 
+use crate::PiWgpuSurface;
+
 #[non_exhaustive]
 pub enum SurfaceTarget<'window> {
     /// Window handle producer.
@@ -77,6 +79,12 @@ pub struct Surface<'w> {
     pub(crate) window: SurfaceTarget<'w>,
 }
 
+impl PiWgpuSurface for Surface<'_> {
+    fn set_direct_present(&self, enable: bool) {
+        self.inner.set_direct_present(enable);
+    }
+}
+
 impl std::fmt::Debug for Surface<'_> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("Surface").field("inner", &self.inner).finish()
@@ -105,6 +113,8 @@ impl<'w> Surface<'w> {
     ///
     /// - A old [`SurfaceTexture`] is still alive referencing an old surface.
     /// - Texture format requested is unsupported on the surface.
+    /// - Present mode requested is unsupported on the surface.
+    /// - Alpha mode requested is unsupported on the surface.
     #[inline]
     pub fn configure(&self, device: &Device, config: &SurfaceConfiguration) {
         // log::trace!("surface.configure(device, &{:?});", config);
@@ -1,7 +1,11 @@
+use parking_lot::Mutex;
+use pi_share::Share;
+
 use super::super::{
     hal, Extent3d, Label, TextureAspect, TextureDimension, TextureFormat, TextureUsages,
     TextureViewDimension,
 };
+use super::{ErrorSink, InvalidObject};
 
 /// Handle to a texture on the GPU.
 ///
@@ -10,7 +14,10 @@ use super::super::{
 /// Corresponds to [WebGPU `GPUTexture`](https://gpuweb.github.io/gpuweb/#texture-interface).
 #[derive(Debug)]
 pub struct Texture {
-    pub(crate) inner: hal::Texture,
+    // 创建失败 时 为 None，它的 视图 和 拷贝 也是 无效的
+    pub(crate) inner: Option<hal::Texture>,
+    // 创建 视图 的 错误 交给 创建 纹理 的 Device；表面纹理 没有，只 打日志
+    error_sink: Option<Share<Mutex<ErrorSink>>>,
 
     size_impl: Extent3d,
     mip_level_count_impl: u32,
@@ -33,7 +40,8 @@ impl Texture {
         };
 
         Self {
-            inner,
+            inner: Some(inner),
+            error_sink: None,
             size_impl,
             format_impl,
             sample_count_impl: 1,
@@ -45,11 +53,13 @@ impl Texture {
 
     #[inline]
     pub(crate) fn from_hal(
-        inner: super::super::hal::Texture,
+        inner: Option<super::super::hal::Texture>,
         desc: &super::super::TextureDescriptor,
+        error_sink: Share<Mutex<ErrorSink>>,
     ) -> Self {
         Self {
             inner,
+            error_sink: Some(error_sink),
             size_impl: desc.size,
             mip_level_count_impl: desc.mip_level_count,
             sample_count_impl: desc.sample_count,
@@ -64,13 +74,48 @@ impl Texture {
     /// Creates a view of this texture.
     #[inline]
     pub fn create_view(&self, desc: &TextureViewDescriptor) -> TextureView {
-        
+        let texture = match self.inner.as_ref() {
+            Some(texture) => texture,
+            None => {
+                self.handle_error(super::Error::validation(
+                    format!("In Texture::create_view, label = {:?}: texture is invalid", desc.label),
+                    InvalidObject("Texture"),
+                ));
+                return TextureView { inner: None };
+            }
+        };
+
+        let inner = match hal::TextureView::new(texture, desc) {
+            Ok(inner) => {
+                log::trace!("let texture_view{} = texture{}.create_view(&{:?});", inner.id, self.debug_str(), desc);
+                Some(inner)
+            }
+            Err(e) => {
+                self.handle_error(super::Error::from_device(
+                    format!("In Texture::create_view, label = {:?}: {}", desc.label, e),
+                    e,
+                ));
+                None
+            }
+        };
 
-        let inner = hal::TextureView::new(&self.inner, desc).unwrap();
-		log::trace!("let texture_view{} = texture{}.create_view(&{:?});", inner.id, self.inner.0.inner.debug_str(), desc);
         TextureView { inner }
     }
 
+    #[inline]
+    pub(crate) fn debug_str(&self) -> String {
+        self.inner
+            .as_ref()
+            .map_or("_invalid".to_string(), |t| t.0.inner.debug_str())
+    }
+
+    fn handle_error(&self, err: super::Error) {
+        match self.error_sink.as_ref() {
+            Some(sink) => super::handle_error(sink, err),
+            None => log::error!("{}", err),
+        }
+    }
+
     /// Make an `ImageCopyTexture` representing the whole texture.
     #[inline]
     pub fn as_image_copy(&self) -> ImageCopyTexture {
@@ -163,15 +208,14 @@ impl Texture {
 /// Corresponds to [WebGPU `GPUTextureView`](https://gpuweb.github.io/gpuweb/#gputextureview).
 #[derive(Debug)]
 pub struct TextureView {
-    pub(crate) inner: hal::TextureView,
+    // 创建失败 时 为 None，用它 的 BindGroup / 渲染通道 也是 无效的
+    pub(crate) inner: Option<hal::TextureView>,
 }
 
 impl TextureView {
-    // 返回 (w, h, d)
     #[inline]
-    pub(crate) fn get_size(&self) -> (u32, u32, u32) {
-        let size = &self.inner.inner.copy_size;
-        (size.width, size.height, size.depth)
+    pub(crate) fn debug_id(&self) -> u64 {
+        self.inner.as_ref().map_or(0, |v| v.id as u64)
     }
 }
 
@@ -185,7 +229,8 @@ impl TextureView {
 pub struct TextureViewDescriptor<'a> {
     /// Debug label of the texture view. This will show up in graphics debuggers for easy identification.
     pub label: Label<'a>,
-    /// Format of the texture view. At this time, it must be the same as the underlying format of the texture.
+    /// Format of the texture view. Either must be the same as the texture format or in the list
+    /// of `view_formats` in the texture's descriptor.
     pub format: Option<TextureFormat>,
     /// The dimension of the texture view. For 1D textures, this must be `D1`. For 2D textures it must be one of
     /// `D2`, `D2Array`, `Cube`, and `CubeArray`. For 3D textures it must be `D3`